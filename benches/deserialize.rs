@@ -0,0 +1,148 @@
+//! Deserializes a config struct with many fields of distinct types.
+//!
+//! Run with `cargo bench --bench deserialize`.
+//! Besides the run time, the bench is useful for tracking the compile time
+//! and the binary size of the code generated for the `EnvField`s.
+
+use std::{
+    env, hint,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    num::{NonZeroI64, NonZeroU32, NonZeroU64},
+    path::PathBuf,
+    time::Instant,
+};
+
+use serde::Deserialize;
+use serde_env_field::env_field_wrap;
+
+const ITERATIONS: u32 = 20_000;
+
+#[env_field_wrap]
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    field_00: u8,
+    field_01: u16,
+    field_02: u32,
+    field_03: u64,
+    field_04: u128,
+    field_05: i8,
+    field_06: i16,
+    field_07: i32,
+    field_08: i64,
+    field_09: i128,
+    field_10: usize,
+    field_11: isize,
+    field_12: f32,
+    field_13: f64,
+    field_14: bool,
+    field_15: char,
+    field_16: String,
+    field_17: PathBuf,
+    field_18: IpAddr,
+    field_19: Ipv4Addr,
+    field_20: Ipv6Addr,
+    field_21: SocketAddr,
+    field_22: NonZeroU32,
+    field_23: NonZeroU64,
+    field_24: NonZeroI64,
+    field_25: Option<u8>,
+    field_26: Option<u16>,
+    field_27: Option<u32>,
+    field_28: Option<u64>,
+    field_29: Option<u128>,
+    field_30: Option<i8>,
+    field_31: Option<i16>,
+    field_32: Option<i32>,
+    field_33: Option<i64>,
+    field_34: Option<i128>,
+    field_35: Option<usize>,
+    field_36: Option<isize>,
+    field_37: Option<f32>,
+    field_38: Option<f64>,
+    field_39: Option<bool>,
+    field_40: Option<char>,
+    field_41: Option<String>,
+    field_42: Option<PathBuf>,
+    field_43: Option<IpAddr>,
+    field_44: Option<Ipv4Addr>,
+    field_45: Option<Ipv6Addr>,
+    field_46: Option<SocketAddr>,
+    field_47: Option<NonZeroU32>,
+    field_48: Option<NonZeroU64>,
+    field_49: Option<NonZeroI64>,
+}
+
+const CONFIG: &str = r#"{
+    "field_00": "$BENCH_NUM",
+    "field_01": "$BENCH_NUM",
+    "field_02": "$BENCH_NUM",
+    "field_03": "$BENCH_NUM",
+    "field_04": "$BENCH_NUM",
+    "field_05": "-$BENCH_NUM",
+    "field_06": "-$BENCH_NUM",
+    "field_07": "-$BENCH_NUM",
+    "field_08": "-$BENCH_NUM",
+    "field_09": "-$BENCH_NUM",
+    "field_10": "$BENCH_NUM",
+    "field_11": "-$BENCH_NUM",
+    "field_12": "$BENCH_NUM.5",
+    "field_13": "$BENCH_NUM.25",
+    "field_14": "$BENCH_FLAG",
+    "field_15": "${BENCH_CHAR:-x}",
+    "field_16": "$BENCH_HOST",
+    "field_17": "$BENCH_HOME/config",
+    "field_18": "$BENCH_IP",
+    "field_19": "$BENCH_IP",
+    "field_20": "::1",
+    "field_21": "$BENCH_IP:$BENCH_NUM",
+    "field_22": "$BENCH_NUM",
+    "field_23": "$BENCH_NUM",
+    "field_24": "-$BENCH_NUM",
+    "field_25": "$BENCH_NUM",
+    "field_26": "$BENCH_NUM",
+    "field_27": "$BENCH_NUM",
+    "field_28": "$BENCH_NUM",
+    "field_29": "$BENCH_NUM",
+    "field_30": "-$BENCH_NUM",
+    "field_31": "-$BENCH_NUM",
+    "field_32": "-$BENCH_NUM",
+    "field_33": "-$BENCH_NUM",
+    "field_34": "-$BENCH_NUM",
+    "field_35": "$BENCH_NUM",
+    "field_36": "-$BENCH_NUM",
+    "field_37": "$BENCH_NUM.5",
+    "field_38": "$BENCH_NUM.25",
+    "field_39": "$BENCH_FLAG",
+    "field_40": "${BENCH_CHAR:-x}",
+    "field_41": "$BENCH_HOST",
+    "field_42": "$BENCH_HOME/config",
+    "field_43": "$BENCH_IP",
+    "field_44": "$BENCH_IP",
+    "field_45": "::1",
+    "field_46": "$BENCH_IP:$BENCH_NUM",
+    "field_47": "$BENCH_NUM",
+    "field_48": "$BENCH_NUM",
+    "field_49": "-$BENCH_NUM"
+}"#;
+
+fn main() {
+    env::set_var("BENCH_NUM", "42");
+    env::set_var("BENCH_FLAG", "true");
+    env::set_var("BENCH_HOST", "example.com");
+    env::set_var("BENCH_HOME", "/home/user");
+    env::set_var("BENCH_IP", "127.0.0.1");
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let config: Config = serde_json::from_str(hint::black_box(CONFIG)).unwrap();
+        hint::black_box(config);
+    }
+
+    let elapsed = start.elapsed();
+    println!(
+        "deserialize {} fields: {:?} per iteration",
+        CONFIG.lines().count() - 2,
+        elapsed / ITERATIONS
+    );
+}
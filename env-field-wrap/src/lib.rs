@@ -1,42 +1,437 @@
+mod shadow;
+
 use proc_macro::TokenStream;
-use proc_macro2::{Span, TokenStream as TokenStream2};
-use proc_macro_error::{abort, abort_call_site, proc_macro_error};
-use quote::{quote, ToTokens};
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use proc_macro_error::{abort, proc_macro_error};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
-    parse_macro_input, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput,
-    GenericArgument, PathArguments, PathSegment, Token,
+    ext::IdentExt, parse::Parser, parse_macro_input, punctuated::Punctuated, spanned::Spanned,
+    Data, DeriveInput, GenericArgument, PathArguments, Token,
 };
 
 #[proc_macro_attribute]
 #[proc_macro_error]
 pub fn env_field_wrap(params: TokenStream, input: TokenStream) -> TokenStream {
-    if !params.is_empty() {
-        abort_call_site!("The `env_field_wrap` doesn't take any parameters");
-    }
+    let container = ContainerAttrs::parse(params);
 
     let input = parse_macro_input!(input as DeriveInput);
+    container.check_fields(&input.data);
 
     let attrs = attrs_tokens(input.attrs);
+    let derive = (!container.derive.is_empty()).then(|| {
+        let derive = &container.derive;
+        quote!(#[derive(#(#derive),*)])
+    });
 
     let vis = input.vis;
     let ident = input.ident;
-    let generics = input.generics;
+    let mut generics = input.generics;
+
+    if !container.bound.is_empty() {
+        generics
+            .make_where_clause()
+            .predicates
+            .extend(container.bound.iter().cloned());
+    }
+
+    let where_clause = generics.where_clause.take();
+    let mut helpers = EnvHelpers::new("env_field_wrap", &ident, container.krate());
 
     let (item_tok, data_with_env_fields) = match input.data {
-        Data::Struct(data) => (quote![struct], wrap_fields(data.fields, WrapKind::Struct)),
-        Data::Enum(data) => (quote![enum], enum_env_field_wrap(data)),
-        Data::Union(data) => abort!(data.union_token, "unions are not supported"),
+        Data::Struct(data) => (
+            quote![struct],
+            wrap_fields(
+                data.fields,
+                WrapKind::Struct(where_clause.as_ref()),
+                &container,
+                &mut helpers,
+            ),
+        ),
+        Data::Enum(data) => (
+            quote![enum],
+            enum_env_field_wrap(data, where_clause.as_ref(), &container, &mut helpers),
+        ),
+        Data::Union(data) => abort!(
+            data.union_token,
+            "unions are not supported, wrap the union fields with the `EnvField` manually instead"
+        ),
     };
 
-    quote! {
-        #attrs
-        #vis
-        #item_tok
-        #ident
-        #generics
-        #data_with_env_fields
+    let item = shadow::shadowed(
+        quote! {
+            #attrs
+            #derive
+            #vis
+            #item_tok
+            #ident
+            #generics
+            #data_with_env_fields
+        },
+        &helpers,
+    );
+
+    let env_helpers = helpers.fns;
+    let output = quote! {
+        #item
+
+        #env_helpers
+    };
+
+    if std::env::var_os("SERDE_ENV_FIELD_DEBUG").is_some_and(|debug| debug == "1") {
+        eprintln!("env_field_wrap: {ident}\n{output}\n");
+    }
+
+    output.into()
+}
+
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn serde_env_field(params: TokenStream, input: TokenStream) -> TokenStream {
+    if let Some(param) = TokenStream2::from(params).into_iter().next() {
+        abort!(param, "`serde_env_field` takes no options");
+    }
+
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let mut helpers = EnvHelpers::new("serde_env_field", &input.ident, default_crate());
+
+    match &mut input.data {
+        Data::Struct(data) => {
+            let rename_all = serde_rename(&input.attrs, "rename_all");
+            expand_fields(
+                &mut data.fields,
+                FieldsKind::Struct(rename_all),
+                &mut helpers,
+            )
+        }
+        Data::Enum(data) => {
+            for variant in &mut data.variants {
+                if let Some(attr) = variant
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path().is_ident("serde_env_field"))
+                {
+                    abort!(
+                        attr,
+                        "`serde_env_field` options are supported only for fields, not for enum variants"
+                    );
+                }
+
+                expand_fields(&mut variant.fields, FieldsKind::Variant, &mut helpers);
+            }
+        }
+        Data::Union(data) => abort!(
+            data.union_token,
+            "unions are not supported, wrap the union fields with the `EnvField` manually instead"
+        ),
+    }
+
+    let item = shadow::shadowed(input.to_token_stream(), &helpers);
+    let env_helpers = helpers.fns;
+    let output = quote! {
+        #item
+
+        #env_helpers
+    };
+
+    if std::env::var_os("SERDE_ENV_FIELD_DEBUG").is_some_and(|debug| debug == "1") {
+        eprintln!("serde_env_field: {}\n{output}\n", input.ident);
+    }
+
+    output.into()
+}
+
+/// The owner of the fields processed by the `serde_env_field`.
+enum FieldsKind {
+    /// The fields of a struct with the `rename_all` rule of the struct.
+    Struct(Option<String>),
+    Variant,
+}
+
+/// Makes the fields expand the environment variables via the `#[serde(deserialize_with = "...")]`,
+/// keeping their types.
+fn expand_fields(fields: &mut syn::Fields, kind: FieldsKind, helpers: &mut EnvHelpers) {
+    for field in fields.iter_mut() {
+        let field_attrs = take_serde_env_field_attr(&mut field.attrs);
+        if field_attrs.skip {
+            continue;
+        }
+
+        if let Some(var_name) = &field_attrs.rename_env {
+            let FieldsKind::Struct(rename_all) = &kind else {
+                abort!(
+                    var_name,
+                    "`rename_env` is supported only for the struct fields"
+                );
+            };
+
+            let Some(ident) = &field.ident else {
+                abort!(var_name, "`rename_env` requires a named field");
+            };
+
+            let serde_name = serde_rename(&field.attrs, "rename").unwrap_or_else(|| {
+                apply_rename_rule(&ident.unraw().to_string(), rename_all.as_deref())
+            });
+
+            // `from_env` recognizes the alias and reads the variable instead of the derived one
+            let alias = format!("\0{}\0{serde_name}", var_name.value());
+            field
+                .attrs
+                .push(syn::parse_quote!(#[serde(alias = #alias)]));
+        }
+
+        let krate = helpers.krate.clone();
+        let wrapped = wrap_type(&field.ty, Expansion::Enabled, &krate);
+
+        // E.g., the `Option<EnvField<T>>` fields are already expanded
+        let is_wrapped = wrapped.to_string() != field.ty.to_token_stream().to_string();
+
+        let de_path = match &field_attrs.skip_if_empty {
+            Some(option) => Some(non_empty_de_path(field, option, &krate)),
+            None => (is_wrapped && !has_serde_option(&field.attrs, &["with", "deserialize_with"]))
+                .then(|| {
+                    format!(
+                        "{}::__private::deserialize_unwrapped::<_, {}>",
+                        krate.to_token_stream(),
+                        wrapped
+                    )
+                }),
+        };
+
+        let mut serde_args = vec![];
+        if let Some(de_path) = de_path {
+            serde_args.push(quote!(deserialize_with = #de_path));
+
+            // The `deserialize_with` disables the implicit `None` for the absent optional fields
+            if is_option(&field.ty)
+                && field_attrs.default.is_none()
+                && field_attrs.default_expand.is_none()
+                && !has_serde_option(&field.attrs, &["default"])
+            {
+                serde_args.push(quote!(default));
+            }
+        }
+
+        if let Some(template_fn) = &field_attrs.default_expand {
+            let default_path = if is_wrapped {
+                let default_fn = helpers.add_unwrapped_default(template_fn);
+                format!("{default_fn}::<{wrapped}>")
+            } else {
+                let private = quote!(#krate::__private);
+                helpers
+                    .add_default(quote!(#private::expand_default(#template_fn())))
+                    .to_string()
+            };
+
+            let deferred = shadow::deferred_attr(&default_path);
+            field.attrs.push(syn::parse_quote!(#deferred));
+        }
+
+        if let Some(template) = &field_attrs.default {
+            let default_fn = quote!(|| #krate::__private::template_default(#template));
+            let deferred = shadow::deferred_attr(&default_fn.to_string());
+            field.attrs.push(syn::parse_quote!(#deferred));
+        }
+
+        if !serde_args.is_empty() {
+            field
+                .attrs
+                .push(syn::parse_quote!(#[serde(#(#serde_args),*)]));
+        }
+    }
+}
+
+/// The `deserialize_with` path of the optional `skip_if_empty` field,
+/// which takes the strings expanded to nothing as `None`.
+fn non_empty_de_path(field: &syn::Field, option: &syn::Path, krate: &syn::Path) -> String {
+    let Some(inner) = option_arg(&field.ty) else {
+        abort!(option, "`skip_if_empty`: an optional type is expected");
+    };
+
+    if has_serde_option(&field.attrs, &["with", "deserialize_with"]) {
+        abort!(
+            option,
+            "`skip_if_empty` can't be combined with a custom `with` or `deserialize_with`"
+        );
+    }
+
+    let wrapped = wrap_type(inner, Expansion::Enabled, krate);
+
+    // E.g., the `Option<EnvField<T>>` fields are kept wrapped
+    let de_fn = if wrapped.to_string() != inner.to_token_stream().to_string() {
+        "deserialize_non_empty_unwrapped"
+    } else {
+        "deserialize_non_empty"
+    };
+
+    format!(
+        "{}::__private::{de_fn}::<_, {}>",
+        krate.to_token_stream(),
+        wrapped
+    )
+}
+
+#[derive(Default)]
+struct SerdeEnvFieldAttrs {
+    skip: bool,
+    rename_env: Option<syn::LitStr>,
+    default: Option<syn::Expr>,
+    default_expand: Option<syn::ExprPath>,
+    skip_if_empty: Option<syn::Path>,
+}
+
+/// Removes the `#[serde_env_field(...)]` attribute, returning its options.
+fn take_serde_env_field_attr(attrs: &mut Vec<syn::Attribute>) -> SerdeEnvFieldAttrs {
+    let Some(index) = attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("serde_env_field"))
+    else {
+        return SerdeEnvFieldAttrs::default();
+    };
+
+    let metas = attrs
+        .remove(index)
+        .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+        .unwrap_or_else(|err| abort!(err.span(), err));
+
+    let mut field_attrs = SerdeEnvFieldAttrs::default();
+    for meta in &metas {
+        match meta {
+            syn::Meta::Path(path) if path.is_ident("skip") => field_attrs.skip = true,
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("rename_env") => {
+                let var_name = lit_str(name_value);
+                if var_name.value().is_empty() || var_name.value().contains(['=', '\0']) {
+                    abort!(var_name, "`rename_env`: invalid variable name");
+                }
+
+                field_attrs.rename_env = Some(var_name.clone());
+            }
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("default") => {
+                field_attrs.default = Some(name_value.value.clone());
+            }
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("default_expand") => {
+                field_attrs.default_expand = Some(template_fn(name_value));
+            }
+            syn::Meta::Path(path) if path.is_ident("skip_if_empty") => {
+                field_attrs.skip_if_empty = Some(path.clone());
+            }
+            _ => abort!(
+                meta.path(),
+                "unknown `serde_env_field` option '{}', expected one of: skip, rename_env, default, default_expand, skip_if_empty",
+                meta.path().to_token_stream()
+            ),
+        }
+    }
+
+    if field_attrs.skip && field_attrs.rename_env.is_some() {
+        abort!(metas, "`skip` and `rename_env` can't be combined");
+    }
+
+    if field_attrs.skip && field_attrs.default.is_some() {
+        abort!(metas, "`skip` and `default` can't be combined");
+    }
+
+    if field_attrs.skip && field_attrs.default_expand.is_some() {
+        abort!(metas, "`skip` and `default_expand` can't be combined");
+    }
+
+    if field_attrs.default.is_some() && field_attrs.default_expand.is_some() {
+        abort!(metas, "`default` and `default_expand` can't be combined");
+    }
+
+    if field_attrs.skip && field_attrs.skip_if_empty.is_some() {
+        abort!(metas, "`skip` and `skip_if_empty` can't be combined");
+    }
+
+    field_attrs
+}
+
+/// Whether the field has any of the listed `#[serde(...)]` options.
+fn has_serde_option(attrs: &[syn::Attribute], names: &[&str]) -> bool {
+    let mut found = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        // The malformed serde attributes are reported by the serde derive
+        let _ = attr.parse_nested_meta(|meta| {
+            found |= names.iter().any(|name| meta.path.is_ident(name));
+
+            if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+
+            Ok(())
+        });
+    }
+
+    found
+}
+
+/// The deserialization name given by the `#[serde(rename = "...")]`-like option, if any.
+fn serde_rename(attrs: &[syn::Attribute], option: &str) -> Option<String> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        // The malformed serde attributes are reported by the serde derive
+        let _ = attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident(option) {
+                if meta.input.peek(Token![=]) {
+                    meta.value()?.parse::<syn::Expr>()?;
+                } else if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+                    meta.parse_nested_meta(|nested| {
+                        nested.value()?.parse::<syn::Expr>().map(drop)
+                    })?;
+                }
+
+                return Ok(());
+            }
+
+            if meta.input.peek(Token![=]) {
+                name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else {
+                meta.parse_nested_meta(|nested| {
+                    let value = nested.value()?.parse::<syn::LitStr>()?;
+                    if nested.path.is_ident("deserialize") {
+                        name = Some(value.value());
+                    }
+
+                    Ok(())
+                })?;
+            }
+
+            Ok(())
+        });
+    }
+
+    name
+}
+
+/// Applies the serde `rename_all` rule to the field name.
+fn apply_rename_rule(field: &str, rule: Option<&str>) -> String {
+    let pascal_case = || {
+        field
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect::<String>()
+    };
+
+    match rule {
+        Some("UPPERCASE" | "SCREAMING_SNAKE_CASE") => field.to_ascii_uppercase(),
+        Some("PascalCase") => pascal_case(),
+        Some("camelCase") => {
+            let pascal = pascal_case();
+            let mut chars = pascal.chars();
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        Some("kebab-case") => field.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => field.to_ascii_uppercase().replace('_', "-"),
+        _ => field.to_owned(),
     }
-    .into()
 }
 
 fn attrs_tokens(attrs: Vec<syn::Attribute>) -> TokenStream2 {
@@ -48,32 +443,534 @@ fn attrs_tokens(attrs: Vec<syn::Attribute>) -> TokenStream2 {
     attrs_tokens
 }
 
+#[derive(Default)]
+struct ContainerAttrs {
+    only: Option<Vec<syn::Ident>>,
+    bound: Vec<syn::WherePredicate>,
+    serialize_with_env: bool,
+    derive: Vec<syn::Path>,
+    krate: Option<syn::Path>,
+    default: bool,
+}
+
+impl ContainerAttrs {
+    fn parse(params: TokenStream) -> Self {
+        let metas = match Punctuated::<syn::Meta, Token![,]>::parse_terminated.parse(params) {
+            Ok(metas) => metas,
+            Err(err) => abort!(err.span(), err),
+        };
+
+        let mut container = Self::default();
+        for meta in metas {
+            match &meta {
+                syn::Meta::List(list) if list.path.is_ident("only") => {
+                    let idents = list
+                        .parse_args_with(Punctuated::<syn::Ident, Token![,]>::parse_terminated)
+                        .unwrap_or_else(|err| abort!(err.span(), err));
+
+                    container.only = Some(idents.into_iter().collect());
+                }
+                syn::Meta::NameValue(name_value) if name_value.path.is_ident("bound") => {
+                    let bound = lit_str(name_value);
+
+                    let predicates = bound
+                        .parse_with(Punctuated::<syn::WherePredicate, Token![,]>::parse_terminated)
+                        .unwrap_or_else(|err| abort!(bound, "`bound`: {}", err));
+
+                    container.bound.extend(predicates);
+                }
+                syn::Meta::List(list) if list.path.is_ident("derive") => {
+                    let paths = list
+                        .parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                        .unwrap_or_else(|err| abort!(err.span(), err));
+
+                    container.derive.extend(paths);
+                }
+                syn::Meta::Path(path) if path.is_ident("serialize_with_env") => {
+                    container.serialize_with_env = true;
+                }
+                syn::Meta::Path(path) if path.is_ident("default") => {
+                    container.default = true;
+                }
+                syn::Meta::NameValue(name_value) if name_value.path.is_ident("crate") => {
+                    let krate = lit_str(name_value);
+                    container.krate = Some(
+                        krate
+                            .parse()
+                            .unwrap_or_else(|err| abort!(krate, "`crate`: {}", err)),
+                    );
+                }
+                _ => unknown_option(&meta, CONTAINER_OPTIONS),
+            }
+        }
+
+        container
+    }
+
+    fn check_fields(&self, data: &Data) {
+        let Some(only) = &self.only else {
+            return;
+        };
+
+        let fields = match data {
+            Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+            Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+            Data::Union(_) => return,
+        };
+
+        for ident in only {
+            if !fields
+                .iter()
+                .any(|field| field.ident.as_ref() == Some(ident))
+            {
+                abort!(ident, "`only`: no field named `{}`", ident);
+            }
+        }
+    }
+
+    /// The path of the `serde_env_field` crate used by the generated code.
+    fn krate(&self) -> syn::Path {
+        self.krate.clone().unwrap_or_else(default_crate)
+    }
+
+    /// The expansion of the wrapped fields.
+    fn expansion(&self) -> Expansion {
+        if self.serialize_with_env {
+            Expansion::Deferred
+        } else {
+            Expansion::Enabled
+        }
+    }
+
+    fn wraps(&self, field: &syn::Field) -> bool {
+        match (&self.only, &field.ident) {
+            (None, _) => true,
+            (Some(only), Some(ident)) => only.contains(ident),
+            (Some(_), None) => false,
+        }
+    }
+}
+
 enum WrapAttr {
     Skip,
+    SkipExpand(Span),
     GenericsOnly(Span),
+    ExpandKeys(Span),
+    Into(Box<syn::Type>),
+}
+
+impl WrapAttr {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::SkipExpand(_) => "skip_expand",
+            Self::GenericsOnly(_) => "generics_only",
+            Self::ExpandKeys(_) => "expand_keys",
+            Self::Into(_) => "into",
+        }
+    }
+}
+
+struct EnvAttr {
+    var_name: syn::LitStr,
+    precedence: syn::Ident,
+}
+
+#[derive(Default)]
+struct TrimAttr {
+    whitespace: bool,
+    matches: String,
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    wrap: Option<WrapAttr>,
+    env: Option<EnvAttr>,
+    trim: Option<TrimAttr>,
+    default_expand: Option<syn::ExprPath>,
+    use_deserialize: Option<Span>,
+}
+
+fn take_env_field_wrap_attr(attrs: &mut Vec<syn::Attribute>) -> FieldAttrs {
+    let Some(index) = attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("env_field_wrap"))
+    else {
+        return FieldAttrs::default();
+    };
+
+    let list = match attrs.remove(index).meta {
+        syn::Meta::List(list) => list,
+        meta => abort!(meta, "expected `#[env_field_wrap(...)]`"),
+    };
+
+    let metas = list
+        .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+        .unwrap_or_else(|err| abort!(err.span(), err));
+
+    let mut field_attrs = FieldAttrs::default();
+    let mut var_name = None;
+    let mut precedence = None;
+    let mut seen = Vec::new();
+
+    for meta in &metas {
+        let name = meta.path().to_token_stream().to_string();
+        if seen.contains(&name) {
+            abort!(meta.path(), "duplicate `{}` option", name);
+        }
+        seen.push(name);
+
+        match meta {
+            syn::Meta::Path(path)
+                if path.is_ident("skip")
+                    || path.is_ident("skip_expand")
+                    || path.is_ident("generics_only")
+                    || path.is_ident("expand_keys") =>
+            {
+                if let Some(wrap) = &field_attrs.wrap {
+                    abort!(
+                        path,
+                        "`{}` and `{}` can't be combined",
+                        wrap.name(),
+                        path.to_token_stream()
+                    );
+                }
+
+                field_attrs.wrap = Some(if path.is_ident("skip") {
+                    WrapAttr::Skip
+                } else if path.is_ident("skip_expand") {
+                    WrapAttr::SkipExpand(list.span())
+                } else if path.is_ident("generics_only") {
+                    WrapAttr::GenericsOnly(list.span())
+                } else {
+                    WrapAttr::ExpandKeys(path.span())
+                });
+            }
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("into") => {
+                if let Some(wrap) = &field_attrs.wrap {
+                    abort!(
+                        name_value.path,
+                        "`{}` and `into` can't be combined",
+                        wrap.name()
+                    );
+                }
+
+                field_attrs.wrap = Some(WrapAttr::Into(Box::new(target_type(name_value))));
+            }
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("env") => {
+                var_name = Some(lit_str(name_value).clone());
+            }
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("precedence") => {
+                precedence = Some(lit_str(name_value).clone());
+            }
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("default_expand") => {
+                field_attrs.default_expand = Some(template_fn(name_value));
+            }
+            syn::Meta::Path(path) if path.is_ident("use_deserialize") => {
+                field_attrs.use_deserialize = Some(path.span());
+            }
+            syn::Meta::Path(path) if path.is_ident("trim") => {
+                field_attrs
+                    .trim
+                    .get_or_insert_with(TrimAttr::default)
+                    .whitespace = true;
+            }
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("trim_matches") => {
+                field_attrs
+                    .trim
+                    .get_or_insert_with(TrimAttr::default)
+                    .matches = lit_str(name_value).value();
+            }
+            _ => unknown_option(meta, FIELD_OPTIONS),
+        }
+    }
+
+    if let Some(span) = field_attrs.use_deserialize {
+        if let Some(wrap @ (WrapAttr::Skip | WrapAttr::SkipExpand(_) | WrapAttr::Into(_))) =
+            &field_attrs.wrap
+        {
+            abort!(
+                span,
+                "`{}` and `use_deserialize` can't be combined",
+                wrap.name()
+            );
+        }
+    }
+
+    if let Some(default_expand) = &field_attrs.default_expand {
+        match &field_attrs.wrap {
+            Some(wrap @ (WrapAttr::Skip | WrapAttr::SkipExpand(_) | WrapAttr::Into(_))) => abort!(
+                default_expand,
+                "`{}` and `default_expand` can't be combined",
+                wrap.name()
+            ),
+            _ if var_name.is_some() => {
+                abort!(
+                    default_expand,
+                    "`default_expand` can't be combined with `env`"
+                )
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(WrapAttr::Into(_)) = &field_attrs.wrap {
+        if let Some(option) = var_name.as_ref().or(precedence.as_ref()) {
+            abort!(option, "`into` can't be combined with `env`");
+        }
+
+        if field_attrs.trim.is_some() {
+            abort!(list, "`into` can't be combined with `trim`");
+        }
+    }
+
+    field_attrs.env = match (var_name, precedence) {
+        (Some(var_name), precedence) => {
+            let precedence = match precedence.as_ref().map(syn::LitStr::value).as_deref() {
+                None | Some("env") => "Env",
+                Some("document") => "Document",
+                Some(_) => abort!(
+                    precedence,
+                    "`precedence`: expected either \"env\" or \"document\""
+                ),
+            };
+
+            Some(EnvAttr {
+                var_name,
+                precedence: syn::Ident::new(precedence, Span::call_site()),
+            })
+        }
+        (None, Some(precedence)) => abort!(precedence, "`precedence` requires `env`"),
+        (None, None) => None,
+    };
+
+    field_attrs
+}
+
+const CONTAINER_OPTIONS: &[&str] = &[
+    "only",
+    "bound",
+    "serialize_with_env",
+    "derive",
+    "crate",
+    "default",
+];
+const FIELD_OPTIONS: &[&str] = &[
+    "skip",
+    "skip_expand",
+    "generics_only",
+    "into",
+    "env",
+    "precedence",
+    "trim",
+    "trim_matches",
+    "default_expand",
+    "use_deserialize",
+    "expand_keys",
+];
+
+fn default_crate() -> syn::Path {
+    syn::parse_quote!(::serde_env_field)
 }
 
-fn take_env_field_wrap_attr(attrs: &mut Vec<syn::Attribute>) -> Option<WrapAttr> {
-    let mut index = 0;
-    let wrap_attr = attrs.iter().find_map(|attr| match &attr.meta {
-        syn::Meta::List(list) => list.path.get_ident().and_then(|ident| {
-            (ident == "env_field_wrap").then_some((list.span(), list.tokens.to_string()))
+fn unknown_option(meta: &syn::Meta, valid: &[&str]) -> ! {
+    let name = meta.path().to_token_stream().to_string();
+    if valid.contains(&name.as_str()) {
+        abort!(meta, "malformed `{}` option", name);
+    }
+
+    abort!(
+        meta.path(),
+        "unknown `env_field_wrap` option '{}', expected one of: {}",
+        name,
+        valid.join(", ")
+    )
+}
+
+/// Parses the `into` target type, given either as a path or as a string literal.
+fn target_type(name_value: &syn::MetaNameValue) -> syn::Type {
+    match &name_value.value {
+        syn::Expr::Path(syn::ExprPath {
+            qself: None, path, ..
+        }) => syn::Type::Path(syn::TypePath {
+            qself: None,
+            path: path.clone(),
         }),
-        _ => {
-            index += 1;
-            None
+        _ => lit_str(name_value)
+            .parse()
+            .unwrap_or_else(|err| abort!(err.span(), "`into`: {}", err)),
+    }
+}
+
+/// Parses the path of the function returning the default template.
+fn template_fn(name_value: &syn::MetaNameValue) -> syn::ExprPath {
+    lit_str(name_value)
+        .parse()
+        .unwrap_or_else(|err| abort!(err.span(), "`default_expand`: {}", err))
+}
+
+fn lit_str(name_value: &syn::MetaNameValue) -> &syn::LitStr {
+    match &name_value.value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) => lit,
+        value => abort!(
+            value,
+            "`{}`: a string literal is expected",
+            name_value.path.to_token_stream()
+        ),
+    }
+}
+
+/// The generated functions implementing the field options via the serde attributes.
+struct EnvHelpers {
+    prefix: &'static str,
+    item: syn::Ident,
+    krate: syn::Path,
+    fns: TokenStream2,
+    count: usize,
+}
+
+impl EnvHelpers {
+    fn new(prefix: &'static str, item: &syn::Ident, krate: syn::Path) -> Self {
+        Self {
+            prefix,
+            item: item.clone(),
+            krate,
+            fns: TokenStream2::new(),
+            count: 0,
         }
-    });
+    }
+
+    fn next_fn(&mut self) -> syn::Ident {
+        let ident = format_ident!("__{}_{}_{}", self.prefix, self.item, self.count);
+        self.count += 1;
 
-    if wrap_attr.is_some() {
-        attrs.remove(index);
+        ident
     }
 
-    wrap_attr.and_then(|(span, wrap_attr)| match wrap_attr.as_str() {
-        "skip" => Some(WrapAttr::Skip),
-        "generics_only" => Some(WrapAttr::GenericsOnly(span)),
-        _ => None,
-    })
+    /// Generates the helper functions for the field options
+    /// and returns the serde attribute using them.
+    fn add(&mut self, field_attrs: &FieldAttrs) -> Option<TokenStream2> {
+        let krate = &self.krate;
+        let private = quote!(#krate::__private);
+        let with_trim = |body: TokenStream2| match &field_attrs.trim {
+            Some(TrimAttr {
+                whitespace,
+                matches,
+            }) => quote! {
+                #private::with_trim(
+                    #private::Trim {
+                        whitespace: #whitespace,
+                        matches: #matches,
+                    },
+                    || #body,
+                )
+            },
+            None => body,
+        };
+
+        let mut serde_args = vec![];
+        if field_attrs.env.is_some() || field_attrs.trim.is_some() {
+            let de_fn = self.next_fn();
+            let de_body = with_trim(match &field_attrs.env {
+                Some(EnvAttr {
+                    var_name,
+                    precedence,
+                }) => quote! {
+                    #private::deserialize_with_env(
+                        deserializer,
+                        #var_name,
+                        #private::Precedence::#precedence,
+                    )
+                },
+                None => quote!(#private::Deserialize::deserialize(deserializer)),
+            });
+
+            self.fns.extend(quote! {
+                #[doc(hidden)]
+                #[allow(non_snake_case, dead_code)]
+                fn #de_fn<'de, D, F>(deserializer: D) -> ::core::result::Result<F, D::Error>
+                where
+                    D: #private::Deserializer<'de>,
+                    F: #private::Deserialize<'de>,
+                {
+                    #de_body
+                }
+            });
+
+            let de_path = de_fn.to_string();
+            serde_args.push(quote!(deserialize_with = #de_path));
+        }
+
+        let deferred = if let Some(EnvAttr { var_name, .. }) = &field_attrs.env {
+            let krate = self.krate.clone();
+            let default_fn = self.next_fn();
+            let default_body = with_trim(quote!(#private::env_default(#var_name)));
+            self.fns.extend(quote! {
+                #[doc(hidden)]
+                #[allow(non_snake_case, dead_code)]
+                fn #default_fn<F>() -> ::core::result::Result<F, #krate::EnvFieldError>
+                where
+                    F: #private::DeserializeOwned + ::core::default::Default,
+                {
+                    #default_body
+                }
+            });
+
+            Some(shadow::deferred_attr(&default_fn.to_string()))
+        } else if let Some(template_fn) = &field_attrs.default_expand {
+            let default_fn =
+                self.add_default(with_trim(quote!(#private::expand_default(#template_fn()))));
+            Some(shadow::deferred_attr(&default_fn.to_string()))
+        } else {
+            None
+        };
+
+        let serde_attr = (!serde_args.is_empty()).then(|| quote!(#[serde(#(#serde_args),*)]));
+        (serde_attr.is_some() || deferred.is_some()).then(|| quote!(#serde_attr #deferred))
+    }
+
+    /// Generates the default function constructing the field from the expanded template.
+    fn add_default(&mut self, default_body: TokenStream2) -> syn::Ident {
+        let krate = self.krate.clone();
+        let private = quote!(#krate::__private);
+        let default_fn = self.next_fn();
+        self.fns.extend(quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case, dead_code)]
+            fn #default_fn<F>() -> ::core::result::Result<F, #krate::EnvFieldError>
+            where
+                F: #private::DeserializeOwned,
+            {
+                #default_body
+            }
+        });
+
+        default_fn
+    }
+
+    /// Same as the `add_default`, but the function is parameterized by the wrapped field type
+    /// and returns the unwrapped one, see the `serde_env_field`.
+    fn add_unwrapped_default(&mut self, template_fn: &syn::ExprPath) -> syn::Ident {
+        let krate = self.krate.clone();
+        let private = quote!(#krate::__private);
+        let default_fn = self.next_fn();
+        self.fns.extend(quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case, dead_code)]
+            fn #default_fn<W>() -> ::core::result::Result<W::Unwrapped, #krate::EnvFieldError>
+            where
+                W: #private::DeserializeOwned + #private::UnwrapEnvFields,
+            {
+                #private::expand_default::<W>(#template_fn())
+                    .map(#private::UnwrapEnvFields::unwrap_env_fields)
+            }
+        });
+
+        default_fn
+    }
 }
 
 fn is_type(ty: &syn::Type, ty_paths: &[&str]) -> bool {
@@ -90,7 +987,7 @@ fn is_type(ty: &syn::Type, ty_paths: &[&str]) -> bool {
             // Remove the last `::`
             let path_ty_str = &path_ty_str[..path_ty_str.len() - 2];
 
-            ty_paths.iter().any(|ty| *ty == path_ty_str)
+            ty_paths.contains(&path_ty_str)
         }
         _ => false,
     }
@@ -103,15 +1000,150 @@ fn is_option(ty: &syn::Type) -> bool {
     )
 }
 
+/// The `T` of the `Option<T>` type.
+fn option_arg(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(ty_path) = ty else {
+        return None;
+    };
+
+    if !is_option(ty) {
+        return None;
+    }
+
+    match &ty_path.path.segments.last()?.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
 fn is_vec(ty: &syn::Type) -> bool {
     is_type(ty, &["Vec", "std::vec::Vec", "alloc::vec::Vec"])
 }
 
-fn is_env_field(ty: &syn::Type) -> bool {
-    is_type(ty, &["EnvField", "serde_env_field::EnvField"])
+fn is_result(ty: &syn::Type) -> bool {
+    is_type(
+        ty,
+        &["Result", "std::result::Result", "core::result::Result"],
+    )
+}
+
+fn is_index_map(ty: &syn::Type) -> bool {
+    cfg!(feature = "indexmap")
+        && is_type(
+            ty,
+            &["IndexMap", "indexmap::IndexMap", "indexmap::map::IndexMap"],
+        )
+}
+
+fn is_map(ty: &syn::Type) -> bool {
+    is_index_map(ty)
+        || is_type(
+            ty,
+            &[
+                "HashMap",
+                "std::collections::HashMap",
+                "std::collections::hash_map::HashMap",
+                "BTreeMap",
+                "std::collections::BTreeMap",
+                "std::collections::btree_map::BTreeMap",
+                "alloc::collections::BTreeMap",
+                "alloc::collections::btree_map::BTreeMap",
+            ],
+        )
+}
+
+fn is_env_field(ty: &syn::Type, krate: &syn::Path) -> bool {
+    let krate = krate
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+
+    is_type(
+        ty,
+        &[
+            "EnvField",
+            "serde_env_field::EnvField",
+            &format!("{krate}::EnvField"),
+            "EnvTemplate",
+            "serde_env_field::EnvTemplate",
+            &format!("{krate}::EnvTemplate"),
+        ],
+    )
+}
+
+/// Whether the wrapped types expand the environment variables.
+#[derive(Clone, Copy)]
+enum Expansion {
+    Enabled,
+    /// The `#[env_field_wrap(use_deserialize)]` fields,
+    /// constructed via the `UseDeserialize`.
+    EnabledDeserialize,
+    /// The `#[env_field_wrap(skip_expand)]` fields.
+    Disabled,
+    /// The `#[env_field_wrap(serialize_with_env)]` containers,
+    /// whose fields preserve the templates via the `EnvTemplate`.
+    Deferred,
+    /// The `#[env_field_wrap(use_deserialize)]` fields of the `serialize_with_env` containers.
+    DeferredDeserialize,
+}
+
+impl Expansion {
+    fn use_deserialize(self) -> Self {
+        match self {
+            Self::Enabled => Self::EnabledDeserialize,
+            Self::Deferred => Self::DeferredDeserialize,
+            expansion => expansion,
+        }
+    }
+
+    /// Whether the wrapped types are constructed via the `FromStr`.
+    fn uses_from_str(self) -> bool {
+        matches!(self, Self::Enabled | Self::Disabled | Self::Deferred)
+    }
+}
+
+fn wrap_generics_only(ty: &syn::Type, expansion: Expansion, krate: &syn::Path) -> TokenStream2 {
+    wrap_generic_args(ty, expansion, krate, |_| true)
+}
+
+fn wrap_ok_value(ty: &syn::Type, expansion: Expansion, krate: &syn::Path) -> TokenStream2 {
+    // Only the `T` in the `Result<T, E>` is wrapped
+    wrap_generic_args(ty, expansion, krate, |arg_index| arg_index == 0)
+}
+
+fn wrap_map_values(ty: &syn::Type, expansion: Expansion, krate: &syn::Path) -> TokenStream2 {
+    // Only the `V` in the `Map<K, V, ...>` is wrapped
+    wrap_generic_args(ty, expansion, krate, |arg_index| arg_index == 1)
+}
+
+fn wrap_map_entries(
+    ty: &syn::Type,
+    expansion: Expansion,
+    krate: &syn::Path,
+    span: Span,
+) -> TokenStream2 {
+    if !is_map(ty) {
+        abort!(
+            span,
+            "`expand_keys`: a map type is expected (`HashMap`, `BTreeMap`, or `IndexMap`)"
+        );
+    }
+
+    // Both the `K` and the `V` in the `Map<K, V, ...>` are wrapped
+    wrap_generic_args(ty, expansion, krate, |arg_index| arg_index < 2)
 }
 
-fn wrap_generics_only(ty: &syn::Type) -> TokenStream2 {
+fn wrap_generic_args(
+    ty: &syn::Type,
+    expansion: Expansion,
+    krate: &syn::Path,
+    wraps_arg: impl Fn(usize) -> bool,
+) -> TokenStream2 {
     match ty {
         syn::Type::Path(ty) => {
             if let Some(qself) = &ty.qself {
@@ -123,23 +1155,43 @@ fn wrap_generics_only(ty: &syn::Type) -> TokenStream2 {
 
             let path = &ty.path;
 
-            let segments = path.segments.iter();
-            let mut leading_segments = Punctuated::<PathSegment, Token![::]>::new();
-            let mut ty_with_generics = None;
+            if let Some(segment) = path
+                .segments
+                .iter()
+                .find(|segment| matches!(segment.arguments, PathArguments::Parenthesized(_)))
+            {
+                abort!(
+                    segment.arguments,
+                    "generics_only: unexpected type arguments"
+                );
+            }
+
+            // The generics may be on a non-final segment, e.g., `a::B<T>::C`,
+            // only the last segment with generics is wrapped
+            let Some(generics_idx) = path
+                .segments
+                .iter()
+                .rposition(|segment| !segment.arguments.is_none())
+            else {
+                abort!(ty, "generics_only: no generics found");
+            };
 
-            for segment in segments {
-                match &segment.arguments {
-                    PathArguments::None => leading_segments.push(segment.clone()),
-                    PathArguments::AngleBracketed(angle_args) => {
+            let segments = path
+                .segments
+                .iter()
+                .enumerate()
+                .map(|(segment_idx, segment)| match &segment.arguments {
+                    PathArguments::AngleBracketed(angle_args) if segment_idx == generics_idx => {
                         let wrapped_generics = angle_args
                             .args
                             .iter()
-                            .map(|arg| match arg {
-                                GenericArgument::Type(generic) => {
-                                    if is_env_field(generic) {
+                            .enumerate()
+                            .map(|(arg_index, arg)| match arg {
+                                GenericArgument::Type(generic) if wraps_arg(arg_index) => {
+                                    if is_env_field(generic, krate) {
                                         quote!(#generic)
                                     } else {
-                                        quote!(::serde_env_field::EnvField<#generic>)
+                                        env_field_type(generic, expansion, krate)
                                     }
                                 }
                                 non_ty_generic => quote!(#non_ty_generic),
@@ -147,54 +1199,156 @@ fn wrap_generics_only(ty: &syn::Type) -> TokenStream2 {
                             .collect::<Punctuated<_, Token![,]>>();
 
                         let ident = &segment.ident;
-                        ty_with_generics = Some(quote!(#ident < #wrapped_generics >));
+                        quote!(#ident < #wrapped_generics >)
                     }
-                    _ => abort!(
-                        segment.arguments,
-                        "generics_only: unexpected type arguments"
-                    ),
-                }
-            }
+                    _ => quote!(#segment),
+                })
+                .collect::<Punctuated<_, Token![::]>>();
 
-            if ty_with_generics.is_none() {
-                abort!(ty, "generics_only: no generics found");
+            let leading_colon = path.leading_colon;
+            quote! {
+                #leading_colon #segments
             }
+        }
+        _ => abort!(ty, "generics_only: a type with generic(s) is expected"),
+    }
+}
 
-            leading_segments.pop_punct();
+fn wrap_type(ty: &syn::Type, expansion: Expansion, krate: &syn::Path) -> TokenStream2 {
+    match ty {
+        syn::Type::Tuple(tuple) => {
+            let elems = tuple
+                .elems
+                .iter()
+                .map(|elem| wrap_type(elem, expansion, krate))
+                .collect::<Punctuated<_, Token![,]>>();
 
-            let leading_colon = path.leading_colon;
-            let ty_path = if leading_segments.is_empty() {
-                quote!(#ty_with_generics)
-            } else {
-                quote!(#leading_segments :: #ty_with_generics)
-            };
+            // A single-element tuple requires the trailing comma
+            let trailing_comma = (elems.len() == 1).then(|| quote!(,));
 
-            quote! {
-                #leading_colon #ty_path
+            quote!((#elems #trailing_comma))
+        }
+        ty if option_arg(ty).is_some_and(is_option) => wrap_nested_option(ty, expansion, krate),
+        ty if is_option(ty) || is_vec(ty) => wrap_generics_only(ty, expansion, krate),
+        ty if is_result(ty) => wrap_ok_value(ty, expansion, krate),
+        ty if is_index_map(ty) => wrap_map_values(ty, expansion, krate),
+        ty if is_env_field(ty, krate) => quote!(#ty),
+        ty => env_field_type(ty, expansion, krate),
+    }
+}
+
+/// Wraps only the innermost `T` of the nested options, e.g., the `Option<Option<T>>`.
+fn wrap_nested_option(ty: &syn::Type, expansion: Expansion, krate: &syn::Path) -> TokenStream2 {
+    let mut ty = ty.clone();
+    if let syn::Type::Path(ty_path) = &mut ty {
+        if let Some(PathArguments::AngleBracketed(args)) = ty_path
+            .path
+            .segments
+            .last_mut()
+            .map(|segment| &mut segment.arguments)
+        {
+            for arg in &mut args.args {
+                if let GenericArgument::Type(inner) = arg {
+                    let wrapped = wrap_type(inner, expansion, krate);
+                    *inner = syn::parse2(wrapped).unwrap_or_else(|err| abort!(inner, err));
+                }
             }
         }
-        _ => abort!(ty, "generics_only: a type with generic(s) is expected"),
     }
+
+    quote!(#ty)
 }
 
-fn process_fields(fields: impl Iterator<Item = syn::Field>) -> TokenStream2 {
+/// Whether the tokens refer to the `Self` type.
+fn mentions_self(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "Self",
+        TokenTree::Group(group) => mentions_self(group.stream()),
+        _ => false,
+    })
+}
+
+/// Wraps the type with the `EnvField`, unless it refers to the `Self`
+/// and the `EnvField` would require it to be `FromStr`.
+///
+/// The span of the type is kept, so the errors point to the original field type.
+fn env_field_type(ty: &syn::Type, expansion: Expansion, krate: &syn::Path) -> TokenStream2 {
+    // E.g., the `Box<Self>` of a recursive type can't be `FromStr`,
+    // while its own fields are already wrapped
+    if expansion.uses_from_str() && mentions_self(ty.to_token_stream()) {
+        return quote!(#ty);
+    }
+
+    let krate = krate
+        .to_token_stream()
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(ty.span());
+            token
+        })
+        .collect::<TokenStream2>();
+
+    match expansion {
+        Expansion::Enabled => quote_spanned!(ty.span()=> #krate::EnvField<#ty>),
+        Expansion::EnabledDeserialize => quote_spanned! {ty.span()=>
+            #krate::EnvField<#ty, #krate::UseDeserialize>
+        },
+        Expansion::Disabled => quote_spanned! {ty.span()=>
+            #krate::EnvField<#ty, #krate::NoExpand>
+        },
+        Expansion::Deferred => quote_spanned!(ty.span()=> #krate::EnvTemplate<#ty>),
+        Expansion::DeferredDeserialize => quote_spanned! {ty.span()=>
+            #krate::EnvTemplate<#ty, #krate::UseDeserialize>
+        },
+    }
+}
+
+fn process_fields(
+    fields: impl Iterator<Item = syn::Field>,
+    container: &ContainerAttrs,
+    helpers: &mut EnvHelpers,
+) -> TokenStream2 {
     fields
         .map(|mut field| {
-            let wrap_attr = take_env_field_wrap_attr(&mut field.attrs);
+            let krate = container.krate();
+            let field_attrs = take_env_field_wrap_attr(&mut field.attrs);
+            let wraps = container.wraps(&field);
+            let serde_attr = helpers.add(&field_attrs);
+
+            // The fields with a default of their own keep it
+            let default_attr = (container.default
+                && field_attrs.env.is_none()
+                && field_attrs.default_expand.is_none()
+                && !has_serde_option(&field.attrs, &["default"]))
+            .then(|| quote!(#[serde(default)]));
 
             let ty: syn::Type = field.ty;
-            let ty = match wrap_attr {
-                Some(WrapAttr::Skip) => quote!(#ty),
-                Some(WrapAttr::GenericsOnly(_)) => wrap_generics_only(&ty),
-                None => {
-                    if is_option(&ty) || is_vec(&ty) {
-                        wrap_generics_only(&ty)
-                    } else if is_env_field(&ty) {
-                        quote!(#ty)
-                    } else {
-                        quote!(::serde_env_field::EnvField<#ty>)
-                    }
+            let serde_attr = match &field_attrs.wrap {
+                Some(WrapAttr::Into(_)) => {
+                    let de_path = format!(
+                        "{}::__private::deserialize_try_into::<_, {}, _>",
+                        krate.to_token_stream(),
+                        ty.to_token_stream()
+                    );
+
+                    Some(quote!(#[serde(deserialize_with = #de_path)]))
                 }
+                _ => serde_attr,
+            };
+
+            let expansion = match field_attrs.use_deserialize {
+                Some(_) => container.expansion().use_deserialize(),
+                None => container.expansion(),
+            };
+
+            let ty = match field_attrs.wrap {
+                Some(WrapAttr::Skip) => quote!(#ty),
+                Some(WrapAttr::SkipExpand(_)) => wrap_type(&ty, Expansion::Disabled, &krate),
+                Some(WrapAttr::GenericsOnly(_)) => wrap_generics_only(&ty, expansion, &krate),
+                Some(WrapAttr::ExpandKeys(span)) => wrap_map_entries(&ty, expansion, &krate, span),
+                Some(WrapAttr::Into(target)) => quote!(#target),
+                None if !wraps && field_attrs.use_deserialize.is_none() => quote!(#ty),
+                None => wrap_type(&ty, expansion, &krate),
             };
 
             let attrs = attrs_tokens(field.attrs);
@@ -204,6 +1358,8 @@ fn process_fields(fields: impl Iterator<Item = syn::Field>) -> TokenStream2 {
 
             quote! {
                 #attrs
+                #serde_attr
+                #default_attr
                 #vis
                 #ident
                 #colon
@@ -214,19 +1370,52 @@ fn process_fields(fields: impl Iterator<Item = syn::Field>) -> TokenStream2 {
         .to_token_stream()
 }
 
-fn process_variants(variants: impl Iterator<Item = syn::Variant>) -> TokenStream2 {
+fn process_variants(
+    variants: impl Iterator<Item = syn::Variant>,
+    container: &ContainerAttrs,
+    helpers: &mut EnvHelpers,
+) -> TokenStream2 {
     variants
         .map(|mut variant| {
-            let wrap_attr = take_env_field_wrap_attr(&mut variant.attrs);
-            let fields = variant.fields;
+            let variant_attrs = take_env_field_wrap_attr(&mut variant.attrs);
+            if let Some(env) = variant_attrs.env {
+                abort!(
+                    env.var_name,
+                    "`env` is supported only for fields, not for enum variants"
+                );
+            }
+
+            if variant_attrs.trim.is_some() {
+                abort!(
+                    variant.ident,
+                    "`trim` is supported only for fields, not for enum variants"
+                );
+            }
+
+            if let Some(span) = variant_attrs.use_deserialize {
+                abort!(
+                    span,
+                    "`use_deserialize` is supported only for fields, not for enum variants"
+                );
+            }
 
-            let fields = match wrap_attr {
+            let fields = variant.fields;
+            let fields = match variant_attrs.wrap {
                 Some(WrapAttr::Skip) => quote!(#fields),
-                Some(WrapAttr::GenericsOnly(span)) => abort!(
+                Some(
+                    wrap @ (WrapAttr::SkipExpand(span)
+                    | WrapAttr::GenericsOnly(span)
+                    | WrapAttr::ExpandKeys(span)),
+                ) => abort!(
                     span,
-                    "`generics_only` is supported only for fields, not for enum variants"
+                    "`{}` is supported only for fields, not for enum variants",
+                    wrap.name()
+                ),
+                Some(WrapAttr::Into(target)) => abort!(
+                    target,
+                    "`into` is supported only for fields, not for enum variants"
                 ),
-                None => wrap_fields(fields, WrapKind::Enum),
+                None => wrap_fields(fields, WrapKind::Enum, container, helpers),
             };
 
             let attrs = attrs_tokens(variant.attrs);
@@ -241,35 +1430,47 @@ fn process_variants(variants: impl Iterator<Item = syn::Variant>) -> TokenStream
         .to_token_stream()
 }
 
-enum WrapKind {
-    Struct,
+enum WrapKind<'a> {
+    Struct(Option<&'a syn::WhereClause>),
     Enum,
 }
 
-fn wrap_fields(fields: syn::Fields, kind: WrapKind) -> TokenStream2 {
-    let delim = match kind {
-        WrapKind::Struct => quote!(;),
-        WrapKind::Enum => quote!(),
+fn wrap_fields(
+    fields: syn::Fields,
+    kind: WrapKind,
+    container: &ContainerAttrs,
+    helpers: &mut EnvHelpers,
+) -> TokenStream2 {
+    let (where_clause, delim) = match kind {
+        WrapKind::Struct(where_clause) => (where_clause, quote!(;)),
+        WrapKind::Enum => (None, quote!()),
     };
 
     match fields {
         syn::Fields::Named(fields) => {
-            let fields = process_fields(fields.named.into_iter());
-            quote![{
+            let fields = process_fields(fields.named.into_iter(), container, helpers);
+            quote![#where_clause {
                 #fields
             }]
         }
         syn::Fields::Unnamed(fields) => {
-            let fields = process_fields(fields.unnamed.into_iter());
-            quote![(#fields) #delim]
+            let fields = process_fields(fields.unnamed.into_iter(), container, helpers);
+            quote![(#fields) #where_clause #delim]
         }
-        syn::Fields::Unit => delim,
+        syn::Fields::Unit => quote![#where_clause #delim],
     }
 }
 
-fn enum_env_field_wrap(data: syn::DataEnum) -> TokenStream2 {
-    let variants = process_variants(data.variants.into_iter());
-    quote! {{
-        #variants
-    }}
+fn enum_env_field_wrap(
+    data: syn::DataEnum,
+    where_clause: Option<&syn::WhereClause>,
+    container: &ContainerAttrs,
+    helpers: &mut EnvHelpers,
+) -> TokenStream2 {
+    let variants = process_variants(data.variants.into_iter(), container, helpers);
+    quote! {
+        #where_clause {
+            #variants
+        }
+    }
 }
@@ -0,0 +1,417 @@
+//! The shadow type deserializing the fields with the fallible defaults.
+//!
+//! The serde `default` functions can't fail the deserialization, so the item with such fields
+//! is deserialized via the `#[serde(try_from = "...")]` from the shadow type with the same fields.
+//! The shadow type keeps the absent fields as such, and their defaults are constructed
+//! while converting it into the item, where the default errors fail the deserialization.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro_error::abort;
+use quote::{format_ident, quote, ToTokens};
+use syn::{punctuated::Punctuated, Data, DeriveInput, Token};
+
+use crate::EnvHelpers;
+
+/// The attribute marking the fields with the fallible defaults, holding the default function.
+const DEFERRED_ATTR: &str = "__serde_env_field_deferred";
+
+/// The container options that replace the item deserialization.
+const REPLACING_OPTIONS: [&str; 3] = ["from", "try_from", "remote"];
+
+/// Marks the field constructed by the default function if it is absent.
+///
+/// The default function (either a path or a closure) returns a `Result`,
+/// so the field is deserialized via the shadow type.
+pub(crate) fn deferred_attr(default_fn: &str) -> TokenStream2 {
+    let ident = format_ident!("{DEFERRED_ATTR}");
+    quote!(#[#ident = #default_fn])
+}
+
+/// Makes the item deserialized via the shadow type if it has the fields marked by the `deferred_attr`.
+pub(crate) fn shadowed(item: TokenStream2, helpers: &EnvHelpers) -> TokenStream2 {
+    let mut item: DeriveInput = syn::parse2(item).unwrap_or_else(|err| abort!(err.span(), err));
+    if !fields_of(&mut item).any(|fields| fields.iter().any(is_deferred)) {
+        return item.to_token_stream();
+    }
+
+    let krate = &helpers.krate;
+    let private = quote!(#krate::__private);
+    let shadow_ident = format_ident!("__{}_{}_Shadow", helpers.prefix, helpers.item);
+    let generics = item.generics.clone();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let shadow_ty = quote!(#shadow_ident #ty_generics);
+    let shadow_path = format!(
+        "{}{}",
+        shadow_ident,
+        ty_generics.as_turbofish().to_token_stream()
+    );
+
+    let mut container_default = None;
+    let mut shadow_attrs = vec![];
+    for attr in item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        let metas = serde_metas(attr);
+        if let Some(meta) = metas.iter().find(|meta| {
+            REPLACING_OPTIONS
+                .iter()
+                .any(|name| meta.path().is_ident(name))
+        }) {
+            abort!(
+                meta,
+                "the `env` and `default_expand` fields can't be combined with the `#[serde({})]` container option",
+                meta.path().to_token_stream()
+            );
+        }
+
+        let (defaults, metas): (Vec<_>, Vec<_>) = metas
+            .into_iter()
+            .partition(|meta| meta.path().is_ident("default"));
+
+        container_default = container_default.or(defaults.into_iter().next());
+        if !metas.is_empty() {
+            shadow_attrs.push(quote!(#[serde(#(#metas),*)]));
+        }
+    }
+
+    let mut methods = TokenStream2::new();
+    let mut methods_count = 0;
+    let mut shadow_data = item.data.clone();
+    for fields in fields_of_data(&mut shadow_data) {
+        for field in fields.iter_mut() {
+            let default_fn = deferred_default(field);
+            field.attrs.retain(|attr| attr.path().is_ident("serde"));
+
+            if default_fn.is_none() {
+                continue;
+            }
+
+            let (deserialize_with, with) = take_deserialize_with(&mut field.attrs);
+            let ty = &field.ty;
+            let de = match (deserialize_with, with) {
+                (Some(path), _) => quote!(#path(deserializer)),
+                (None, Some(module)) => quote!(#module::deserialize(deserializer)),
+                (None, None) => {
+                    quote!(<#ty as #private::Deserialize<'__de>>::deserialize(deserializer))
+                }
+            };
+
+            let method = format_ident!("__deserialize_{methods_count}");
+            methods_count += 1;
+            methods.extend(quote! {
+                fn #method<'__de, __D>(
+                    deserializer: __D,
+                ) -> ::core::result::Result<#private::Deferred<#ty>, __D::Error>
+                where
+                    __D: #private::Deserializer<'__de>,
+                {
+                    ::core::result::Result::map(#de, #private::Deferred::Present)
+                }
+            });
+
+            let de_path = format!("{shadow_path}::{method}");
+            field
+                .attrs
+                .push(syn::parse_quote!(#[serde(default, deserialize_with = #de_path)]));
+            field.ty = syn::parse_quote!(#private::Deferred<#ty>);
+        }
+    }
+
+    let ident = &item.ident;
+    let conversion = conversion(ident, &shadow_ident, &item.data, &private);
+    let shadow_default = container_default.map(|default| {
+        let default_expr = match default {
+            syn::Meta::NameValue(name_value) => {
+                let path = match &name_value.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(path),
+                        ..
+                    }) => path
+                        .parse::<syn::ExprPath>()
+                        .unwrap_or_else(|err| abort!(err.span(), err)),
+                    value => abort!(value, "a string literal is expected"),
+                };
+
+                quote!(#path())
+            }
+            _ => quote!(::core::default::Default::default()),
+        };
+
+        let to_shadow = conversion_from_item(ident, &shadow_ident, &item.data, &private);
+        shadow_attrs.push(quote!(#[serde(default)]));
+
+        quote! {
+            impl #impl_generics ::core::default::Default for #shadow_ty #where_clause {
+                fn default() -> Self {
+                    let item: #ident #ty_generics = #default_expr;
+                    #to_shadow
+                }
+            }
+        }
+    });
+
+    let vis = item.vis.clone();
+    let shadow_body = match shadow_data {
+        Data::Struct(data) => match data.fields {
+            syn::Fields::Named(fields) => {
+                quote!(struct #shadow_ident #generics #where_clause #fields)
+            }
+            syn::Fields::Unnamed(fields) => {
+                quote!(struct #shadow_ident #generics #fields #where_clause;)
+            }
+            syn::Fields::Unit => quote!(struct #shadow_ident #generics #where_clause;),
+        },
+        Data::Enum(data) => {
+            let variants = data.variants.into_iter().map(|mut variant| {
+                variant.attrs.retain(|attr| attr.path().is_ident("serde"));
+                variant
+            });
+
+            quote!(enum #shadow_ident #generics #where_clause { #(#variants),* })
+        }
+        Data::Union(_) => unreachable!("the unions aren't supported"),
+    };
+
+    let ident = item.ident.clone();
+
+    // The item keeps its fields, but is deserialized from the shadow type
+    let try_from = shadow_ty.to_string();
+    item.attrs
+        .push(syn::parse_quote!(#[serde(try_from = #try_from)]));
+    for fields in fields_of(&mut item) {
+        for field in fields.iter_mut() {
+            field
+                .attrs
+                .retain(|attr| !attr.path().is_ident(DEFERRED_ATTR));
+        }
+    }
+
+    quote! {
+        #item
+
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        #[derive(#private::Deserialize)]
+        #(#shadow_attrs)*
+        #vis #shadow_body
+
+        #[allow(non_snake_case)]
+        impl #impl_generics #shadow_ty #where_clause {
+            #methods
+        }
+
+        #shadow_default
+
+        impl #impl_generics ::core::convert::TryFrom<#shadow_ty> for #ident #ty_generics #where_clause {
+            type Error = #krate::EnvFieldError;
+
+            fn try_from(shadow: #shadow_ty) -> ::core::result::Result<Self, Self::Error> {
+                ::core::result::Result::Ok(#conversion)
+            }
+        }
+    }
+}
+
+fn is_deferred(field: &syn::Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident(DEFERRED_ATTR))
+}
+
+/// The default function of the field marked by the `deferred_attr`.
+fn deferred_default(field: &syn::Field) -> Option<syn::Expr> {
+    let attr = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(DEFERRED_ATTR))?;
+
+    let syn::Meta::NameValue(syn::MetaNameValue {
+        value:
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(path),
+                ..
+            }),
+        ..
+    }) = &attr.meta
+    else {
+        unreachable!("the deferred attribute holds the default function");
+    };
+
+    Some(path.parse().unwrap_or_else(|err| abort!(err.span(), err)))
+}
+
+fn serde_metas(attr: &syn::Attribute) -> Punctuated<syn::Meta, Token![,]> {
+    attr.parse_args_with(Punctuated::parse_terminated)
+        .unwrap_or_else(|err| abort!(err.span(), err))
+}
+
+/// Removes the `deserialize_with`, `with`, and `default` options from the serde attributes,
+/// returning the `deserialize_with` path and the `with` module.
+fn take_deserialize_with(
+    attrs: &mut Vec<syn::Attribute>,
+) -> (Option<syn::ExprPath>, Option<syn::ExprPath>) {
+    let mut deserialize_with = None;
+    let mut with = None;
+
+    let serde_attrs = std::mem::take(attrs);
+    for attr in serde_attrs {
+        let mut metas = vec![];
+        for meta in serde_metas(&attr) {
+            let slot = match meta.path() {
+                path if path.is_ident("deserialize_with") => &mut deserialize_with,
+                path if path.is_ident("with") => &mut with,
+                path if path.is_ident("default") => continue,
+                _ => {
+                    metas.push(meta);
+                    continue;
+                }
+            };
+
+            let syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(path),
+                        ..
+                    }),
+                ..
+            }) = &meta
+            else {
+                abort!(meta, "a string literal is expected");
+            };
+
+            *slot = Some(path.parse().unwrap_or_else(|err| abort!(err.span(), err)));
+        }
+
+        if !metas.is_empty() {
+            attrs.push(syn::parse_quote!(#[serde(#(#metas),*)]));
+        }
+    }
+
+    (deserialize_with, with)
+}
+
+fn fields_of(item: &mut DeriveInput) -> impl Iterator<Item = &mut syn::Fields> {
+    fields_of_data(&mut item.data)
+}
+
+fn fields_of_data(data: &mut Data) -> Box<dyn Iterator<Item = &mut syn::Fields> + '_> {
+    match data {
+        Data::Struct(data) => Box::new(std::iter::once(&mut data.fields)),
+        Data::Enum(data) => Box::new(data.variants.iter_mut().map(|variant| &mut variant.fields)),
+        Data::Union(_) => Box::new(std::iter::empty()),
+    }
+}
+
+/// The bindings of the fields in the patterns and the constructors.
+fn bindings(fields: &syn::Fields) -> Vec<syn::Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| match &field.ident {
+            Some(ident) => ident.clone(),
+            None => syn::Ident::new(&format!("__field{idx}"), Span::call_site()),
+        })
+        .collect()
+}
+
+/// Destructures the fields into their bindings, or constructs the fields from the values.
+///
+/// The `None` value stands for the binding itself.
+fn fields_tokens(fields: &syn::Fields, values: &[Option<TokenStream2>]) -> TokenStream2 {
+    let bindings = bindings(fields);
+    let values = values.iter().zip(&bindings);
+
+    match fields {
+        syn::Fields::Named(_) => {
+            let values = values.map(|(value, binding)| match value {
+                Some(value) => quote!(#binding: #value),
+                None => quote!(#binding),
+            });
+
+            quote!({ #(#values),* })
+        }
+        syn::Fields::Unnamed(_) => {
+            let values = values.map(|(value, binding)| match value {
+                Some(value) => value.clone(),
+                None => binding.to_token_stream(),
+            });
+
+            quote!(( #(#values),* ))
+        }
+        syn::Fields::Unit => quote!(),
+    }
+}
+
+/// Converts the `shadow` into the item, constructing the absent deferred fields from their defaults.
+fn conversion(
+    ident: &syn::Ident,
+    shadow_ident: &syn::Ident,
+    data: &Data,
+    private: &TokenStream2,
+) -> TokenStream2 {
+    let convert = |fields: &syn::Fields| {
+        let values: Vec<_> = fields
+            .iter()
+            .zip(bindings(fields))
+            .map(|(field, binding)| {
+                deferred_default(field)
+                    .map(|default_fn| quote!(#private::Deferred::resolve(#binding, #default_fn)?))
+            })
+            .collect();
+
+        let pattern = fields_tokens(fields, &vec![None; values.len()]);
+        (pattern, fields_tokens(fields, &values))
+    };
+
+    match data {
+        Data::Struct(data) => {
+            let (pattern, values) = convert(&data.fields);
+            quote!({
+                let #shadow_ident #pattern = shadow;
+                #ident #values
+            })
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let variant_ident = &variant.ident;
+                let (pattern, values) = convert(&variant.fields);
+                quote!(#shadow_ident::#variant_ident #pattern => #ident::#variant_ident #values)
+            });
+
+            quote!(match shadow { #(#arms,)* })
+        }
+        Data::Union(_) => unreachable!("the unions aren't supported"),
+    }
+}
+
+/// Converts the `item` into the shadow type, taking all the deferred fields as present.
+fn conversion_from_item(
+    ident: &syn::Ident,
+    shadow_ident: &syn::Ident,
+    data: &Data,
+    private: &TokenStream2,
+) -> TokenStream2 {
+    let Data::Struct(data) = data else {
+        // The serde reports the container `default` of the enums
+        return quote!(::core::unreachable!());
+    };
+
+    let values: Vec<_> = data
+        .fields
+        .iter()
+        .zip(bindings(&data.fields))
+        .map(|(field, binding)| {
+            is_deferred(field).then(|| quote!(#private::Deferred::Present(#binding)))
+        })
+        .collect();
+
+    let pattern = fields_tokens(&data.fields, &vec![None; values.len()]);
+    let values = fields_tokens(&data.fields, &values);
+    quote!({
+        let #ident #pattern = item;
+        #shadow_ident #values
+    })
+}
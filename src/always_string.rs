@@ -0,0 +1,104 @@
+use std::{fmt, str::FromStr};
+
+use serde::{
+    de::{value::StringDeserializer, DeserializeOwned, Error},
+    Deserialize, Serialize,
+};
+use serde_untagged::UntaggedEnumVisitor;
+
+use crate::{EnvField, EnvFieldError, UseDeserialize, UseFromStr};
+
+/// A marker type for passing into the [`EnvField<T>`] type as a third parameter.
+///
+/// The `EnvField` will accept only strings, so a format can't supply a bare number
+/// or any other non-string value instead.
+/// Also, it will always serialize as a string using the [`Display`](fmt::Display) trait,
+/// so the value stays quoted in the serialized data.
+///
+/// It is useful for secrets and other values that must not be re-typed by a format.
+///
+/// Since the value is constructed only from a string, the `T` doesn't have to implement
+/// the `Deserialize` trait. E.g., the `EnvField<regex::Regex, UseFromStr, AlwaysString>`
+/// compiles the pattern (see also the `UseRegex` marker enabled by the `regex` feature).
+///
+/// If the deserializer is not human-readable (e.g., `bincode`),
+/// the string is used as is without the expansion.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseFromStr, AlwaysString};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     pin: EnvField<u32, UseFromStr, AlwaysString>,
+/// }
+///
+/// std::env::set_var("SECRET_PIN", "1234");
+/// let de: Example = toml::from_str(r#"
+///     pin = "$SECRET_PIN"
+/// "#).unwrap();
+///
+/// assert_eq!(*de.pin, 1234);
+/// assert_eq!(toml::to_string(&de).unwrap(), "pin = \"1234\"\n");
+///
+/// assert!(toml::from_str::<Example>("pin = 1234").is_err());
+/// ```
+pub struct AlwaysString;
+
+impl<T: fmt::Display, V> Serialize for EnvField<T, V, AlwaysString> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&self.0)
+    }
+}
+
+pub(crate) fn deserialize_string<'de, D, T>(
+    deserializer: D,
+    expand_and_construct: impl Fn(&str) -> Result<T, EnvFieldError>,
+    construct: impl FnOnce(String) -> Result<T, EnvFieldError>,
+) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return construct(String::deserialize(deserializer)?).map_err(Error::custom);
+    }
+
+    UntaggedEnumVisitor::new()
+        .expecting("a string")
+        .string(|s| expand_and_construct(s).map_err(Error::custom))
+        .borrowed_str(|s| expand_and_construct(s).map_err(Error::custom))
+        .deserialize(deserializer)
+}
+
+impl<'de, T> Deserialize<'de> for EnvField<T, UseFromStr, AlwaysString>
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_string(deserializer, Self::env_expand_and_construct, |s| {
+            s.parse().map(Self::new).map_err(EnvFieldError::custom)
+        })
+    }
+}
+
+impl<'de, T> Deserialize<'de> for EnvField<T, UseDeserialize, AlwaysString>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_string(deserializer, Self::env_expand_and_construct, |s| {
+            T::deserialize(StringDeserializer::new(s)).map(Self::new)
+        })
+    }
+}
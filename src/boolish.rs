@@ -0,0 +1,58 @@
+use crate::{error::truncated, EnvFieldError, ExpandStrategy};
+
+/// A marker type for passing into the [`EnvField<bool>`] type as a second parameter.
+///
+/// The `EnvField` will accept the common environment conventions for the boolean flags
+/// after the environment variables expansion.
+/// The accepted values are case-insensitive:
+/// * `true`, `1`, `yes`, `on` -- the `true` value.
+/// * `false`, `0`, `no`, `off` -- the `false` value.
+///
+/// Any other string is an error.
+///
+/// If the supplied data was not a string, the `EnvField` will accept a boolean or the `0`/`1` integers.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseBoolish};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     verbose: EnvField<bool, UseBoolish>,
+/// }
+///
+/// std::env::set_var("VERBOSE", "ON");
+/// let de: Example = toml::from_str(r#"
+///     verbose = "$VERBOSE"
+/// "#).unwrap();
+///
+/// assert!(*de.verbose);
+/// ```
+pub struct UseBoolish;
+
+const TRUTHY: [&str; 4] = ["true", "1", "yes", "on"];
+const FALSY: [&str; 4] = ["false", "0", "no", "off"];
+
+impl ExpandStrategy<'_, bool> for UseBoolish {
+    fn from_expanded(expanded: &str) -> Result<bool, EnvFieldError> {
+        let is_one_of = |values: &[&str]| values.iter().any(|v| expanded.eq_ignore_ascii_case(v));
+
+        if is_one_of(&TRUTHY) {
+            Ok(true)
+        } else if is_one_of(&FALSY) {
+            Ok(false)
+        } else {
+            Err(EnvFieldError::Parse(format!(
+                "invalid boolean value '{}': expected one of {} (case-insensitive)",
+                truncated(expanded),
+                TRUTHY
+                    .iter()
+                    .zip(FALSY)
+                    .map(|(t, f)| format!("{t}/{f}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )))
+        }
+    }
+}
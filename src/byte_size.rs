@@ -0,0 +1,119 @@
+use crate::{error::truncated, EnvFieldError, ExpandStrategy};
+
+/// A marker type for passing into the [`EnvField<u64>`] type as a second parameter.
+///
+/// The `EnvField` will parse a human-readable byte size, like `10MB` or `1.5 GiB`,
+/// into the number of bytes after the environment variables expansion.
+///
+/// The units are case-insensitive:
+/// * `B` or no unit -- bytes.
+/// * `KB`, `MB`, `GB`, `TB`, `PB`, `EB` -- the SI units, the powers of 1000.
+/// * `KiB`, `MiB`, `GiB`, `TiB`, `PiB`, `EiB` -- the binary units, the powers of 1024.
+///
+/// The number may have a fractional part if the result is a whole number of bytes.
+/// The number and the unit may be separated by whitespace.
+///
+/// If the supplied data was not a string, the `EnvField` will accept an integer number of bytes.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use serde_env_field::{EnvField, UseByteSize};
+/// #[derive(Deserialize)]
+/// struct Example {
+///     cache_size: EnvField<u64, UseByteSize>,
+///     max_upload: EnvField<u64, UseByteSize>,
+/// }
+///
+/// std::env::set_var("CACHE_SIZE", "1GiB");
+/// let de: Example = toml::from_str(r#"
+///     cache_size = "$CACHE_SIZE"
+///     max_upload = "${MAX_UPLOAD:-10MB}"
+/// "#).unwrap();
+///
+/// assert_eq!(*de.cache_size, 1024 * 1024 * 1024);
+/// assert_eq!(*de.max_upload, 10_000_000);
+/// ```
+pub struct UseByteSize;
+
+const UNITS: [(&str, u64); 13] = [
+    ("B", 1),
+    ("KB", 1000),
+    ("MB", 1000u64.pow(2)),
+    ("GB", 1000u64.pow(3)),
+    ("TB", 1000u64.pow(4)),
+    ("PB", 1000u64.pow(5)),
+    ("EB", 1000u64.pow(6)),
+    ("KiB", 1 << 10),
+    ("MiB", 1 << 20),
+    ("GiB", 1 << 30),
+    ("TiB", 1 << 40),
+    ("PiB", 1 << 50),
+    ("EiB", 1 << 60),
+];
+
+impl ExpandStrategy<'_, u64> for UseByteSize {
+    fn from_expanded(expanded: &str) -> Result<u64, EnvFieldError> {
+        let invalid = |reason: &str| {
+            EnvFieldError::Parse(format!(
+                "invalid byte size '{}': {reason}",
+                truncated(expanded)
+            ))
+        };
+
+        let s = expanded.trim();
+        let number_len = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(number_len);
+        let unit = unit.trim_start();
+
+        let multiplier = if unit.is_empty() {
+            1
+        } else {
+            UNITS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+                .map(|(_, multiplier)| *multiplier)
+                .ok_or_else(|| {
+                    invalid(&format!(
+                        "unknown unit '{}', expected one of {}",
+                        truncated(unit),
+                        UNITS.map(|(name, _)| name).join(", ")
+                    ))
+                })?
+        };
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+            return Err(invalid("expected a number"));
+        }
+
+        let parse = |digits: &str| -> Result<u128, EnvFieldError> {
+            if digits.is_empty() {
+                return Ok(0);
+            }
+
+            digits
+                .parse()
+                .map_err(|_| invalid("the number is too large"))
+        };
+
+        let scale = 10u128
+            .checked_pow(fraction.len() as u32)
+            .ok_or_else(|| invalid("the fractional part is too long"))?;
+
+        let scaled = parse(whole)?
+            .checked_mul(scale)
+            .and_then(|whole| whole.checked_add(parse(fraction).ok()?))
+            .and_then(|number| number.checked_mul(multiplier.into()))
+            .ok_or_else(|| invalid("the size is too large"))?;
+
+        if scaled % scale != 0 {
+            return Err(invalid("the size is not a whole number of bytes"));
+        }
+
+        u64::try_from(scaled / scale).map_err(|_| invalid("the size is too large"))
+    }
+}
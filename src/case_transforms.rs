@@ -0,0 +1,71 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{context, expand, EnvFieldError, ExpandStrategy, UseFromStr};
+
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
+///
+/// Same as the [`UseFromStr`], but the expansion also recognizes
+/// the Bash-like case transforms:
+/// * `${VAR^^}` -- the value of the variable in uppercase.
+/// * `${VAR,,}` -- the value of the variable in lowercase.
+///
+/// The transforms are opt-in, so the other markers treat `${VAR^^}` as a reference
+/// to the variable named `VAR^^`.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseCaseTransforms};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     level: EnvField<String, UseCaseTransforms>,
+///     region: EnvField<String, UseCaseTransforms>,
+/// }
+///
+/// std::env::set_var("LOG_LEVEL", "debug");
+/// std::env::set_var("REGION", "EU-West");
+/// let de: Example = toml::from_str(r#"
+///     level = "${LOG_LEVEL^^}"
+///     region = "${REGION,,}"
+/// "#).unwrap();
+///
+/// assert_eq!(&de.level, "DEBUG");
+/// assert_eq!(&de.region, "eu-west");
+/// ```
+pub struct UseCaseTransforms;
+
+impl<'de, T> ExpandStrategy<'de, T> for UseCaseTransforms
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    fn expand(str_data: &str) -> Result<Cow<'_, str>, EnvFieldError> {
+        context::scoped(
+            |context| &mut context.case_transforms,
+            true,
+            || expand::env(str_data),
+        )
+        .0
+    }
+
+    fn from_expanded(expanded: &str) -> Result<T, EnvFieldError> {
+        UseFromStr::from_expanded(expanded)
+    }
+
+    fn from_nested<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        // The nested `EnvField`s recognize the transforms too
+        context::scoped(
+            |context| &mut context.case_transforms,
+            true,
+            || T::deserialize(deserializer),
+        )
+        .0
+    }
+}
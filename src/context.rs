@@ -0,0 +1,209 @@
+//! The thread-local settings of the environment variables expansion.
+//!
+//! Each thread has its own context, and the threads spawned within a scope
+//! start with the default settings.
+//! The settings are replaced for a scope via the [`ScopeGuard`],
+//! so the nested scopes restore the outer settings when they end, even on panics.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    env::{self, VarError},
+    ffi::{OsStr, OsString},
+    marker::PhantomData,
+    mem,
+};
+
+use crate::{
+    expand::{Resolution, Trim},
+    EnvFieldError,
+};
+
+#[derive(Default)]
+pub(crate) struct Context {
+    /// The expansion errors collected instead of being reported.
+    pub(crate) collected_errors: Option<Vec<EnvFieldError>>,
+
+    /// Whether the strings are taken literally, without the expansion.
+    pub(crate) literal: bool,
+
+    /// Whether the strings are already expanded by an enclosing `ExpandingDeserializer`.
+    pub(crate) expanded: bool,
+
+    /// Whether the values are redacted from the error messages.
+    pub(crate) redact_values: bool,
+
+    /// Whether all the missing variables of a string are reported at once.
+    pub(crate) report_all_missing: bool,
+
+    /// Whether the `${VAR^^}` and `${VAR,,}` case transforms are recognized.
+    pub(crate) case_transforms: bool,
+
+    /// The trimming of the expanded strings.
+    pub(crate) trim: Option<Trim>,
+
+    /// The environment variables used instead of the process environment.
+    pub(crate) env_snapshot: Option<HashMap<OsString, OsString>>,
+
+    /// The positional arguments the `$0`, `$1`, etc. references expand to.
+    pub(crate) positional: Option<Vec<String>>,
+
+    /// The callback notified about each resolved variable.
+    pub(crate) observer: Option<Observer>,
+
+    /// The callback notified about the length of each expanded string.
+    pub(crate) metrics: Option<MetricsHook>,
+}
+
+pub(crate) type Observer = Box<dyn FnMut(&str, Resolution)>;
+
+pub(crate) type MetricsHook = Box<dyn FnMut(usize)>;
+
+thread_local! {
+    static CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+pub(crate) fn with<R>(f: impl FnOnce(&mut Context) -> R) -> R {
+    CONTEXT.with(|context| f(&mut context.borrow_mut()))
+}
+
+/// Replaces a context setting with a value, restoring the previous value on drop.
+///
+/// The context is thread-local, so the guard is `!Send`:
+/// it must restore the setting on the same thread it was created on.
+pub(crate) struct ScopeGuard<S> {
+    setting: fn(&mut Context) -> &mut S,
+    prev: Option<S>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<S> ScopeGuard<S> {
+    pub(crate) fn new(setting: fn(&mut Context) -> &mut S, value: S) -> Self {
+        let prev = with(|context| mem::replace(setting(context), value));
+
+        Self {
+            setting,
+            prev: Some(prev),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Restores the previous value, returning the setting value right before the restoration.
+    pub(crate) fn finish(mut self) -> S {
+        let prev = self.prev.take().expect("the setting is restored only once");
+        with(|context| mem::replace((self.setting)(context), prev))
+    }
+}
+
+impl<S> Drop for ScopeGuard<S> {
+    fn drop(&mut self) {
+        if let Some(prev) = self.prev.take() {
+            with(|context| *(self.setting)(context) = prev);
+        }
+    }
+}
+
+/// Runs the closure with the context setting replaced by the value,
+/// restoring the previous value afterward, even if the closure panics.
+///
+/// Returns the closure result and the setting value right before the restoration.
+pub(crate) fn scoped<S, R>(
+    setting: fn(&mut Context) -> &mut S,
+    value: S,
+    f: impl FnOnce() -> R,
+) -> (R, S) {
+    let guard = ScopeGuard::new(setting, value);
+    let result = f();
+
+    (result, guard.finish())
+}
+
+/// Records the error if the errors are being collected.
+///
+/// Returns `false` if the error should be reported instead.
+pub(crate) fn collect_error(err: &EnvFieldError) -> bool {
+    with(|context| match &mut context.collected_errors {
+        Some(errors) => {
+            errors.push(err.clone());
+            true
+        }
+        None => false,
+    })
+}
+
+/// Notifies the observer, if any, about the variable resolution.
+pub(crate) fn observe(var_name: &str, resolution: Resolution) {
+    // The observer is taken out of the context while it runs,
+    // so it may expand the variables itself without a reentrant borrow.
+    let Some(mut observer) = with(|context| context.observer.take()) else {
+        return;
+    };
+
+    observer(var_name, resolution);
+    with(|context| {
+        context.observer.get_or_insert(observer);
+    });
+}
+
+/// Notifies the metrics hook, if any, about the length of an expanded string.
+pub(crate) fn record_length(len: usize) {
+    // Same as the observer, the hook is taken out of the context while it runs
+    let Some(mut hook) = with(|context| context.metrics.take()) else {
+        return;
+    };
+
+    hook(len);
+    with(|context| {
+        context.metrics.get_or_insert(hook);
+    });
+}
+
+/// Returns the number of the positional arguments
+/// if the name references one of them, i.e., consists of the digits only.
+pub(crate) fn positional_len(name: &str) -> Option<usize> {
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    with(|context| context.positional.as_ref().map(Vec::len))
+}
+
+/// Looks the positional argument up if the name references one,
+/// the environment variable is looked up in the snapshot if there is one,
+/// and in the process environment otherwise.
+pub(crate) fn var(name: &str) -> Result<String, VarError> {
+    if positional_len(name).is_some() {
+        return with(|context| {
+            let args = context.positional.as_ref()?;
+            name.parse()
+                .ok()
+                .and_then(|idx: usize| args.get(idx).cloned())
+        })
+        .ok_or(VarError::NotPresent);
+    }
+
+    let value = with(|context| {
+        context
+            .env_snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.get(OsStr::new(name)).cloned())
+    });
+
+    match value {
+        Some(Some(value)) => value.into_string().map_err(VarError::NotUnicode),
+        Some(None) => Err(VarError::NotPresent),
+        None => env::var(name),
+    }
+}
+
+/// Returns all the environment variables from the snapshot if there is one,
+/// and from the process environment otherwise.
+pub(crate) fn vars_os() -> Vec<(OsString, OsString)> {
+    with(|context| {
+        context
+            .env_snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.clone().into_iter().collect())
+    })
+    .unwrap_or_else(|| env::vars_os().collect())
+}
@@ -0,0 +1,95 @@
+use serde::{de::Error, Deserialize};
+use serde_untagged::UntaggedEnumVisitor;
+
+use crate::{error::truncated, expand, EnvField, EnvFieldError};
+
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
+///
+/// The `EnvField` will parse the string with all environment variables expanded
+/// as a block of the `KEY=VALUE` lines, and then collect the pairs into the `T` map
+/// (e.g., a [`HashMap<String, String>`](std::collections::HashMap)).
+///
+/// The keys and the values are trimmed.
+/// The blank lines and the lines starting with `#` are skipped.
+/// A value may contain `=` and may be enclosed in the double or the single quotes,
+/// which are removed.
+///
+/// If the supplied data was not a string, the `EnvField` will accept a map directly.
+///
+/// ### Example
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseDotenvBlock};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     settings: EnvField<HashMap<String, String>, UseDotenvBlock>,
+/// }
+///
+/// std::env::set_var("SETTINGS", "# The database settings\nHOST=localhost\nQUERY=\"a=b\"\n");
+/// let de: Example = toml::from_str(r#"
+///     settings = "$SETTINGS"
+/// "#).unwrap();
+///
+/// assert_eq!(de.settings["HOST"], "localhost");
+/// assert_eq!(de.settings["QUERY"], "a=b");
+/// ```
+pub struct UseDotenvBlock;
+
+impl<T: FromIterator<(String, String)>> EnvField<T, UseDotenvBlock> {
+    fn env_expand_and_parse_block(str_data: &str) -> Result<Self, EnvFieldError> {
+        let expanded = expand::env(str_data)?;
+
+        expanded
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (key, value) = line.split_once('=').ok_or_else(|| {
+                    EnvFieldError::Parse(format!(
+                        "invalid line '{}': expected KEY=VALUE",
+                        truncated(line)
+                    ))
+                })?;
+
+                Ok((
+                    key.trim_end().to_owned(),
+                    unquoted(value.trim_start()).to_owned(),
+                ))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self::new)
+    }
+}
+
+fn unquoted(value: &str) -> &str {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(quote)
+                .and_then(|value| value.strip_suffix(quote))
+        })
+        .unwrap_or(value)
+}
+
+impl<'de, T> Deserialize<'de> for EnvField<T, UseDotenvBlock>
+where
+    T: FromIterator<(String, String)> + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return T::deserialize(deserializer).map(Self::new);
+        }
+
+        UntaggedEnumVisitor::new()
+            .string(|s| Self::env_expand_and_parse_block(s).map_err(Error::custom))
+            .borrowed_str(|s| Self::env_expand_and_parse_block(s).map_err(Error::custom))
+            .map(|map| map.deserialize().map(Self::new))
+            .deserialize(deserializer)
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{de::Error, Deserialize};
+use serde_untagged::UntaggedEnumVisitor;
+
+use crate::{error::truncated, expand, EnvField, EnvFieldError};
+
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
+///
+/// The `EnvField` will decode the string with all environment variables expanded
+/// from the standard base64 (with padding), and then construct the `T` type from the bytes.
+///
+/// If the supplied data was not a string, the `EnvField` will accept bytes or a sequence of bytes.
+///
+/// Requires the `base64` feature.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseBase64};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     key: EnvField<Vec<u8>, UseBase64>,
+/// }
+///
+/// std::env::set_var("SECRET_KEY", "AQID");
+/// let de: Example = toml::from_str(r#"
+///     key = "$SECRET_KEY"
+/// "#).unwrap();
+///
+/// assert_eq!(*de.key, [1, 2, 3]);
+/// ```
+#[cfg(feature = "base64")]
+pub struct UseBase64;
+
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
+///
+/// The `EnvField` will decode the string with all environment variables expanded
+/// from the hex representation, and then construct the `T` type from the bytes.
+///
+/// If the supplied data was not a string, the `EnvField` will accept bytes or a sequence of bytes.
+///
+/// Requires the `hex` feature.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseHex};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     key: EnvField<Vec<u8>, UseHex>,
+/// }
+///
+/// std::env::set_var("HEX_KEY", "0a0b0c");
+/// let de: Example = toml::from_str(r#"
+///     key = "$HEX_KEY"
+/// "#).unwrap();
+///
+/// assert_eq!(*de.key, [10, 11, 12]);
+/// ```
+#[cfg(feature = "hex")]
+pub struct UseHex;
+
+macro_rules! impl_decoding {
+    ($marker:ident, $encoding:literal, $decode:expr) => {
+        impl<T: From<Vec<u8>>> EnvField<T, $marker> {
+            fn env_expand_and_decode(str_data: &str) -> Result<Self, EnvFieldError> {
+                let expanded = expand::env(str_data)?;
+                let decoded = $decode(expanded.as_bytes()).map_err(|err| {
+                    EnvFieldError::Parse(format!(
+                        "invalid {} string '{}': {err}",
+                        $encoding,
+                        truncated(&expanded),
+                    ))
+                })?;
+
+                Ok(Self::new(decoded.into()))
+            }
+        }
+
+        impl<'de, T> Deserialize<'de> for EnvField<T, $marker>
+        where
+            T: From<Vec<u8>> + Deserialize<'de>,
+        {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                if !deserializer.is_human_readable() {
+                    return T::deserialize(deserializer).map(Self::new);
+                }
+
+                UntaggedEnumVisitor::new()
+                    .string(|s| Self::env_expand_and_decode(s).map_err(Error::custom))
+                    .borrowed_str(|s| Self::env_expand_and_decode(s).map_err(Error::custom))
+                    .bytes(|b| Ok(Self::new(b.to_vec().into())))
+                    .borrowed_bytes(|b| Ok(Self::new(b.to_vec().into())))
+                    .seq(|seq| seq.deserialize().map(Self::new))
+                    .deserialize(deserializer)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "base64")]
+impl_decoding!(UseBase64, "base64", |bytes| {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.decode(bytes)
+});
+
+#[cfg(feature = "hex")]
+impl_decoding!(UseHex, "hex", hex::decode);
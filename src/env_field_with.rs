@@ -0,0 +1,80 @@
+/// Generates a serde `with` module deserializing the field as the [`EnvField`](crate::EnvField).
+///
+/// It is an alternative to the [`env_field_wrap`](crate::env_field_wrap)
+/// for the fields whose types can't be changed.
+/// The generated module has the `serialize` and the `deserialize` functions,
+/// so it can be used via the `#[serde(with = "...")]` attribute.
+///
+/// The macro takes the module name, the field type,
+/// and optionally the `EnvField` variant marker (the [`UseFromStr`](crate::UseFromStr) by default).
+/// The field is serialized as is.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{env_field_with, UseDeserialize};
+/// env_field_with!(mod port_env: u16);
+/// env_field_with!(mod mode_env: Mode, UseDeserialize);
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     #[serde(with = "port_env")]
+///     port: u16,
+///
+///     #[serde(with = "mode_env")]
+///     mode: Mode,
+/// }
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Mode {
+///     Fast,
+///     Slow,
+/// }
+///
+/// # fn main() {
+/// std::env::set_var("EXAMPLE_PORT", "8080");
+/// std::env::set_var("EXAMPLE_MODE", "slow");
+/// let de: Example = toml::from_str(r#"
+///     port = "$EXAMPLE_PORT"
+///     mode = "$EXAMPLE_MODE"
+/// "#).unwrap();
+///
+/// assert_eq!(de.port, 8080);
+/// assert_eq!(de.mode, Mode::Slow);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! env_field_with {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident: $ty:ty $(, $variant:ty)? $(,)?) => {
+        $(#[$attr])*
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Serializes the field as is.
+            pub fn serialize<T, S>(
+                value: &T,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                T: $crate::__private::Serialize + ?Sized,
+                S: $crate::__private::Serializer,
+            {
+                $crate::__private::Serialize::serialize(value, serializer)
+            }
+
+            /// Deserializes the field as the `EnvField`.
+            pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<$ty, D::Error>
+            where
+                D: $crate::__private::Deserializer<'de>,
+            {
+                <$crate::EnvField<$ty $(, $variant)?> as $crate::__private::Deserialize<'de>>::deserialize(
+                    deserializer,
+                )
+                .map($crate::EnvField::into_inner)
+            }
+        }
+    };
+}
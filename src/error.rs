@@ -0,0 +1,149 @@
+use std::{borrow::Cow, env::VarError, fmt};
+
+use crate::context;
+
+/// An error that occurred while expanding the environment variables
+/// or while constructing the value from the expanded string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EnvFieldError {
+    /// A referenced environment variable couldn't be looked up.
+    Lookup {
+        /// The name of the variable.
+        var_name: String,
+
+        /// The reason why the lookup failed.
+        cause: VarError,
+
+        /// The braced reference the unbraced one was likely meant to be, if any.
+        ///
+        /// E.g., `${FOO}_bar` for the unset `$FOO_bar` while the `FOO` is set.
+        suggestion: Option<String>,
+    },
+
+    /// The expanded string couldn't be converted into the target type.
+    Parse(String),
+
+    /// Several environment variables referenced by the same string couldn't be looked up.
+    ///
+    /// Reported instead of the [`Lookup`](Self::Lookup) error
+    /// within the [`EnvField::report_all_missing`](crate::EnvField::report_all_missing).
+    /// Each of the errors is a `Lookup` error.
+    MultipleLookups(Vec<EnvFieldError>),
+}
+
+impl EnvFieldError {
+    /// Whether a referenced environment variable is unset.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvField;
+    /// std::env::remove_var("EXAMPLE_MISSING");
+    /// let err = EnvField::<u16>::parse_expanded("$EXAMPLE_MISSING").unwrap_err();
+    ///
+    /// assert!(err.is_missing_var());
+    /// assert_eq!(err.missing_var_name(), Some("EXAMPLE_MISSING"));
+    /// ```
+    pub fn is_missing_var(&self) -> bool {
+        self.missing_var_name().is_some()
+    }
+
+    /// Returns the name of the unset environment variable, if any.
+    ///
+    /// For the [`MultipleLookups`](Self::MultipleLookups) error,
+    /// it is the first of the unset variables.
+    pub fn missing_var_name(&self) -> Option<&str> {
+        match self {
+            Self::Lookup {
+                var_name,
+                cause: VarError::NotPresent,
+                ..
+            } => Some(var_name),
+            Self::MultipleLookups(errors) => errors.iter().find_map(Self::missing_var_name),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EnvFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lookup {
+                var_name,
+                suggestion: Some(suggestion),
+                ..
+            } => {
+                write!(
+                    f,
+                    "variable '{var_name}' not found; did you mean '{suggestion}'?"
+                )
+            }
+            Self::Lookup {
+                var_name, cause, ..
+            } => {
+                write!(f, "error looking key '{var_name}' up: {cause}")
+            }
+            Self::Parse(msg) => f.write_str(msg),
+            Self::MultipleLookups(errors) => {
+                f.write_str("error looking keys up:")?;
+                for (idx, err) in errors.iter().enumerate() {
+                    let separator = if idx == 0 { " " } else { ", " };
+                    match err {
+                        Self::Lookup {
+                            var_name,
+                            suggestion: Some(suggestion),
+                            ..
+                        } => write!(f, "{separator}'{var_name}' (did you mean '{suggestion}'?)")?,
+                        Self::Lookup {
+                            var_name, cause, ..
+                        } => write!(f, "{separator}'{var_name}' ({cause})")?,
+                        err => write!(f, "{separator}{err}")?,
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvFieldError {}
+
+impl serde::de::Error for EnvFieldError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Parse(msg.to_string())
+    }
+}
+
+const REDACTED: &str = "***";
+
+fn redacting() -> bool {
+    context::with(|context| context.redact_values)
+}
+
+/// Truncates the string for including it into an error message.
+///
+/// The string is replaced entirely if the values are redacted in the current context.
+pub(crate) fn truncated(s: &str) -> Cow<'_, str> {
+    const MAX_CHARS: usize = 32;
+
+    if redacting() {
+        return REDACTED.into();
+    }
+
+    match s.char_indices().nth(MAX_CHARS) {
+        Some((idx, _)) => format!("{}...", &s[..idx]).into(),
+        None => s.into(),
+    }
+}
+
+/// Replaces the value in the error message if the values are redacted in the current context.
+///
+/// It covers the messages constructed by the inner types, which might echo the value.
+pub(crate) fn redacted(err: EnvFieldError, value: &str) -> EnvFieldError {
+    match err {
+        EnvFieldError::Parse(msg) if !value.is_empty() && redacting() => {
+            EnvFieldError::Parse(msg.replace(value, REDACTED))
+        }
+        err => err,
+    }
+}
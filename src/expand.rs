@@ -0,0 +1,345 @@
+//! The environment variables expansion.
+//!
+//! Supports the following forms:
+//! * `$VAR` and `${VAR}` -- the value of the variable, it is an error if the variable is unset.
+//! * `${VAR:-default}` -- the default is used if the variable is unset or empty.
+//! * `${VAR-default}` -- the default is used only if the variable is unset.
+//!
+//!   The defaults are expanded too, so they can reference other variables,
+//!   e.g., `${PRIMARY:-${SECONDARY:-localhost}}`.
+//! * `$$` -- the literal `$`.
+//!
+//! If the positional arguments are provided in the current context, also supports:
+//! * `$0`, `$1`, ..., and `${0}`, `${1}`, ... -- the positional argument by its index.
+//!
+//!   An unbraced reference ends at the last digit, e.g., `$1st` is the argument `1` followed by `st`.
+//!
+//! If the case transforms are enabled in the current context, also supports:
+//! * `${VAR^^}` -- the value of the variable in uppercase.
+//! * `${VAR,,}` -- the value of the variable in lowercase.
+
+use std::{borrow::Cow, env::VarError};
+
+use crate::{context, EnvFieldError};
+
+/// The characters to trim from the expanded strings.
+#[derive(Clone, Copy)]
+pub struct Trim {
+    /// Whether to trim the whitespace.
+    pub whitespace: bool,
+
+    /// The additional characters to trim.
+    pub matches: &'static str,
+}
+
+impl Trim {
+    fn apply(self, expanded: Cow<'_, str>) -> Cow<'_, str> {
+        let trims = |c: char| (self.whitespace && c.is_whitespace()) || self.matches.contains(c);
+
+        match expanded {
+            Cow::Borrowed(s) => Cow::Borrowed(s.trim_matches(trims)),
+            Cow::Owned(s) => Cow::Owned(s.trim_matches(trims).to_owned()),
+        }
+    }
+}
+
+/// How an environment variable reference was resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The value of the variable was used.
+    VarSet,
+
+    /// The variable was unset (or empty, for the `${VAR:-default}` form),
+    /// so the default was used.
+    DefaultUsed,
+
+    /// The variable couldn't be looked up, and there was no default.
+    Missing,
+}
+
+/// Expands all the environment variables in the string.
+///
+/// The input is taken as is if the current context is literal,
+/// or if the strings are already expanded by an enclosing `ExpandingDeserializer`.
+/// The result is trimmed if the trimming is enabled in the current context.
+/// Its length is reported to the metrics hook of the current context, if any.
+pub(crate) fn env(input: &str) -> Result<Cow<'_, str>, EnvFieldError> {
+    let literal = context::with(|context| context.literal || context.expanded);
+    env_unless(input, literal)
+}
+
+/// Same as the `env`, but the input is expanded even if the strings are marked as already expanded.
+///
+/// The `ExpandingDeserializer` expands the strings of its region this way.
+pub(crate) fn region_env(input: &str) -> Result<Cow<'_, str>, EnvFieldError> {
+    let literal = context::with(|context| context.literal);
+    env_unless(input, literal)
+}
+
+fn env_unless(input: &str, literal: bool) -> Result<Cow<'_, str>, EnvFieldError> {
+    let trim = context::with(|context| context.trim);
+    let expanded = if literal {
+        Cow::Borrowed(input)
+    } else {
+        expand_reporting(input)?
+    };
+
+    let expanded = match trim {
+        Some(trim) => trim.apply(expanded),
+        None => expanded,
+    };
+
+    context::record_length(expanded.len());
+    Ok(expanded)
+}
+
+/// Same as the `expand`, but if the current context reports all the missing variables,
+/// the lookup error covers all of them instead of only the first one.
+fn expand_reporting(input: &str) -> Result<Cow<'_, str>, EnvFieldError> {
+    let err = match expand(input) {
+        Err(err @ EnvFieldError::Lookup { .. })
+            if context::with(|context| context.report_all_missing) =>
+        {
+            err
+        }
+        result => return result,
+    };
+
+    // The expansion is repeated to find the rest of the missing variables,
+    // the observer is already notified about the variables referenced before the failure
+    let ((_, errors), _) = context::scoped(
+        |context| &mut context.observer,
+        None,
+        || {
+            context::scoped(
+                |context| &mut context.collected_errors,
+                Some(vec![]),
+                || expand(input),
+            )
+        },
+    );
+
+    let errors: Vec<_> = errors
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|err| matches!(err, EnvFieldError::Lookup { .. }))
+        .collect();
+
+    if errors.len() > 1 {
+        Err(EnvFieldError::MultipleLookups(errors))
+    } else {
+        Err(err)
+    }
+}
+
+fn expand(input: &str) -> Result<Cow<'_, str>, EnvFieldError> {
+    if !input.contains('$') {
+        return Ok(Cow::Borrowed(input));
+    }
+
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(dollar_idx) = rest.find('$') {
+        result.push_str(&rest[..dollar_idx]);
+        rest = &rest[dollar_idx + 1..];
+
+        match rest.chars().next() {
+            Some('{') => match closing_brace(rest) {
+                Some(closing_brace_idx) => {
+                    expand_braced(&rest[1..closing_brace_idx], &mut result)?;
+                    rest = &rest[closing_brace_idx + 1..];
+                }
+                // Unbalanced braces are left as is
+                None => {
+                    result.push_str("${");
+                    rest = &rest[1..];
+                }
+            },
+            Some(c) if is_var_name_char(c) => {
+                let name_len = match positional_name_len(rest) {
+                    Some(len) => len,
+                    None => var_name_len(rest),
+                };
+                result.push_str(&lookup_unbraced(&rest[..name_len])?);
+                rest = &rest[name_len..];
+            }
+            Some('$') => {
+                result.push('$');
+                rest = &rest[1..];
+            }
+            _ => result.push('$'),
+        }
+    }
+
+    result.push_str(rest);
+    Ok(Cow::Owned(result))
+}
+
+fn expand_braced(body: &str, result: &mut String) -> Result<(), EnvFieldError> {
+    let name_len = var_name_len(body);
+    let (name, op) = body.split_at(name_len);
+
+    if name.is_empty() {
+        context::observe(body, Resolution::Missing);
+        result.push_str(&recover(not_present(body))?);
+        return Ok(());
+    }
+
+    if let Some(default) = op.strip_prefix(":-") {
+        match context::var(name) {
+            Ok(value) if !value.is_empty() => {
+                context::observe(name, Resolution::VarSet);
+                result.push_str(&value);
+            }
+            Ok(_) | Err(VarError::NotPresent) => {
+                context::observe(name, Resolution::DefaultUsed);
+                result.push_str(&expand(default)?);
+            }
+            Err(cause) => {
+                context::observe(name, Resolution::Missing);
+                result.push_str(&recover(lookup_error(name, cause))?);
+            }
+        }
+    } else if let Some(default) = op.strip_prefix('-') {
+        match context::var(name) {
+            Ok(value) => {
+                context::observe(name, Resolution::VarSet);
+                result.push_str(&value);
+            }
+            Err(VarError::NotPresent) => {
+                context::observe(name, Resolution::DefaultUsed);
+                result.push_str(&expand(default)?);
+            }
+            Err(cause) => {
+                context::observe(name, Resolution::Missing);
+                result.push_str(&recover(lookup_error(name, cause))?);
+            }
+        }
+    } else if let Some(transform) = case_transform(op) {
+        result.push_str(&transform(&lookup(name)?));
+    } else {
+        // Not a recognized operator, the whole body is treated as the variable name
+        result.push_str(&lookup(body)?);
+    }
+
+    Ok(())
+}
+
+/// Returns the case transform of the operator if the case transforms are enabled.
+fn case_transform(op: &str) -> Option<fn(&str) -> String> {
+    if !context::with(|context| context.case_transforms) {
+        return None;
+    }
+
+    match op {
+        "^^" => Some(str::to_uppercase),
+        ",," => Some(str::to_lowercase),
+        _ => None,
+    }
+}
+
+fn lookup(name: &str) -> Result<String, EnvFieldError> {
+    resolve(name, context::var(name))
+}
+
+/// Same as the `lookup`, but if the variable is unset
+/// while a shorter variable is set (e.g., `FOO` for the `$FOO_bar`),
+/// the error suggests using the braces to delimit the name.
+fn lookup_unbraced(name: &str) -> Result<String, EnvFieldError> {
+    let value = context::var(name);
+    if let Err(VarError::NotPresent) = value {
+        let suggestion = name
+            .rmatch_indices('_')
+            .map(|(idx, _)| name.split_at(idx))
+            .find(|(prefix, _)| !prefix.is_empty() && context::var(prefix).is_ok());
+
+        if let Some((prefix, suffix)) = suggestion {
+            context::observe(name, Resolution::Missing);
+            return recover(EnvFieldError::Lookup {
+                var_name: name.to_owned(),
+                cause: VarError::NotPresent,
+                suggestion: Some(format!("${{{prefix}}}{suffix}")),
+            });
+        }
+    }
+
+    resolve(name, value)
+}
+
+fn resolve(name: &str, value: Result<String, VarError>) -> Result<String, EnvFieldError> {
+    match value {
+        Ok(value) => {
+            context::observe(name, Resolution::VarSet);
+            Ok(value)
+        }
+        Err(cause) => {
+            context::observe(name, Resolution::Missing);
+            recover(lookup_error(name, cause))
+        }
+    }
+}
+
+/// Substitutes the failed variable with an empty string if the errors are being collected.
+fn recover(err: EnvFieldError) -> Result<String, EnvFieldError> {
+    if context::collect_error(&err) {
+        Ok(String::new())
+    } else {
+        Err(err)
+    }
+}
+
+fn lookup_error(name: &str, cause: VarError) -> EnvFieldError {
+    if let Some(len) = context::positional_len(name) {
+        return EnvFieldError::Parse(format!(
+            "positional argument '${name}' is out of range: {len} arguments are provided"
+        ));
+    }
+
+    EnvFieldError::Lookup {
+        var_name: name.to_owned(),
+        cause,
+        suggestion: None,
+    }
+}
+
+fn not_present(name: &str) -> EnvFieldError {
+    lookup_error(name, VarError::NotPresent)
+}
+
+/// Finds the brace closing the one the string starts with,
+/// skipping the nested `${...}` expressions.
+fn closing_brace(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut chars = s.char_indices().skip(1).peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '$' if chars.next_if(|&(_, c)| c == '{').is_some() => depth += 1,
+            '$' => {
+                // The `$$` escape can't open a nested expression
+                chars.next_if(|&(_, c)| c == '$');
+            }
+            '}' if depth == 0 => return Some(idx),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Returns the length of the unbraced positional argument reference the string starts with,
+/// if the positional arguments are provided.
+fn positional_name_len(s: &str) -> Option<usize> {
+    let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    context::positional_len(&s[..len]).map(|_| len)
+}
+
+fn var_name_len(s: &str) -> usize {
+    s.find(|c| !is_var_name_char(c)).unwrap_or(s.len())
+}
+
+fn is_var_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
@@ -0,0 +1,348 @@
+use std::fmt;
+
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+
+use crate::{context, expand};
+
+/// A [`Deserializer`] wrapper that expands the environment variables
+/// in all the string values it meets, however deep they are nested.
+///
+/// The map keys are left untouched.
+///
+/// It makes the [`EnvField<T, UseDeserialize>`](crate::UseDeserialize) expand the string leaves
+/// of the sequences and the maps the `T` is deserialized from.
+/// It can also mark a region of a document where the strings are expanded,
+/// e.g., a single section of a config, while the rest of the document is taken literally.
+/// The expanded strings are handed over as strings,
+/// so the other types within the region still need the [`EnvField`](crate::EnvField).
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Deserialize, Deserializer};
+/// # use std::collections::HashMap;
+/// use serde_env_field::{EnvField, ExpandingDeserializer};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     // Only this section is expanded
+///     #[serde(deserialize_with = "expanding")]
+///     server: Server,
+///
+///     // The user-supplied data is taken literally
+///     templates: HashMap<String, String>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Server {
+///     url: String,
+///     ports: Vec<EnvField<u16>>,
+/// }
+///
+/// fn expanding<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+/// where
+///     D: Deserializer<'de>,
+///     T: Deserialize<'de>,
+/// {
+///     T::deserialize(ExpandingDeserializer::new(deserializer))
+/// }
+///
+/// std::env::set_var("REGION_HOST", "example.com");
+/// std::env::set_var("REGION_PORT", "8080");
+/// let config: Config = toml::from_str(r#"
+///     [server]
+///     url = "https://$REGION_HOST"
+///     ports = [443, "$REGION_PORT"]
+///
+///     [templates]
+///     greeting = "Hello, $USER!"
+/// "#).unwrap();
+///
+/// assert_eq!(config.server.url, "https://example.com");
+/// assert_eq!(config.server.ports, [443, 8080]);
+/// assert_eq!(config.templates["greeting"], "Hello, $USER!");
+/// ```
+pub struct ExpandingDeserializer<D> {
+    deserializer: D,
+
+    /// Whether the deserializer starts a region rather than reads a value nested in one.
+    region: bool,
+}
+
+impl<D> ExpandingDeserializer<D> {
+    /// Wraps the deserializer, expanding the strings of the data it reads.
+    pub fn new(deserializer: D) -> Self {
+        Self {
+            deserializer,
+            region: true,
+        }
+    }
+
+    fn nested(deserializer: D) -> Self {
+        Self {
+            deserializer,
+            region: false,
+        }
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {
+        $(
+            fn $method<V: Visitor<'de>>(
+                self,
+                $($arg: $ty,)*
+                visitor: V,
+            ) -> Result<V::Value, Self::Error> {
+                let deserializer = self.deserializer;
+                if !self.region {
+                    return deserializer.$method($($arg,)* ExpandingVisitor(visitor));
+                }
+
+                // A region within another one reads the strings the outer region already expanded
+                if context::with(|context| context.expanded) {
+                    return deserializer.$method($($arg,)* visitor);
+                }
+
+                // The strings are marked as expanded for the whole region,
+                // so the `EnvField`s inside don't expand them once again,
+                // even if they are buffered and replayed later
+                // (e.g., the content of an adjacently tagged enum preceding the tag)
+                context::scoped(
+                    |context| &mut context.expanded,
+                    true,
+                    || deserializer.$method($($arg,)* ExpandingVisitor(visitor)),
+                )
+                .0
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for ExpandingDeserializer<D> {
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any()
+        deserialize_bool()
+        deserialize_i8()
+        deserialize_i16()
+        deserialize_i32()
+        deserialize_i64()
+        deserialize_i128()
+        deserialize_u8()
+        deserialize_u16()
+        deserialize_u32()
+        deserialize_u64()
+        deserialize_u128()
+        deserialize_f32()
+        deserialize_f64()
+        deserialize_char()
+        deserialize_str()
+        deserialize_string()
+        deserialize_bytes()
+        deserialize_byte_buf()
+        deserialize_option()
+        deserialize_unit()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_seq()
+        deserialize_tuple(len: usize)
+        deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_map()
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier()
+        deserialize_ignored_any()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
+    }
+}
+
+struct ExpandingVisitor<V>(V);
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty))*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for ExpandingVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    forward_visit! {
+        visit_bool(bool)
+        visit_i8(i8)
+        visit_i16(i16)
+        visit_i32(i32)
+        visit_i64(i64)
+        visit_i128(i128)
+        visit_u8(u8)
+        visit_u16(u16)
+        visit_u32(u32)
+        visit_u64(u64)
+        visit_u128(u128)
+        visit_f32(f32)
+        visit_f64(f64)
+        visit_char(char)
+        visit_bytes(&[u8])
+        visit_borrowed_bytes(&'de [u8])
+        visit_byte_buf(Vec<u8>)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let expanded = expand::region_env(v).map_err(E::custom)?;
+        self.0.visit_str(&expanded)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        self.visit_str(&v)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0
+            .visit_some(ExpandingDeserializer::nested(deserializer))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        self.0
+            .visit_newtype_struct(ExpandingDeserializer::nested(deserializer))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(ExpandingAccess(seq))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_map(ExpandingAccess(map))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_enum(ExpandingAccess(data))
+    }
+}
+
+struct ExpandingSeed<S>(S);
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for ExpandingSeed<S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0
+            .deserialize(ExpandingDeserializer::nested(deserializer))
+    }
+}
+
+/// The sequence, map, and enum accessors expanding the nested values.
+struct ExpandingAccess<A>(A);
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for ExpandingAccess<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.0.next_element_seed(ExpandingSeed(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for ExpandingAccess<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        // The keys aren't expanded by the region, so the `EnvField` keys expand them on their own
+        context::scoped(
+            |context| &mut context.expanded,
+            false,
+            || self.0.next_key_seed(seed),
+        )
+        .0
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        self.0.next_value_seed(ExpandingSeed(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: EnumAccess<'de>> EnumAccess<'de> for ExpandingAccess<A> {
+    type Error = A::Error;
+    type Variant = ExpandingAccess<A::Variant>;
+
+    fn variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<(T::Value, Self::Variant), Self::Error> {
+        self.0
+            .variant_seed(ExpandingSeed(seed))
+            .map(|(value, variant)| (value, ExpandingAccess(variant)))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for ExpandingAccess<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        self.0.newtype_variant_seed(ExpandingSeed(seed))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.tuple_variant(len, ExpandingVisitor(visitor))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.struct_variant(fields, ExpandingVisitor(visitor))
+    }
+}
@@ -0,0 +1,320 @@
+use std::{env::VarError, ffi::OsString, str::FromStr};
+
+use serde::{
+    de::{
+        self, value::SeqDeserializer, DeserializeOwned, DeserializeSeed, IntoDeserializer,
+        MapAccess, Visitor,
+    },
+    forward_to_deserialize_any, Deserializer,
+};
+
+use crate::{context, EnvFieldError};
+
+/// Deserializes the `T` type from the environment variables with the given prefix.
+///
+/// Each field of the `T` struct is read from the `{prefix}{FIELD}` variable,
+/// where `FIELD` is the uppercased field name.
+/// The fields whose variables are unset are treated as absent,
+/// so the `Option` fields and the fields with `#[serde(default)]` may be omitted.
+///
+/// The [`serde_env_field`](crate::serde_env_field) fields marked with
+/// the `#[serde_env_field(rename_env = "VAR")]` attribute are read from the `VAR` variable instead.
+/// The variable name is taken as is, without the prefix.
+///
+/// The variable values are taken literally and parsed according to the field types.
+/// The sequences are read from the comma-separated values the same way as by the
+/// [`UseSplit`](crate::UseSplit), so an empty value is an empty sequence.
+/// Wrap the fields with the [`EnvField`](crate::EnvField) to expand the environment variables
+/// referenced in the values, just like in the documents.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use serde_env_field::EnvField;
+/// #[derive(Deserialize)]
+/// struct Config {
+///     name: String,
+///     size: u32,
+///     url: EnvField<String>,
+///     tags: Vec<String>,
+///     comment: Option<String>,
+/// }
+///
+/// std::env::set_var("APP_NAME", "example");
+/// std::env::set_var("APP_SIZE", "64");
+/// std::env::set_var("APP_URL", "http://${APP_NAME}.com");
+/// std::env::set_var("APP_TAGS", "one,two");
+///
+/// let cfg: Config = serde_env_field::from_env("APP_").unwrap();
+///
+/// assert_eq!(cfg.name, "example");
+/// assert_eq!(cfg.size, 64);
+/// assert_eq!(&cfg.url, "http://example.com");
+/// assert_eq!(cfg.tags, ["one", "two"]);
+/// assert_eq!(cfg.comment, None);
+/// ```
+pub fn from_env<T: DeserializeOwned>(prefix: &str) -> Result<T, EnvFieldError> {
+    T::deserialize(EnvDeserializer::new(prefix))
+}
+
+/// A [`Deserializer`] that constructs the values from the environment variables.
+///
+/// See the [`from_env`] for the details.
+pub struct EnvDeserializer<'a> {
+    prefix: &'a str,
+}
+
+impl<'a> EnvDeserializer<'a> {
+    /// Creates a new deserializer reading the variables with the given prefix.
+    pub fn new(prefix: &'a str) -> Self {
+        Self { prefix }
+    }
+
+    fn var(&self, key: &str, var_name: String) -> Result<Option<EnvVar>, EnvFieldError> {
+        match context::var(&var_name) {
+            Ok(value) => Ok(Some(EnvVar {
+                key: key.to_owned(),
+                var_name,
+                value,
+            })),
+            Err(VarError::NotPresent) => Ok(None),
+            Err(cause) => Err(EnvFieldError::Lookup {
+                var_name,
+                cause,
+                suggestion: None,
+            }),
+        }
+    }
+
+    fn prefixed_vars(&self) -> Result<Vec<EnvVar>, EnvFieldError> {
+        let mut vars = vec![];
+
+        for (var_name, value) in context::vars_os() {
+            let Some(key) = var_name
+                .to_str()
+                .and_then(|name| name.strip_prefix(self.prefix))
+            else {
+                continue;
+            };
+
+            vars.push(EnvVar {
+                key: key.to_lowercase(),
+                var_name: var_name.to_string_lossy().into_owned(),
+                value: into_string(&var_name, value)?,
+            });
+        }
+
+        Ok(vars)
+    }
+}
+
+/// Parses the alias added by the `#[serde_env_field(rename_env = "...")]`,
+/// returning the variable name and the serde name of the field.
+fn rename_env(alias: &str) -> Option<(&str, &str)> {
+    alias.strip_prefix('\0')?.split_once('\0')
+}
+
+fn into_string(var_name: &OsString, value: OsString) -> Result<String, EnvFieldError> {
+    value.into_string().map_err(|value| EnvFieldError::Lookup {
+        var_name: var_name.to_string_lossy().into_owned(),
+        cause: VarError::NotUnicode(value),
+        suggestion: None,
+    })
+}
+
+impl<'de> Deserializer<'de> for EnvDeserializer<'_> {
+    type Error = EnvFieldError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(EnvVars::new(self.prefixed_vars()?))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let renamed: Vec<_> = fields
+            .iter()
+            .filter_map(|alias| rename_env(alias).map(|(var_name, field)| (alias, var_name, field)))
+            .collect();
+
+        let mut vars = vec![];
+        for field in fields {
+            if rename_env(field).is_some() || renamed.iter().any(|(.., renamed)| renamed == field) {
+                continue;
+            }
+
+            let var_name = format!("{}{}", self.prefix, field.to_uppercase());
+            vars.extend(self.var(field, var_name)?);
+        }
+
+        for (alias, var_name, _) in renamed {
+            vars.extend(self.var(alias, var_name.to_owned())?);
+        }
+
+        visitor.visit_map(EnvVars::new(vars))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct EnvVar {
+    key: String,
+    var_name: String,
+    value: String,
+}
+
+struct EnvVars {
+    vars: std::vec::IntoIter<EnvVar>,
+    pending: Option<(String, String)>,
+}
+
+impl EnvVars {
+    fn new(vars: Vec<EnvVar>) -> Self {
+        Self {
+            vars: vars.into_iter(),
+            pending: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for EnvVars {
+    type Error = EnvFieldError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some(var) = self.vars.next() else {
+            return Ok(None);
+        };
+
+        self.pending = Some((var.var_name, var.value));
+        seed.deserialize(var.key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (var_name, value) = self
+            .pending
+            .take()
+            .expect("the value is requested after its key");
+
+        seed.deserialize(ValueDeserializer(value)).map_err(|err| {
+            EnvFieldError::Parse(format!("invalid value of the '{var_name}' variable: {err}"))
+        })
+    }
+}
+
+/// Deserializes a variable value, parsing it according to the requested type.
+struct ValueDeserializer(String);
+
+impl ValueDeserializer {
+    fn parse<T>(&self) -> Result<T, EnvFieldError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.0.parse().map_err(de::Error::custom)
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),* $(,)?) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.$visit(self.parse()?)
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = EnvFieldError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.0)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // Same as the `UseSplit`, a single trailing separator is allowed
+        let value = self.0.strip_suffix(',').unwrap_or(&self.0);
+        let items = (!value.is_empty())
+            .then(|| value.split(','))
+            .into_iter()
+            .flatten()
+            .map(|item| ValueDeserializer(item.to_owned()));
+
+        visitor.visit_seq(SeqDeserializer::new(items))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, EnvFieldError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
@@ -0,0 +1,87 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    error::{redacted, truncated},
+    EnvFieldError, ExpandStrategy,
+};
+
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter,
+/// where the `T` is a primitive number.
+///
+/// The `EnvField` will remove the `SEP` grouping separators (the underscore by default)
+/// from the string with all environment variables expanded,
+/// and then construct the `T` number using the [`FromStr`] trait.
+///
+/// Only the separators between two digits are removed,
+/// so a misplaced separator (e.g., `_1000` or `1__000`) is still an error.
+///
+/// If the supplied data was not a string, the `EnvField` will accept a number directly.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseGrouped};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     max_size: EnvField<u64, UseGrouped>,
+///     budget: EnvField<f64, UseGrouped<','>>,
+/// }
+///
+/// std::env::set_var("MAX_SIZE", "1_000_000");
+/// let de: Example = toml::from_str(r#"
+///     max_size = "$MAX_SIZE"
+///     budget = "12,500.50"
+/// "#).unwrap();
+///
+/// assert_eq!(de.max_size, 1_000_000);
+/// assert_eq!(de.budget, 12_500.5);
+/// ```
+pub struct UseGrouped<const SEP: char = '_'>;
+
+mod sealed {
+    /// The numbers that can be written with the grouping separators.
+    pub trait Number {}
+
+    macro_rules! impl_number {
+        ($($ty:ty),* $(,)?) => {
+            $(impl Number for $ty {})*
+        };
+    }
+
+    impl_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+}
+
+fn ungrouped(s: &str, sep: char) -> String {
+    let mut prev = None;
+    let mut chars = s.chars().peekable();
+    let mut result = String::with_capacity(s.len());
+
+    while let Some(c) = chars.next() {
+        let between_digits = prev.is_some_and(|p: char| p.is_ascii_digit())
+            && chars.peek().is_some_and(char::is_ascii_digit);
+
+        if c != sep || !between_digits {
+            result.push(c);
+        }
+
+        prev = Some(c);
+    }
+
+    result
+}
+
+impl<T, const SEP: char> ExpandStrategy<'_, T> for UseGrouped<SEP>
+where
+    T: sealed::Number + FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    fn from_expanded(expanded: &str) -> Result<T, EnvFieldError> {
+        ungrouped(expanded, SEP).parse().map_err(|err| {
+            redacted(
+                EnvFieldError::Parse(format!("invalid value '{}': {err}", truncated(expanded))),
+                expanded,
+            )
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::{AlwaysString, EnvField, EnvTemplate};
+
+const ENV_STRING_DESCRIPTION: &str =
+    "A string with environment variables to expand, e.g. `${VAR:-default}`";
+
+fn env_schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            generator.subschema_for::<T>(),
+            {
+                "type": "string",
+                "description": ENV_STRING_DESCRIPTION,
+            },
+        ]
+    })
+}
+
+impl<T: JsonSchema, V> JsonSchema for EnvField<T, V> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        format!("EnvField_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!("serde_env_field::EnvField<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        env_schema::<T>(generator)
+    }
+}
+
+impl<T, V> JsonSchema for EnvField<T, V, AlwaysString> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "EnvField_AlwaysString".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": ENV_STRING_DESCRIPTION,
+        })
+    }
+}
+
+impl<T: JsonSchema, V> JsonSchema for EnvTemplate<T, V> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        EnvField::<T, V>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        EnvField::<T, V>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        env_schema::<T>(generator)
+    }
+}
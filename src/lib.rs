@@ -14,7 +14,10 @@
 //!
 //! Also, the crate provides the [`env_field_wrap`] attribute that wraps
 //! all the fields of a struct or an enum with the `EnvField` type.
-//! The attribute also honors the optional and vector fields.
+//! The attribute also honors the optional, vector, and tuple fields.
+//! For the fields whose types can't be changed,
+//! the [`env_field_with!`] macro generates a module for the `#[serde(with = "...")]` attribute.
+//! To keep the field types intact altogether, use the [`serde_env_field`] attribute.
 //!
 //! #### `EnvField` Example
 //!
@@ -77,34 +80,203 @@
 #![warn(missing_docs)]
 
 use std::{
+    borrow::{Borrow, Cow},
+    collections::HashMap,
     fmt::{self, Debug},
+    hash::{Hash, Hasher},
     marker::PhantomData,
     ops::*,
     str::FromStr,
+    sync::Arc,
 };
 
 use serde::{
-    de::{self, value::StringDeserializer, Error},
+    de::{
+        self,
+        value::{
+            EnumAccessDeserializer, MapAccessDeserializer, SeqAccessDeserializer, UnitDeserializer,
+        },
+        Error,
+    },
     Deserialize, Serialize,
 };
-use serde_untagged::{de::Error as UntaggedError, UntaggedEnumVisitor};
+use serde_untagged::UntaggedEnumVisitor;
+
+mod always_string;
+mod boolish;
+mod byte_size;
+mod case_transforms;
+mod context;
+mod dotenv_block;
+mod env_field_with;
+mod error;
+mod expand;
+mod expanding;
+mod from_env;
+mod grouped;
+mod no_expand;
+mod optional;
+mod path_list;
+mod split;
+mod strategy;
+mod template;
+
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
+#[cfg(any(feature = "base64", feature = "hex"))]
+mod encoding;
+#[cfg(feature = "schemars")]
+mod json_schema;
+#[cfg(feature = "regex")]
+mod regex;
+#[cfg(feature = "secrecy")]
+mod secret;
+
+pub use always_string::AlwaysString;
+pub use boolish::UseBoolish;
+pub use byte_size::UseByteSize;
+pub use case_transforms::UseCaseTransforms;
+pub use dotenv_block::UseDotenvBlock;
+#[cfg(feature = "base64")]
+pub use encoding::UseBase64;
+#[cfg(feature = "hex")]
+pub use encoding::UseHex;
+pub use error::EnvFieldError;
+pub use expand::Resolution;
+pub use expanding::ExpandingDeserializer;
+pub use from_env::{from_env, EnvDeserializer};
+pub use grouped::UseGrouped;
+pub use no_expand::NoExpand;
+pub use path_list::UsePathList;
+#[cfg(feature = "regex")]
+pub use regex::UseRegex;
+#[cfg(feature = "secrecy")]
+pub use secret::UseSecret;
+pub use split::UseSplit;
+pub use strategy::{ExpandStrategy, FromExpandedStr};
+pub use template::{EnvTemplate, StaticTemplate};
+
+use error::{redacted, truncated};
+use optional::SomeDeserializer;
 
 /// The `env_field_wrap` wraps all the fields of a struct or an enum with the [`EnvField`] type.
 ///
 /// The [`Option<T>`] fields will remain optional, with only the `T` type wrapped with the `EnvField`.
+/// The nested options (e.g., the `Option<Option<T>>` telling a null from an absent field)
+/// wrap only the innermost `T`.
 ///
 /// Similarly, the [`Vec<T>`] fields will remain vectors, with only the `T` type wrapped.
 ///
+/// The tuple fields will remain tuples, with each element wrapped individually.
+///
+/// The [`Result<T, E>`] fields will remain results, with only the `T` type wrapped.
+/// The error type stays untouched.
+///
+/// With the `indexmap` feature enabled, the `IndexMap<K, V>` fields will remain maps,
+/// with only the `V` type wrapped. The keys and the order of the entries stay untouched.
+///
 /// It is possible to skip a field using the `#[env_field_wrap(skip)]` attribute.
 /// The fields that already have the `EnvField` type skipped automatically.
+/// So are the types referring to the `Self` (e.g., the `Vec<Self>` of a recursive type
+/// remains as is), since the fields of the `Self` are wrapped already.
+/// With the `use_deserialize`, they are wrapped as usual.
+///
+/// To keep a field wrapped while taking its strings literally, without the expansion,
+/// use the `#[env_field_wrap(skip_expand)]` attribute.
+/// The field type becomes the `EnvField<T, NoExpand>` (see the [`NoExpand`]).
+///
+/// The fields whose types don't implement the `FromStr` (e.g., the enums with data)
+/// can be constructed via the [`UseDeserialize`] using the `#[env_field_wrap(use_deserialize)]` attribute.
+/// The field type becomes the `EnvField<T, UseDeserialize>`, and the optional, vector,
+/// and tuple fields are honored as usual.
+///
+/// A field can be deserialized as the `EnvField` of its type and then converted into another type
+/// using the `#[env_field_wrap(into = Target)]` attribute (or `into = "Target<T>"` for the generic types).
+/// The field type becomes the `Target`, which must implement the `TryFrom` (or `From`)
+/// of the original field type. The conversion error is reported as the deserialization error.
 ///
 /// Also, one can wrap a generic type similarly to an `Option` field
 /// using the `#[env_field_wrap(generics_only)]` attribute.
 ///
+/// The `#[env_field_wrap(expand_keys)]` attribute wraps both the keys and the values
+/// of a `HashMap<K, V>`, `BTreeMap<K, V>`, or `IndexMap<K, V>` field,
+/// so the keys expand the environment variables too.
+///
+/// To wrap only some of the fields, list their names in the `#[env_field_wrap(only(...))]`
+/// container attribute. The rest of the fields will keep their original types,
+/// unless they have their own `env_field_wrap` attribute.
+///
+/// A field can be overridden with an environment variable using the
+/// `#[env_field_wrap(env = "VAR")]` attribute. The `precedence` option selects
+/// which value wins when both the document value and the variable are present:
+/// * `precedence = "env"` (the default) -- the variable value is used,
+///   and the document value is ignored without being expanded.
+/// * `precedence = "document"` -- the document value is used (and expanded as usual),
+///   and the variable serves as a fallback.
+///
+/// The variable name is independent of the field name and its serde name,
+/// so renaming the field with `#[serde(rename = "...")]` doesn't affect the variable.
+///
+/// If the field is absent in the document, the variable value is used in both cases.
+/// If the variable is unset too, the field defaults to `Default::default()`,
+/// so the field type must implement the `Default` trait.
+/// The variable value is taken literally, i.e., it isn't expanded.
+/// An invalid variable value fails the deserialization of the whole item.
+/// The absent `env` fields are constructed after the rest of the item is deserialized,
+/// so the same container attribute restrictions as for the `default_expand` fields apply (see below).
+///
+/// The `#[env_field_wrap(trim)]` attribute trims the whitespace from the expanded strings
+/// of the field before constructing the value.
+/// Similarly, the `#[env_field_wrap(trim_matches = "\"'")]` trims the listed characters.
+/// The options can be combined, and they don't affect the non-string values.
+///
+/// The `#[serde(default = "...")]` functions return the field values as is,
+/// so the templates they might contain aren't expanded.
+/// Instead, the `#[env_field_wrap(default_expand = "make_template")]` attribute
+/// makes the absent field constructed from the template returned by the `make_template` function
+/// (e.g., a `&'static str` or a `String`), which is expanded the same way as the document values.
+/// If the template can't be expanded or the field can't be constructed from the result,
+/// the deserialization of the whole item fails.
+///
+/// Since the absent `default_expand` fields are constructed after the rest of the item is deserialized,
+/// such items can't use the `#[serde(from = "...")]`, `#[serde(try_from = "...")]`,
+/// or `#[serde(remote = "...")]` container attributes.
+///
+/// The `#[env_field_wrap(serialize_with_env)]` container attribute wraps the fields
+/// with the [`EnvTemplate`] instead of the `EnvField`.
+/// The fields preserve their original templates and serialize back to them,
+/// so the whole item round-trips to its templated form.
+/// Note that the `EnvTemplate` expands the variables on the first access to the value.
+///
+/// The wrapped fields of a generic struct or enum might require additional bounds
+/// on the type parameters (e.g., the [`FromStr`] for the `EnvField<T>`).
+/// The `#[env_field_wrap(bound = "...")]` container attribute adds the listed predicates
+/// to the where-clause of the item, similarly to the `#[serde(bound = "...")]`.
+///
+/// The `#[env_field_wrap(derive(...))]` container attribute adds the listed derives
+/// to the generated item, after the original attributes.
+///
+/// The `#[env_field_wrap(default)]` container attribute adds the `#[serde(default)]`
+/// to every field, so the absent fields default to `Default::default()`
+/// and the field types must implement the `Default` trait.
+/// Unlike the container-level `#[serde(default)]`, it doesn't require the item itself
+/// to implement the `Default`.
+/// The fields with a default of their own (e.g., the `env` or the `default_expand` ones) keep it.
+///
+/// The generated code refers to this crate as `::serde_env_field`.
+/// If the crate is available only under another path (e.g., re-exported by another crate),
+/// the `#[env_field_wrap(crate = "...")]` container attribute sets the path to use instead,
+/// similarly to the `#[serde(crate = "...")]`.
+///
 /// **NOTE:** If you are using the `#[derive(Deserialize)]`,
 /// the `#[env_field_wrap]` attribute must appear **before** it.
 /// Otherwise, it won't work.
 ///
+/// To see the generated item, set the `SERDE_ENV_FIELD_DEBUG=1` environment variable
+/// during the compilation. The attribute will print the resulting tokens to the stderr.
+/// Cargo doesn't track this variable, so the crate might need to be rebuilt to see the output.
+///
 /// ### Examples
 ///
 /// #### Basic
@@ -197,6 +369,27 @@ use serde_untagged::{de::Error as UntaggedError, UntaggedEnumVisitor};
 ///
 /// ```
 ///
+/// #### Tuple fields
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::env_field_wrap;
+/// #[env_field_wrap]
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     pair: (String, i32),
+/// }
+///
+/// std::env::set_var("PAIR_NUM", "77");
+/// let de: Example = toml::from_str(r#"
+///     pair = ["name", "$PAIR_NUM"]
+/// "#).unwrap();
+///
+/// assert_eq!(&de.pair.0, "name");
+/// assert_eq!(de.pair.1, 77);
+///
+/// ```
+///
 /// #### Skip a field
 ///
 /// ```
@@ -222,6 +415,104 @@ use serde_untagged::{de::Error as UntaggedError, UntaggedEnumVisitor};
 ///
 /// ```
 ///
+/// #### Wrap only the listed fields
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::env_field_wrap;
+/// #[env_field_wrap(only(wrapped))]
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///    wrapped: String,
+///    not_listed: String,
+/// }
+///
+/// std::env::set_var("WRAPPED", "From Env");
+/// let de: Example = toml::from_str(r#"
+///     wrapped = "$WRAPPED"
+///     not_listed = "$NOT_LISTED"
+/// "#).unwrap();
+///
+/// assert_eq!(&de.wrapped, "From Env");
+/// assert_eq!(&de.not_listed, "$NOT_LISTED");
+///
+/// ```
+///
+/// #### Override a field with an environment variable
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::env_field_wrap;
+/// #[env_field_wrap]
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     #[env_field_wrap(env = "OVERRIDE_PORT")]
+///     port: u16,
+///
+///     #[env_field_wrap(env = "FALLBACK_HOST", precedence = "document")]
+///     host: String,
+/// }
+///
+/// std::env::set_var("OVERRIDE_PORT", "9000");
+/// std::env::set_var("FALLBACK_HOST", "fallback.example.com");
+///
+/// let de: Example = toml::from_str(r#"
+///     port = 8080
+///     host = "example.com"
+/// "#).unwrap();
+///
+/// assert_eq!(de.port, 9000);
+/// assert_eq!(&de.host, "example.com");
+///
+/// let de: Example = toml::from_str("").unwrap();
+///
+/// assert_eq!(de.port, 9000);
+/// assert_eq!(&de.host, "fallback.example.com");
+///
+/// ```
+///
+/// #### Trim the expanded strings
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::env_field_wrap;
+/// #[env_field_wrap]
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     #[env_field_wrap(trim, trim_matches = "\"'")]
+///     token: String,
+/// }
+///
+/// std::env::set_var("QUOTED_TOKEN", " 'secret' ");
+/// let de: Example = toml::from_str(r#"
+///     token = "$QUOTED_TOKEN"
+/// "#).unwrap();
+///
+/// assert_eq!(&de.token, "secret");
+///
+/// ```
+///
+/// #### Generic structs
+///
+/// ```
+/// # use std::{fmt::Display, str::FromStr};
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::env_field_wrap;
+/// #[env_field_wrap(bound = "T: FromStr, <T as FromStr>::Err: Display")]
+/// #[derive(Serialize, Deserialize)]
+/// struct Example<T> {
+///     value: T,
+/// }
+///
+/// std::env::set_var("GENERIC_VALUE", "12");
+/// let de: Example<u8> = toml::from_str(r#"
+///     value = "$GENERIC_VALUE"
+/// "#).unwrap();
+///
+/// assert_eq!(de.value, 12);
+///
+/// ```
+///
 /// #### Skip an enum variant
 ///
 /// ```
@@ -310,8 +601,69 @@ use serde_untagged::{de::Error as UntaggedError, UntaggedEnumVisitor};
 /// ```
 pub use serde_env_field_wrap::env_field_wrap;
 
+/// Makes the fields of a struct or an enum expand the environment variables
+/// while keeping their original types.
+///
+/// Unlike the [`env_field_wrap`], which changes the field types,
+/// this attribute adds the `#[serde(deserialize_with = "...")]` to each field,
+/// so the `EnvField` doesn't leak into the public API of the item.
+/// The fields are deserialized as if they were wrapped by the `env_field_wrap`
+/// and are unwrapped afterward.
+/// The optional, vector, tuple, and `Result` fields are honored the same way.
+///
+/// The serialization isn't affected.
+///
+/// A field can be left as is using the `#[serde_env_field(skip)]` attribute.
+/// The `#[serde_env_field(default_expand = "...")]` attribute works the same way
+/// as the one of the `env_field_wrap`.
+/// The `#[serde_env_field(rename_env = "VAR")]` attribute binds the field to the `VAR` variable
+/// when the item is deserialized via the [`from_env`], instead of the variable derived
+/// from the prefix and the serde name of the field. The prefix isn't prepended to `VAR`.
+/// The documents still use the serde name of the field.
+/// To override the document values with a variable, use the `env` option of the `env_field_wrap`.
+/// The `#[serde_env_field(default = CONST)]` attribute takes a [`StaticTemplate`] constant instead,
+/// constructing the absent field from the template with the expansion strategy of the constant,
+/// so the same default can be reused across the items.
+/// An optional field marked with the `#[serde_env_field(skip_if_empty)]` attribute
+/// is `None` if its string expands to nothing, e.g., `"$VAR"` with the `VAR` set to an empty string.
+/// The fields with their own `#[serde(with = "...")]` or `#[serde(deserialize_with = "...")]`
+/// are left as is too.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::serde_env_field;
+/// #[serde_env_field]
+/// #[derive(Serialize, Deserialize)]
+/// pub struct Example {
+///     pub name: String,
+///     pub port: u16,
+///     pub timeout: Option<u32>,
+///
+///     #[serde_env_field(skip)]
+///     pub pattern: String,
+/// }
+///
+/// std::env::set_var("EXAMPLE_PORT", "8080");
+/// let de: Example = toml::from_str(r#"
+///     name = "${EXAMPLE_NAME:-example}"
+///     port = "$EXAMPLE_PORT"
+///     pattern = "$HOME/*.log"
+/// "#).unwrap();
+///
+/// // The fields are plain values
+/// let port: u16 = de.port;
+///
+/// assert_eq!(de.name, "example");
+/// assert_eq!(port, 8080);
+/// assert!(de.timeout.is_none());
+/// assert_eq!(de.pattern, "$HOME/*.log");
+/// ```
+pub use serde_env_field_wrap::serde_env_field;
+
 /// A field that deserializes either as `T` or as `String`
-/// with all environment variables expanded via the [`shellexpand`] crate.
+/// with all environment variables expanded.
 ///
 /// By default, it requires `T` to implement the `FromStr` trait
 /// for deserialization from `String` after environment variables expansion.
@@ -319,10 +671,37 @@ pub use serde_env_field_wrap::env_field_wrap;
 /// You can use the [`UseDeserialize`] to bypass the `FromStr` and deserialize the `T`
 /// directly from the string with all environment variables expanded.
 ///
+/// The following expansion forms are supported:
+/// * `$VAR` and `${VAR}` expand to the value of the variable. It is an error if the variable is unset.
+/// * `${VAR:-default}` expands to the `default` if the variable is unset or empty.
+/// * `${VAR-default}` expands to the `default` only if the variable is unset.
+/// * `$$` expands to the literal `$`.
+///
 /// The `EnvField` serializes transparently as the `T` type if the `T` is serializable.
 ///
+/// The third type parameter controls the data representation.
+/// By default, it is the [`Transparent`] representation described above.
+/// The [`AlwaysString`] representation accepts only strings and always serializes as a string.
+///
+/// With the `schemars` feature enabled, the `EnvField` implements the `JsonSchema` trait.
+/// The generated schema accepts either the `T` schema or a string with environment variables.
+///
+/// The `half`, the `num-bigint`, the `uuid`, and the `semver` features enable the `serde` support
+/// of the respective crates, so the `EnvField<half::f16>`, the `EnvField<num_bigint::BigInt>`,
+/// the `EnvField<uuid::Uuid>`, and the `EnvField<semver::Version>` (or `EnvField<semver::VersionReq>`)
+/// can be used.
+/// The numbers these types can't be deserialized from directly (e.g., an integer for the `f16`)
+/// are parsed using the [`FromStr`] trait instead.
+///
+/// If the deserializer is not human-readable (e.g., `bincode`),
+/// no expansion is performed, and the `T` type is deserialized directly.
+///
 /// Works nicely with `Option`, `Vec`, and `#[serde(default)]`.
 ///
+/// The `EnvField` can be used as a map key, e.g., `HashMap<EnvField<String>, EnvField<String>>`
+/// (or the [`EnvFieldMap<String, String>`] for short), so both the keys and the values get expanded.
+/// It works for the maps capturing the unknown fields via `#[serde(flatten)]` as well.
+///
 /// Note: if you want to wrap all the fields of a struct or an enum
 /// with the `EnvField`, you might want to use the [`env_field_wrap`] attribute.
 ///
@@ -415,6 +794,58 @@ pub use serde_env_field_wrap::env_field_wrap;
 ///
 /// ```
 ///
+/// #### Tuples
+///
+/// A tuple doesn't implement the `FromStr`, and deserializing it via the [`UseDeserialize`]
+/// won't expand its string elements. Instead, wrap each element of the tuple.
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::EnvField;
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     pair: (EnvField<String>, EnvField<i32>),
+/// }
+///
+/// std::env::set_var("PAIR_NAME", "env name");
+/// let de: Example = toml::from_str(r#"
+///     pair = ["$PAIR_NAME", 42]
+/// "#).unwrap();
+///
+/// assert_eq!(&de.pair.0, "env name");
+/// assert_eq!(de.pair.1, 42);
+///
+/// ```
+///
+/// #### Optional values
+///
+/// The `Option<T>` doesn't implement the `FromStr`, so prefer the `Option<EnvField<T>>`
+/// over the `EnvField<Option<T>>`.
+///
+/// The `EnvField<Option<T>, UseDeserialize>` is supported, though.
+/// It deserializes a `null` as `None`, and any other value as `Some`,
+/// e.g., a string with all environment variables expanded.
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseDeserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     preferred: Option<EnvField<i32>>,
+///     supported: EnvField<Option<String>, UseDeserialize>,
+/// }
+///
+/// std::env::set_var("OPTIONAL_NUM", "42");
+/// let de: Example = serde_json::from_str(r#"{
+///     "preferred": "$OPTIONAL_NUM",
+///     "supported": null
+/// }"#).unwrap();
+///
+/// assert_eq!(de.preferred.unwrap(), 42);
+/// assert!(de.supported.is_none());
+///
+/// ```
+///
 /// #### Defaults
 ///
 /// ```
@@ -551,7 +982,38 @@ pub use serde_env_field_wrap::env_field_wrap;
 /// ```
 ///
 #[repr(transparent)]
-pub struct EnvField<T, Variant = UseFromStr>(T, PhantomData<Variant>);
+pub struct EnvField<T, Variant = UseFromStr, Repr = Transparent>(T, PhantomData<(Variant, Repr)>);
+
+/// A map expanding the environment variables in both its keys and its values.
+///
+/// It is a shorthand for the `HashMap<EnvField<K>, EnvField<V>>`,
+/// handy for the dynamically-keyed configs, e.g., the unknown fields captured via `#[serde(flatten)]`.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use serde_env_field::{EnvField, EnvFieldMap};
+/// #[derive(Deserialize)]
+/// struct Example {
+///     name: EnvField<String>,
+///
+///     #[serde(flatten)]
+///     ports: EnvFieldMap<String, u16>,
+/// }
+///
+/// std::env::set_var("EXAMPLE_SERVICE", "api");
+/// std::env::set_var("EXAMPLE_PORT", "8080");
+/// let de: Example = toml::from_str(r#"
+///     name = "gateway"
+///     "$EXAMPLE_SERVICE" = "$EXAMPLE_PORT"
+///     "${EXAMPLE_ADMIN:-admin}" = 9090
+/// "#).unwrap();
+///
+/// assert_eq!(de.ports["api"], 8080);
+/// assert_eq!(de.ports["admin"], 9090);
+/// ```
+pub type EnvFieldMap<K, V> = HashMap<EnvField<K>, EnvField<V>>;
 
 /// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
 ///
@@ -567,6 +1029,12 @@ pub struct UseFromStr;
 /// after the environment variables expansion.
 /// I.e., the `T` will be deserialized directly from the string with all environment variables expanded.
 ///
+/// If the supplied data is a sequence or a map (e.g., a TOML inline table),
+/// the environment variables are expanded in all the nested string values.
+/// The map keys stay untouched.
+/// This works at any depth, so dynamic values like the `serde_json::Value`
+/// get every string leaf expanded.
+///
 /// ### Example
 ///
 /// ```
@@ -597,7 +1065,145 @@ pub struct UseFromStr;
 /// ```
 pub struct UseDeserialize;
 
-impl<T: Serialize, V> Serialize for EnvField<T, V> {
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
+///
+/// Same as the [`UseFromStr`], but the parsing errors are formatted using the [`Debug`] trait.
+/// It allows using the types whose [`FromStr::Err`] doesn't implement the [`Display`](fmt::Display).
+///
+/// ### Example
+///
+/// ```
+/// # use std::str::FromStr;
+/// # use serde::Deserialize;
+/// # use serde_env_field::{EnvField, UseFromStrDebug};
+/// #[derive(Deserialize)]
+/// struct Example {
+///     level: EnvField<Level, UseFromStrDebug>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Level(u8);
+///
+/// #[derive(Debug)]
+/// struct LevelError;
+///
+/// impl FromStr for Level {
+///     type Err = LevelError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         s.strip_prefix('L')
+///             .and_then(|level| level.parse().ok())
+///             .map(Level)
+///             .ok_or(LevelError)
+///     }
+/// }
+///
+/// std::env::set_var("LOG_LEVEL", "L3");
+/// let de: Example = toml::from_str(r#"
+///     level = "$LOG_LEVEL"
+/// "#).unwrap();
+/// assert_eq!(de.level.0, 3);
+///
+/// let err = toml::from_str::<Example>(r#"
+///     level = "3"
+/// "#).err().unwrap();
+/// assert!(err.message().contains("invalid value '3': LevelError"));
+/// ```
+pub struct UseFromStrDebug;
+
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
+///
+/// The `EnvField` will use the [`FromExpandedStr`] trait instead of the [`FromStr`]
+/// for constructing the `T` type after the environment variables expansion.
+/// It lets a type interpret the expanded string its own way
+/// while keeping its `FromStr` implementation for the other uses.
+/// The error message returned by the `T` is reported along with the expanded string.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use serde_env_field::{EnvField, FromExpandedStr, UseFromExpanded};
+/// #[derive(Deserialize)]
+/// struct Example {
+///     endpoint: EnvField<Endpoint, UseFromExpanded>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Endpoint {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// impl FromExpandedStr for Endpoint {
+///     fn from_expanded(expanded: &str) -> Result<Self, String> {
+///         let (host, port) = expanded.rsplit_once(':').ok_or("no port")?;
+///         let port = port.parse().map_err(|err| format!("invalid port: {err}"))?;
+///
+///         Ok(Self {
+///             host: host.to_owned(),
+///             port,
+///         })
+///     }
+/// }
+///
+/// std::env::set_var("ENDPOINT_HOST", "example.com");
+/// let de: Example = toml::from_str(r#"
+///     endpoint = "$ENDPOINT_HOST:8080"
+/// "#).unwrap();
+/// assert_eq!(de.endpoint.host, "example.com");
+/// assert_eq!(de.endpoint.port, 8080);
+///
+/// let err = toml::from_str::<Example>(r#"
+///     endpoint = "$ENDPOINT_HOST"
+/// "#).err().unwrap();
+/// assert!(err.message().contains("invalid value 'example.com': no port"));
+/// ```
+pub struct UseFromExpanded;
+
+/// A marker type for passing into the [`EnvField<Result<T, String>>`](EnvField) type as a second parameter.
+///
+/// Same as the [`UseFromStr`], but the parsing errors don't fail the deserialization.
+/// Instead, the field holds the `Err` with the error message,
+/// so the application decides how to handle the invalid value.
+///
+/// The environment variables lookup errors still fail the deserialization.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use serde_env_field::{EnvField, UseLenient};
+/// #[derive(Deserialize)]
+/// struct Example {
+///     workers: EnvField<Result<u8, String>, UseLenient>,
+///     timeout: EnvField<Result<u32, String>, UseLenient>,
+/// }
+///
+/// std::env::set_var("LENIENT_WORKERS", "many");
+/// let de: Example = toml::from_str(r#"
+///     workers = "$LENIENT_WORKERS"
+///     timeout = 30
+/// "#).unwrap();
+///
+/// assert_eq!(
+///     de.workers.as_ref().unwrap_err(),
+///     "invalid value 'many': invalid digit found in string"
+/// );
+/// assert_eq!(*de.timeout, Ok(30));
+/// ```
+pub struct UseLenient;
+
+/// A marker type for passing into the [`EnvField<T>`] type as a third parameter.
+///
+/// The `EnvField` will accept either a string with environment variables
+/// or any data the `T` type can be deserialized from directly.
+/// It will serialize transparently as the `T` type.
+///
+/// This is the default for the `EnvField`.
+pub struct Transparent;
+
+impl<T: Serialize, V> Serialize for EnvField<T, V, Transparent> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
@@ -606,130 +1212,783 @@ impl<T: Serialize, V> Serialize for EnvField<T, V> {
     }
 }
 
-impl<T, V> EnvField<T, V> {
+impl<T, V, R> EnvField<T, V, R> {
+    /// Wraps the value into the env field.
+    ///
+    /// Unlike the `From<T>` conversion, it can be used in const contexts.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvField;
+    /// const DEFAULT_PORT: EnvField<u16> = EnvField::new(8080);
+    ///
+    /// assert_eq!(DEFAULT_PORT, 8080);
+    /// ```
+    pub const fn new(value: T) -> Self {
+        Self(value, PhantomData)
+    }
+
     /// Unwraps the value, consuming the env field.
     pub fn into_inner(self) -> T {
         self.0
     }
+
+    /// Clones the value out of the borrowed env field.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvField;
+    /// fn hosts(configs: &[EnvField<String>]) -> Vec<String> {
+    ///     configs.iter().map(EnvField::cloned_inner).collect()
+    /// }
+    ///
+    /// let configs: Vec<EnvField<String>> = vec!["a.example".to_string().into()];
+    /// assert_eq!(hosts(&configs), ["a.example"]);
+    /// ```
+    pub fn cloned_inner(&self) -> T
+    where
+        T: Clone,
+    {
+        self.0.clone()
+    }
+
+    /// Moves the value into an [`Arc`], e.g., to share it between the worker threads.
+    ///
+    /// ```
+    /// # use std::{sync::Arc, thread};
+    /// # use serde_env_field::EnvField;
+    /// let hosts: EnvField<Vec<String>> = vec!["localhost".to_string()].into();
+    /// let hosts: Arc<Vec<String>> = hosts.into_arc();
+    ///
+    /// let worker = thread::spawn({
+    ///     let hosts = hosts.clone();
+    ///     move || hosts.len()
+    /// });
+    ///
+    /// assert_eq!(worker.join().unwrap(), 1);
+    /// assert_eq!(hosts[0], "localhost");
+    /// ```
+    pub fn into_arc(self) -> Arc<T> {
+        Arc::new(self.0)
+    }
+
+    /// Converts the env field into an owned [`Cow`].
+    ///
+    /// ```
+    /// # use std::borrow::Cow;
+    /// # use serde_env_field::EnvField;
+    /// fn describe(name: Cow<'_, String>) -> String {
+    ///     format!("name: {name}")
+    /// }
+    ///
+    /// let name: EnvField<String> = "example".to_string().into();
+    /// assert!(matches!(name.clone().into_cow(), Cow::Owned(_)));
+    /// assert_eq!(describe(name.into_cow()), "name: example");
+    /// ```
+    pub fn into_cow<'a>(self) -> Cow<'a, T>
+    where
+        T: Clone,
+    {
+        Cow::Owned(self.0)
+    }
+
+    /// Unwraps the value into a `Some`.
+    ///
+    /// It composes the present env field with the code working on the optional values.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvField;
+    /// fn timeout(configured: Option<u32>, fallback: Option<EnvField<u32>>) -> Option<u32> {
+    ///     configured.or_else(|| fallback?.some())
+    /// }
+    ///
+    /// assert_eq!(timeout(None, Some(30.into())), Some(30));
+    /// assert_eq!(timeout(Some(5), Some(30.into())), Some(5));
+    /// assert_eq!(timeout(None, None), None);
+    /// ```
+    pub fn some(self) -> Option<T> {
+        Some(self.0)
+    }
+
+    /// Unwraps the value into an `Ok`.
+    ///
+    /// The error is never produced: it only names the error type,
+    /// so the env field composes with the `?`-based validation code.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvField;
+    /// fn validate(workers: EnvField<u32>) -> Result<u32, String> {
+    ///     let workers = workers.ok_or(String::new())?;
+    ///     if workers == 0 {
+    ///         return Err("at least one worker is required".to_string());
+    ///     }
+    ///
+    ///     Ok(workers)
+    /// }
+    ///
+    /// assert_eq!(validate(4.into()), Ok(4));
+    /// assert!(validate(0.into()).is_err());
+    /// ```
+    pub fn ok_or<E>(self, _err: E) -> Result<T, E> {
+        Ok(self.0)
+    }
+
+    /// Passes the value to the function producing another env field.
+    ///
+    /// It allows chaining the transformations that keep the value wrapped.
+    ///
+    /// ```
+    /// # use serde_env_field::{EnvField, UseDeserialize};
+    /// fn parse_port(s: String) -> EnvField<u16> {
+    ///     s.trim_start_matches(':').parse().unwrap()
+    /// }
+    ///
+    /// fn to_address(port: u16) -> EnvField<String, UseDeserialize> {
+    ///     format!("localhost:{port}").into()
+    /// }
+    ///
+    /// let port: EnvField<String> = ":8080".to_string().into();
+    /// let address = port.and_then(parse_port).and_then(to_address);
+    ///
+    /// assert_eq!(address.as_str(), "localhost:8080");
+    /// ```
+    pub fn and_then<U, W, Q>(self, f: impl FnOnce(T) -> EnvField<U, W, Q>) -> EnvField<U, W, Q> {
+        f(self.0)
+    }
+}
+
+impl<T: Deref, V, R> EnvField<Option<T>, V, R> {
+    /// Converts from `&EnvField<Option<T>>` to `Option<&T::Target>`.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvField;
+    /// let name: EnvField<Option<String>> = Some("name".to_string()).into();
+    /// assert_eq!(name.as_deref(), Some("name"));
+    ///
+    /// let name: EnvField<Option<String>> = None.into();
+    /// assert_eq!(name.as_deref(), None);
+    /// ```
+    pub fn as_deref(&self) -> Option<&T::Target> {
+        self.0.as_deref()
+    }
+}
+
+impl<T: ?Sized, V, R> EnvField<Box<T>, V, R> {
+    /// Converts from `&EnvField<Box<T>>` to `&T`, skipping the double dereference.
+    ///
+    /// It comes in handy for the recursive config structures.
+    ///
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::{EnvField, UseDeserialize};
+    /// #[derive(Deserialize)]
+    /// struct Node {
+    ///     name: String,
+    ///     next: Option<EnvField<Box<Node>, UseDeserialize>>,
+    /// }
+    ///
+    /// let node: Node = serde_json::from_str(r#"{
+    ///     "name": "first",
+    ///     "next": { "name": "second", "next": null }
+    /// }"#).unwrap();
+    ///
+    /// let next: &Node = node.next.as_ref().unwrap().as_inner();
+    /// assert_eq!(next.name, "second");
+    /// ```
+    pub fn as_inner(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, E, V, R> EnvField<Result<T, E>, V, R> {
+    /// Unwraps the stored result, consuming the env field.
+    ///
+    /// It comes in handy with the [`UseLenient`] fields
+    /// to handle the invalid values after the deserialization.
+    ///
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::{EnvField, UseLenient};
+    /// #[derive(Deserialize)]
+    /// struct Example {
+    ///     workers: EnvField<Result<u8, String>, UseLenient>,
+    /// }
+    ///
+    /// std::env::set_var("INTO_RESULT_WORKERS", "many");
+    /// let de: Example = toml::from_str(r#"workers = "$INTO_RESULT_WORKERS""#).unwrap();
+    ///
+    /// let workers = de.workers.into_result().unwrap_or_else(|err| {
+    ///     assert_eq!(err, "invalid value 'many': invalid digit found in string");
+    ///     4
+    /// });
+    /// assert_eq!(workers, 4);
+    /// ```
+    pub fn into_result(self) -> Result<T, E> {
+        self.0
+    }
+}
+
+impl<V, R> EnvField<String, V, R> {
+    /// Extracts a string slice containing the entire string.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvField;
+    /// let name: EnvField<String> = "name".to_string().into();
+    /// assert_eq!(name.as_str(), "name");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+/// The scoped settings of the environment variables expansion.
+///
+/// The settings apply only to the current thread and only within the supplied closure.
+/// The threads spawned within the closure don't inherit them,
+/// and the other threads are never affected.
+///
+/// The scopes can be nested: the inner scope overrides the setting,
+/// and the outer value is restored when the inner closure returns or panics.
+impl EnvField<()> {
+    /// Runs the closure, collecting the environment variables lookup errors
+    /// instead of failing on the first one.
+    ///
+    /// Within the closure, a variable that can't be looked up is expanded to an empty string,
+    /// so the expansion continues, and all the missing variables get reported at once.
+    ///
+    /// Note that the deserialization can still fail if a field can't be constructed
+    /// from the resulting string (e.g., an empty string isn't a number).
+    /// In that case, the collected errors contain the variables referenced before the failure.
+    ///
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::{EnvField, EnvFieldError};
+    /// #[derive(Deserialize)]
+    /// struct Example {
+    ///     user: EnvField<String>,
+    ///     password: EnvField<String>,
+    /// }
+    ///
+    /// let (result, errors) = EnvField::collect_errors(|| {
+    ///     toml::from_str::<Example>(r#"
+    ///         user = "$DB_USER"
+    ///         password = "$DB_PASSWORD"
+    ///     "#)
+    /// });
+    ///
+    /// assert!(result.is_ok());
+    ///
+    /// let missing_vars = errors
+    ///     .iter()
+    ///     .map(|err| match err {
+    ///         EnvFieldError::Lookup { var_name, .. } => var_name.as_str(),
+    ///         _ => unreachable!(),
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(missing_vars, ["DB_USER", "DB_PASSWORD"]);
+    /// ```
+    pub fn collect_errors<R>(f: impl FnOnce() -> R) -> (R, Vec<EnvFieldError>) {
+        let (result, errors) =
+            context::scoped(|context| &mut context.collected_errors, Some(vec![]), f);
+
+        (result, errors.unwrap_or_default())
+    }
+
+    /// Runs the closure, redacting the values from the error messages.
+    ///
+    /// By default, the errors include the offending strings, which might leak secrets into logs.
+    /// Within the closure, the expanded strings and the environment variable values
+    /// are replaced with `***` in the error messages.
+    ///
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::EnvField;
+    /// #[derive(Deserialize, Debug)]
+    /// struct Example {
+    ///     port: EnvField<u16>,
+    /// }
+    ///
+    /// std::env::set_var("EXAMPLE_PORT", "secret");
+    /// let err = EnvField::redact_values(|| {
+    ///     toml::from_str::<Example>(r#"port = "$EXAMPLE_PORT""#)
+    /// })
+    /// .unwrap_err();
+    ///
+    /// assert!(err.message().contains("invalid value '***'"));
+    /// assert!(!err.message().contains("secret"));
+    /// ```
+    pub fn redact_values<R>(f: impl FnOnce() -> R) -> R {
+        context::scoped(|context| &mut context.redact_values, true, f).0
+    }
+
+    /// Runs the closure, reporting all the missing variables of a string at once.
+    ///
+    /// By default, the expansion of a string like `"$A/$B/$C"` stops at the first variable
+    /// that can't be looked up. Within the closure, if several variables of the same string
+    /// can't be looked up, they are all reported by the [`EnvFieldError::MultipleLookups`] error.
+    ///
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::EnvField;
+    /// #[derive(Deserialize)]
+    /// struct Example {
+    ///     url: EnvField<String>,
+    /// }
+    ///
+    /// std::env::set_var("EXAMPLE_HOST", "localhost");
+    /// let err = EnvField::report_all_missing(|| {
+    ///     toml::from_str::<Example>(r#"url = "$EXAMPLE_SCHEME://$EXAMPLE_HOST:$EXAMPLE_PORT""#)
+    /// })
+    /// .err()
+    /// .unwrap();
+    ///
+    /// assert!(err.message().contains("'EXAMPLE_SCHEME'"));
+    /// assert!(err.message().contains("'EXAMPLE_PORT'"));
+    /// ```
+    pub fn report_all_missing<R>(f: impl FnOnce() -> R) -> R {
+        context::scoped(|context| &mut context.report_all_missing, true, f).0
+    }
+
+    /// Runs the closure, expanding the environment variables using a snapshot
+    /// of the process environment taken at the start.
+    ///
+    /// It gives a consistent view of the environment for the whole deserialization,
+    /// unaffected by the concurrent changes of the process environment.
+    ///
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::EnvField;
+    /// #[derive(Deserialize)]
+    /// struct Example {
+    ///     mode: EnvField<String>,
+    /// }
+    ///
+    /// std::env::set_var("EXAMPLE_MODE", "fast");
+    /// let de: Example = EnvField::with_env_snapshot(|| {
+    ///     std::env::set_var("EXAMPLE_MODE", "slow");
+    ///     toml::from_str(r#"mode = "$EXAMPLE_MODE""#)
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(&de.mode, "fast");
+    /// ```
+    pub fn with_env_snapshot<R>(f: impl FnOnce() -> R) -> R {
+        let snapshot = std::env::vars_os().collect();
+        context::scoped(|context| &mut context.env_snapshot, Some(snapshot), f).0
+    }
+
+    /// Runs the closure, expanding the `$0`, `$1`, etc. references
+    /// into the positional arguments with the corresponding indices.
+    ///
+    /// It comes in handy for the command templates.
+    /// The other references are still expanded from the environment.
+    /// It is an error to reference an argument beyond the provided ones,
+    /// unless the reference has a default.
+    ///
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::EnvField;
+    /// #[derive(Deserialize)]
+    /// struct Example {
+    ///     command: EnvField<String>,
+    /// }
+    ///
+    /// let args = ["cp".to_string(), "a.txt".to_string(), "b.txt".to_string()];
+    /// let example: Example = EnvField::with_positional(&args, || {
+    ///     toml::from_str(r#"command = "$0 -v $1 ${2}""#)
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(&example.command, "cp -v a.txt b.txt");
+    /// ```
+    pub fn with_positional<R>(args: &[String], f: impl FnOnce() -> R) -> R {
+        context::scoped(|context| &mut context.positional, Some(args.to_vec()), f).0
+    }
+
+    /// Runs the closure, notifying the observer about each environment variable
+    /// referenced by the expanded strings and how it was [resolved](Resolution).
+    ///
+    /// This helps to spot the configs that silently run on the defaults.
+    ///
+    /// ```
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::{EnvField, Resolution};
+    /// #[derive(Deserialize)]
+    /// struct Example {
+    ///     host: EnvField<String>,
+    /// }
+    ///
+    /// let defaults = Rc::new(RefCell::new(vec![]));
+    /// let observed = defaults.clone();
+    ///
+    /// std::env::remove_var("EXAMPLE_HOST");
+    /// let example: Example = EnvField::observe_resolutions(
+    ///     move |var_name, resolution| {
+    ///         if resolution == Resolution::DefaultUsed {
+    ///             observed.borrow_mut().push(var_name.to_owned());
+    ///         }
+    ///     },
+    ///     || toml::from_str(r#"host = "${EXAMPLE_HOST:-localhost}""#),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(&example.host, "localhost");
+    /// assert_eq!(*defaults.borrow(), ["EXAMPLE_HOST"]);
+    /// ```
+    pub fn observe_resolutions<R>(
+        observer: impl FnMut(&str, Resolution) + 'static,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        let observer: context::Observer = Box::new(observer);
+        let (result, _) = context::scoped(|context| &mut context.observer, Some(observer), f);
+
+        result
+    }
+
+    /// Runs the closure, notifying the hook about the length in bytes
+    /// of each string resolved during the expansion.
+    ///
+    /// Only the lengths are reported, never the values,
+    /// so the hook may feed the metrics without leaking the secrets.
+    ///
+    /// ```
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// # use serde::Deserialize;
+    /// # use serde_env_field::EnvField;
+    /// #[derive(Deserialize)]
+    /// struct Example {
+    ///     token: EnvField<String>,
+    /// }
+    ///
+    /// let lengths = Rc::new(RefCell::new(vec![]));
+    /// let recorded = lengths.clone();
+    ///
+    /// std::env::set_var("EXAMPLE_TOKEN", "s3cr3t");
+    /// let example: Example = EnvField::with_metrics(
+    ///     move |len| recorded.borrow_mut().push(len),
+    ///     || toml::from_str(r#"token = "$EXAMPLE_TOKEN""#),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(&example.token, "s3cr3t");
+    /// assert_eq!(*lengths.borrow(), [6]);
+    /// ```
+    pub fn with_metrics<R>(hook: impl FnMut(usize) + 'static, f: impl FnOnce() -> R) -> R {
+        let hook: context::MetricsHook = Box::new(hook);
+        let (result, _) = context::scoped(|context| &mut context.metrics, Some(hook), f);
+
+        result
+    }
 }
 
-impl<T> EnvField<T, UseFromStr>
+impl<T, R> EnvField<T, UseFromStr, R>
 where
     T: FromStr,
     <T as FromStr>::Err: fmt::Display,
 {
-    fn env_expand_and_parse(str_data: &str) -> Result<Self, UntaggedError> {
-        match shellexpand::env(&str_data) {
-            Ok(expanded) => expanded
-                .parse()
-                .map(|v| Self(v, PhantomData))
-                .map_err(Error::custom),
-            Err(err) => Err(Error::custom(err)),
+    /// Reads the environment variable and parses its value.
+    ///
+    /// The value is taken literally, i.e., it isn't expanded.
+    /// It is an error if the variable is unset.
+    ///
+    /// ```
+    /// # use serde_env_field::{EnvField, EnvFieldError};
+    /// std::env::set_var("WORKERS", "4");
+    /// let workers: EnvField<usize> = EnvField::from_env_var("WORKERS").unwrap();
+    /// assert_eq!(workers, 4);
+    ///
+    /// std::env::remove_var("WORKERS");
+    /// assert!(matches!(
+    ///     EnvField::<usize>::from_env_var("WORKERS"),
+    ///     Err(EnvFieldError::Lookup { .. }),
+    /// ));
+    /// ```
+    pub fn from_env_var(name: &str) -> Result<Self, EnvFieldError> {
+        let value = context::var(name).map_err(|cause| EnvFieldError::Lookup {
+            var_name: name.to_owned(),
+            cause,
+            suggestion: None,
+        })?;
+
+        Self::parse_env_var(name, &value)
+    }
+
+    /// Reads the environment variable and parses its value,
+    /// using the default if the variable is unset.
+    ///
+    /// The value is taken literally, i.e., it isn't expanded.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvField;
+    /// std::env::remove_var("WORKERS");
+    /// let workers: EnvField<usize> = EnvField::from_env_var_or("WORKERS", 1).unwrap();
+    /// assert_eq!(workers, 1);
+    /// ```
+    pub fn from_env_var_or(name: &str, default: T) -> Result<Self, EnvFieldError> {
+        match context::var(name) {
+            Ok(value) => Self::parse_env_var(name, &value),
+            Err(std::env::VarError::NotPresent) => Ok(Self::new(default)),
+            Err(cause) => Err(EnvFieldError::Lookup {
+                var_name: name.to_owned(),
+                cause,
+                suggestion: None,
+            }),
         }
     }
+
+    fn parse_env_var(name: &str, value: &str) -> Result<Self, EnvFieldError> {
+        value.parse().map(Self::new).map_err(|err| {
+            redacted(
+                EnvFieldError::Parse(format!(
+                    "invalid value '{}' of the '{name}' variable: {err}",
+                    truncated(value)
+                )),
+                value,
+            )
+        })
+    }
 }
 
-impl<'de, T> EnvField<T, UseDeserialize>
+impl<'de, T, V, R> EnvField<T, V, R>
 where
-    T: Deserialize<'de>,
+    V: ExpandStrategy<'de, T>,
 {
-    fn env_expand_and_deserialize(str_data: &str) -> Result<Self, UntaggedError> {
-        match shellexpand::env(&str_data) {
-            Ok(expanded) => T::deserialize(StringDeserializer::new(expanded.into()))
-                .map(|v| Self(v, PhantomData)),
-            Err(err) => Err(Error::custom(err)),
-        }
+    /// Expands the environment variables in the string and constructs the `EnvField` from the result,
+    /// the same way the deserialization does.
+    ///
+    /// Unlike the [`FromStr`] implementation, which parses the string as is,
+    /// it can be used as a `clap` value parser to expand the variables in the command line arguments.
+    ///
+    /// ```
+    /// # use clap::Parser;
+    /// # use serde_env_field::EnvField;
+    /// #[derive(Parser)]
+    /// struct Cli {
+    ///     #[arg(long, value_parser = EnvField::<u16>::parse_expanded)]
+    ///     port: EnvField<u16>,
+    /// }
+    ///
+    /// std::env::set_var("CLI_PORT", "8080");
+    /// let cli = Cli::parse_from(["app", "--port", "$CLI_PORT"]);
+    ///
+    /// assert_eq!(cli.port, 8080);
+    /// ```
+    pub fn parse_expanded(s: &str) -> Result<Self, EnvFieldError> {
+        Self::env_expand_and_construct(s)
+    }
+
+    pub(crate) fn env_expand_and_construct(str_data: &str) -> Result<Self, EnvFieldError> {
+        let expanded = V::expand(str_data)?;
+        V::from_expanded(&expanded).map(Self::new)
     }
 }
 
-impl<T, V> From<T> for EnvField<T, V> {
+impl<T, V, R> From<T> for EnvField<T, V, R> {
     fn from(value: T) -> Self {
         Self(value, PhantomData)
     }
 }
 
-macro_rules! deserialize_value {
-    ($de:ident) => {
-        |v| T::deserialize(de::value::$de::new(v)).map(|v| Self(v, PhantomData))
-    };
-}
+/// The floats from this magnitude on can't hold all the integer digits.
+const MAX_EXACT_FLOAT_INT: f64 = (1u64 << f64::MANTISSA_DIGITS) as f64;
 
-impl<'de, T> Deserialize<'de> for EnvField<T, UseFromStr>
+impl<'de, T, V> Deserialize<'de> for EnvField<T, V>
 where
-    T: Deserialize<'de> + FromStr,
-    <T as FromStr>::Err: fmt::Display,
+    T: Deserialize<'de>,
+    V: ExpandStrategy<'de, T>,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        UntaggedEnumVisitor::new()
-            .string(Self::env_expand_and_parse)
-            .borrowed_str(Self::env_expand_and_parse)
-            .bool(deserialize_value!(BoolDeserializer))
-            .i8(deserialize_value!(I8Deserializer))
-            .i16(deserialize_value!(I16Deserializer))
-            .i32(deserialize_value!(I32Deserializer))
-            .i64(deserialize_value!(I64Deserializer))
-            .i128(deserialize_value!(I128Deserializer))
-            .u8(deserialize_value!(U8Deserializer))
-            .u16(deserialize_value!(U16Deserializer))
-            .u32(deserialize_value!(U32Deserializer))
-            .u64(deserialize_value!(U64Deserializer))
-            .u128(deserialize_value!(U128Deserializer))
-            .f32(deserialize_value!(F32Deserializer))
-            .f64(deserialize_value!(F64Deserializer))
-            .char(deserialize_value!(CharDeserializer))
-            .bytes(deserialize_value!(BytesDeserializer))
-            .borrowed_bytes(deserialize_value!(BorrowedBytesDeserializer))
-            .seq(|seq| seq.deserialize().map(|v| Self(v, PhantomData)))
-            .map(|map| map.deserialize().map(|v| Self(v, PhantomData)))
-            .deserialize(deserializer)
-    }
-}
-
-impl<'de, T> Deserialize<'de> for EnvField<T, UseDeserialize>
+        if !deserializer.is_human_readable() {
+            return T::deserialize(deserializer).map(Self::new);
+        }
+
+        deserializer.deserialize_any(EnvFieldVisitor(PhantomData))
+    }
+}
+
+/// The visitor of the human-readable data.
+///
+/// The strings are expanded, while the rest of the data is handed over to the `T`.
+///
+/// Unlike the `UntaggedEnumVisitor` used by the other markers,
+/// it doesn't set up the boxed handlers of all the data kinds for every value,
+/// which keeps the code small for the structs with many fields of distinct types.
+struct EnvFieldVisitor<T, V>(PhantomData<(T, V)>);
+
+impl<'de, T, V> EnvFieldVisitor<T, V>
 where
     T: Deserialize<'de>,
+    V: ExpandStrategy<'de, T>,
 {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    fn value<D>(deserializer: D) -> Result<EnvField<T, V>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(SomeDeserializer(deserializer)).map(EnvField::new)
+    }
+
+    /// Same as the `value`, but if the `T` doesn't accept the scalar directly,
+    /// the scalar is constructed from its string representation instead.
+    ///
+    /// E.g., the YAML plain scalars like `42` or `true` are still accepted by the strings.
+    fn scalar<D>(deserializer: D, scalar: impl fmt::Display) -> Result<EnvField<T, V>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Self::value(deserializer).or_else(|err| {
+            V::from_expanded(&scalar.to_string())
+                .map(EnvField::new)
+                .map_err(|_| err)
+        })
+    }
+
+    /// Same as the `scalar`, but the 128-bit integers aren't constructed
+    /// from the floats too large to hold all the integer digits.
+    ///
+    /// The formats without the 128-bit integers, e.g., JSON, hand such big integers over
+    /// as the floats, so the digits are already lost.
+    fn float<D, F>(deserializer: D, float: F) -> Result<EnvField<T, V>, D::Error>
     where
         D: serde::Deserializer<'de>,
+        F: Copy + Into<f64> + fmt::Display + fmt::LowerExp,
     {
+        if float.into().abs() < MAX_EXACT_FLOAT_INT {
+            return Self::scalar(deserializer, float);
+        }
+
+        Self::value(deserializer).or_else(|err| {
+            // Only the 128-bit integers accept the integers beyond the 64-bit range
+            let beyond_64_bit = u128::from(u64::MAX) + 1;
+            let beyond_64_bit = de::value::U128Deserializer::<de::value::Error>::new(beyond_64_bit);
+            if T::deserialize(beyond_64_bit).is_ok() {
+                return Err(D::Error::custom(format!(
+                    "the 128-bit integer was read as the float `{float:e}` losing its precision, \
+                    pass it as a string instead"
+                )));
+            }
+
+            V::from_expanded(&float.to_string())
+                .map(EnvField::new)
+                .map_err(|_| err)
+        })
+    }
+}
+
+macro_rules! visit_values {
+    ($($method:ident($ty:ty) => $de:ident)*) => {
+        $(
+            fn $method<E: Error>(self, v: $ty) -> Result<Self::Value, E> {
+                Self::value(de::value::$de::new(v))
+            }
+        )*
+    };
+}
+
+macro_rules! visit_scalars {
+    ($($method:ident($ty:ty) => $handler:ident($de:ident))*) => {
+        $(
+            fn $method<E: Error>(self, v: $ty) -> Result<Self::Value, E> {
+                Self::$handler(de::value::$de::new(v), v)
+            }
+        )*
+    };
+}
+
+impl<'de, T, V> de::Visitor<'de> for EnvFieldVisitor<T, V>
+where
+    T: Deserialize<'de>,
+    V: ExpandStrategy<'de, T>,
+{
+    type Value = EnvField<T, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(
+            "a boolean, integer, float, character, string, byte array, null, array or map",
+        )
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        EnvField::<T, V>::env_expand_and_construct(v).map_err(E::custom)
+    }
+
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        self.visit_str(v)
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        self.visit_str(&v)
+    }
+
+    visit_scalars! {
+        visit_bool(bool) => scalar(BoolDeserializer)
+        visit_i8(i8) => scalar(I8Deserializer)
+        visit_i16(i16) => scalar(I16Deserializer)
+        visit_i32(i32) => scalar(I32Deserializer)
+        visit_i64(i64) => scalar(I64Deserializer)
+        visit_i128(i128) => scalar(I128Deserializer)
+        visit_u8(u8) => scalar(U8Deserializer)
+        visit_u16(u16) => scalar(U16Deserializer)
+        visit_u32(u32) => scalar(U32Deserializer)
+        visit_u64(u64) => scalar(U64Deserializer)
+        visit_u128(u128) => scalar(U128Deserializer)
+        visit_f32(f32) => float(F32Deserializer)
+        visit_f64(f64) => float(F64Deserializer)
+    }
+
+    visit_values! {
+        visit_char(char) => CharDeserializer
+        visit_bytes(&[u8]) => BytesDeserializer
+        visit_borrowed_bytes(&'de [u8]) => BorrowedBytesDeserializer
+    }
+
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        self.visit_bytes(&v)
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        T::deserialize(UnitDeserializer::new()).map(EnvField::new)
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        T::deserialize(UnitDeserializer::new()).map(EnvField::new)
+    }
+
+    // The nested data is passed through the type-erased accessors of the `UntaggedEnumVisitor`,
+    // so the recursive types (e.g., the `EnvField<Box<Node>, UseDeserialize>` inside the `Node`)
+    // don't instantiate the visitor for ever-growing accessor types
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        UntaggedEnumVisitor::new()
+            .seq(|seq| V::from_nested(SeqAccessDeserializer::new(seq)).map(EnvField::new))
+            .visit_seq(seq)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
         UntaggedEnumVisitor::new()
-            .string(Self::env_expand_and_deserialize)
-            .borrowed_str(Self::env_expand_and_deserialize)
-            .bool(deserialize_value!(BoolDeserializer))
-            .i8(deserialize_value!(I8Deserializer))
-            .i16(deserialize_value!(I16Deserializer))
-            .i32(deserialize_value!(I32Deserializer))
-            .i64(deserialize_value!(I64Deserializer))
-            .i128(deserialize_value!(I128Deserializer))
-            .u8(deserialize_value!(U8Deserializer))
-            .u16(deserialize_value!(U16Deserializer))
-            .u32(deserialize_value!(U32Deserializer))
-            .u64(deserialize_value!(U64Deserializer))
-            .u128(deserialize_value!(U128Deserializer))
-            .f32(deserialize_value!(F32Deserializer))
-            .f64(deserialize_value!(F64Deserializer))
-            .char(deserialize_value!(CharDeserializer))
-            .bytes(deserialize_value!(BytesDeserializer))
-            .borrowed_bytes(deserialize_value!(BorrowedBytesDeserializer))
-            .seq(|seq| seq.deserialize().map(|v| Self(v, PhantomData)))
-            .map(|map| map.deserialize().map(|v| Self(v, PhantomData)))
-            .deserialize(deserializer)
-    }
-}
-
-impl<T: Clone, V> Clone for EnvField<T, V> {
+            .map(|map| V::from_nested(MapAccessDeserializer::new(map)).map(EnvField::new))
+            .visit_map(map)
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        // E.g., the YAML tagged values, handed over to the `T` the same way as the maps
+        V::from_nested(EnumAccessDeserializer::new(data)).map(EnvField::new)
+    }
+}
+
+impl<T: Clone, V, R> Clone for EnvField<T, V, R> {
     fn clone(&self) -> Self {
         Self(self.0.clone(), PhantomData)
     }
 }
 
-impl<T: Copy, V> Copy for EnvField<T, V> {}
+impl<T: Copy, V, R> Copy for EnvField<T, V, R> {}
 
-impl<T: FromStr, V> FromStr for EnvField<T, V> {
+/// Parses the `T` from the string as is, without expanding the environment variables.
+///
+/// Use the [`EnvField::parse_expanded`] to expand them, e.g., in the `clap` arguments.
+impl<T: FromStr, V, R> FromStr for EnvField<T, V, R> {
     type Err = T::Err;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -737,19 +1996,19 @@ impl<T: FromStr, V> FromStr for EnvField<T, V> {
     }
 }
 
-impl<T: Default, V> Default for EnvField<T, V> {
+impl<T: Default, V, R> Default for EnvField<T, V, R> {
     fn default() -> Self {
         Self(T::default(), PhantomData)
     }
 }
 
-impl<T: Debug, V> Debug for EnvField<T, V> {
+impl<T: Debug, V, R> Debug for EnvField<T, V, R> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
 }
 
-impl<T, V> Deref for EnvField<T, V> {
+impl<T, V, R> Deref for EnvField<T, V, R> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -757,53 +2016,93 @@ impl<T, V> Deref for EnvField<T, V> {
     }
 }
 
-impl<T, V> DerefMut for EnvField<T, V> {
+impl<T, V, R> DerefMut for EnvField<T, V, R> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
 
-impl<T: PartialEq, V> PartialEq<T> for EnvField<T, V> {
+impl<T: Hash, V, R> Hash for EnvField<T, V, R> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<T, V, R> Borrow<T> for EnvField<T, V, R> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<V, R> Borrow<str> for EnvField<String, V, R> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<T: PartialEq, V, R> PartialEq<T> for EnvField<T, V, R> {
     fn eq(&self, other: &T) -> bool {
         self.0.eq(other)
     }
 }
 
-impl<T: PartialEq<str>, V> PartialEq<str> for EnvField<T, V> {
+impl<T: PartialEq<str>, V, R> PartialEq<str> for EnvField<T, V, R> {
     fn eq(&self, other: &str) -> bool {
         self.0.eq(other)
     }
 }
 
-impl<T: PartialEq, V> PartialEq for EnvField<T, V> {
+impl<T: PartialEq, V, R> PartialEq for EnvField<T, V, R> {
     fn eq(&self, other: &Self) -> bool {
         self.0.eq(&other.0)
     }
 }
 
-impl<T: Eq, V> Eq for EnvField<T, V> {}
+impl<T: Eq, V, R> Eq for EnvField<T, V, R> {}
 
-impl<T: PartialOrd, V> PartialOrd<T> for EnvField<T, V> {
+impl<T: PartialOrd, V, R> PartialOrd<T> for EnvField<T, V, R> {
     fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering> {
         self.0.partial_cmp(other)
     }
 }
 
-impl<T: PartialOrd, V> PartialOrd for EnvField<T, V> {
+impl<T: PartialOrd, V, R> PartialOrd for EnvField<T, V, R> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         self.0.partial_cmp(&other.0)
     }
 }
 
-impl<T: Ord, V> Ord for EnvField<T, V> {
+impl<T: Ord, V, R> Ord for EnvField<T, V, R> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.0.cmp(&other.0)
     }
 }
 
+// The reverse comparisons can't be implemented generically,
+// so they are implemented for the common inner types only.
+macro_rules! impl_reverse_cmp {
+    ($($ty:ty),* $(,)?) => {$(
+        impl<V, R> PartialEq<EnvField<$ty, V, R>> for $ty {
+            fn eq(&self, other: &EnvField<$ty, V, R>) -> bool {
+                self.eq(&other.0)
+            }
+        }
+
+        impl<V, R> PartialOrd<EnvField<$ty, V, R>> for $ty {
+            fn partial_cmp(&self, other: &EnvField<$ty, V, R>) -> Option<std::cmp::Ordering> {
+                self.partial_cmp(&other.0)
+            }
+        }
+    )*};
+}
+
+impl_reverse_cmp!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char, String,
+);
+
 macro_rules! impl_unary_op {
     ($trait:ident, $method:ident) => {
-        impl<T: $trait, V> $trait for EnvField<T, V> {
+        impl<T: $trait, V, R> $trait for EnvField<T, V, R> {
             type Output = <T as $trait>::Output;
 
             fn $method(self) -> Self::Output {
@@ -815,7 +2114,7 @@ macro_rules! impl_unary_op {
 
 macro_rules! impl_binary_op {
     ($trait:ident, $method:ident) => {
-        impl<T: $trait, V> $trait<T> for EnvField<T, V> {
+        impl<T: $trait, V, R> $trait<T> for EnvField<T, V, R> {
             type Output = <T as $trait>::Output;
 
             fn $method(self, rhs: T) -> Self::Output {
@@ -823,7 +2122,7 @@ macro_rules! impl_binary_op {
             }
         }
 
-        impl<T: $trait, V> $trait for EnvField<T, V> {
+        impl<T: $trait, V, R> $trait for EnvField<T, V, R> {
             type Output = <T as $trait>::Output;
 
             fn $method(self, rhs: Self) -> Self::Output {
@@ -835,13 +2134,13 @@ macro_rules! impl_binary_op {
 
 macro_rules! impl_binary_assign_op {
     ($trait:ident, $method:ident) => {
-        impl<T: $trait, V> $trait<T> for EnvField<T, V> {
+        impl<T: $trait, V, R> $trait<T> for EnvField<T, V, R> {
             fn $method(&mut self, rhs: T) {
                 self.0.$method(rhs);
             }
         }
 
-        impl<T: $trait, V> $trait for EnvField<T, V> {
+        impl<T: $trait, V, R> $trait for EnvField<T, V, R> {
             fn $method(&mut self, rhs: Self) {
                 self.0.$method(rhs.0);
             }
@@ -849,6 +2148,32 @@ macro_rules! impl_binary_assign_op {
     };
 }
 
+// The reverse operators can't be implemented generically either,
+// so they are implemented for the primitive numeric types only.
+macro_rules! impl_reverse_binary_op {
+    ($trait:ident, $method:ident; $($ty:ty),* $(,)?) => {$(
+        impl<V, R> $trait<EnvField<$ty, V, R>> for $ty {
+            type Output = <$ty as $trait>::Output;
+
+            fn $method(self, rhs: EnvField<$ty, V, R>) -> Self::Output {
+                self.$method(rhs.0)
+            }
+        }
+    )*};
+    ($trait:ident, $method:ident) => {
+        impl_reverse_binary_op!(
+            $trait, $method;
+            i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64,
+        );
+    };
+    ($trait:ident, $method:ident, integers) => {
+        impl_reverse_binary_op!(
+            $trait, $method;
+            i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize,
+        );
+    };
+}
+
 impl_unary_op!(Neg, neg);
 impl_unary_op!(Not, not);
 
@@ -863,6 +2188,17 @@ impl_binary_op!(BitXor, bitxor);
 impl_binary_op!(Shl, shl);
 impl_binary_op!(Shr, shr);
 
+impl_reverse_binary_op!(Add, add);
+impl_reverse_binary_op!(Sub, sub);
+impl_reverse_binary_op!(Mul, mul);
+impl_reverse_binary_op!(Div, div);
+impl_reverse_binary_op!(Rem, rem);
+impl_reverse_binary_op!(BitAnd, bitand, integers);
+impl_reverse_binary_op!(BitOr, bitor, integers);
+impl_reverse_binary_op!(BitXor, bitxor, integers);
+impl_reverse_binary_op!(Shl, shl, integers);
+impl_reverse_binary_op!(Shr, shr, integers);
+
 impl_binary_assign_op!(AddAssign, add_assign);
 impl_binary_assign_op!(SubAssign, sub_assign);
 impl_binary_assign_op!(MulAssign, mul_assign);
@@ -873,3 +2209,19 @@ impl_binary_assign_op!(BitOrAssign, bitor_assign);
 impl_binary_assign_op!(BitXorAssign, bitxor_assign);
 impl_binary_assign_op!(ShlAssign, shl_assign);
 impl_binary_assign_op!(ShrAssign, shr_assign);
+
+// The `String` is concatenated with the string slices only,
+// so the generic operators above don't cover it.
+impl<V, R> Add<&str> for EnvField<String, V, R> {
+    type Output = String;
+
+    fn add(self, rhs: &str) -> Self::Output {
+        self.0 + rhs
+    }
+}
+
+impl<V, R> AddAssign<&str> for EnvField<String, V, R> {
+    fn add_assign(&mut self, rhs: &str) {
+        self.0.push_str(rhs);
+    }
+}
@@ -0,0 +1,65 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{context, expand, EnvFieldError, ExpandStrategy, UseFromStr};
+
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
+///
+/// Same as the [`UseFromStr`], but the strings are taken literally,
+/// i.e., the environment variables aren't expanded.
+///
+/// It keeps the field type an `EnvField` while disabling the expansion,
+/// e.g., via the `#[env_field_wrap(skip_expand)]` attribute.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, NoExpand};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     pattern: EnvField<String, NoExpand>,
+/// }
+///
+/// std::env::set_var("HOME", "/home/user");
+/// let de: Example = toml::from_str(r#"
+///     pattern = "$HOME/*.log"
+/// "#).unwrap();
+///
+/// assert_eq!(de.pattern.as_str(), "$HOME/*.log");
+/// ```
+pub struct NoExpand;
+
+impl<'de, T> ExpandStrategy<'de, T> for NoExpand
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    fn expand(str_data: &str) -> Result<Cow<'_, str>, EnvFieldError> {
+        context::scoped(
+            |context| &mut context.literal,
+            true,
+            || expand::env(str_data),
+        )
+        .0
+    }
+
+    fn from_expanded(expanded: &str) -> Result<T, EnvFieldError> {
+        UseFromStr::from_expanded(expanded)
+    }
+
+    fn from_nested<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        // The nested `EnvField`s take their strings literally too
+        context::scoped(
+            |context| &mut context.literal,
+            true,
+            || T::deserialize(deserializer),
+        )
+        .0
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{de::Visitor, forward_to_deserialize_any, Deserializer};
+
+/// A deserializer wrapper that treats the value as present
+/// if the `T` type asks for an optional value.
+///
+/// It makes the `EnvField<Option<T>, UseDeserialize>` accept strings and primitives
+/// in the same way as the `EnvField<T, UseDeserialize>`.
+pub(crate) struct SomeDeserializer<D>(pub(crate) D);
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for SomeDeserializer<D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self.0)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
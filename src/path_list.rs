@@ -0,0 +1,60 @@
+use std::{env, path::PathBuf};
+
+use crate::{EnvFieldError, ExpandStrategy};
+
+/// A marker type for passing into the [`EnvField<Vec<PathBuf>>`] type as a second parameter.
+///
+/// The `EnvField` will split the string with all environment variables expanded
+/// by the platform path separator (the colon on Unix and the semicolon on Windows),
+/// like the `PATH` variable is split by the [`env::split_paths`].
+///
+/// An empty string yields an empty vector.
+/// The empty segments follow the platform conventions:
+/// on Unix, an empty segment means the current directory and yields the `.` path,
+/// while on Windows, the empty segments are ignored.
+///
+/// If the supplied data was not a string, the `EnvField` will accept a sequence of paths.
+///
+/// ### Example
+///
+/// ```
+/// # use std::path::PathBuf;
+/// # use serde::Deserialize;
+/// # use serde_env_field::{EnvField, UsePathList};
+/// #[derive(Deserialize)]
+/// struct Example {
+///     plugin_dirs: EnvField<Vec<PathBuf>, UsePathList>,
+/// }
+///
+/// let dirs = std::env::join_paths(["/opt/plugins", "/usr/lib/plugins"]).unwrap();
+/// std::env::set_var("PLUGIN_DIRS", dirs);
+///
+/// let de: Example = toml::from_str(r#"
+///     plugin_dirs = "$PLUGIN_DIRS"
+/// "#).unwrap();
+///
+/// assert_eq!(*de.plugin_dirs, [PathBuf::from("/opt/plugins"), PathBuf::from("/usr/lib/plugins")]);
+/// ```
+pub struct UsePathList;
+
+impl ExpandStrategy<'_, Vec<PathBuf>> for UsePathList {
+    fn from_expanded(expanded: &str) -> Result<Vec<PathBuf>, EnvFieldError> {
+        if expanded.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let paths = env::split_paths(expanded)
+            .filter_map(|path| {
+                if !path.as_os_str().is_empty() {
+                    Some(path)
+                } else if cfg!(windows) {
+                    None
+                } else {
+                    Some(PathBuf::from("."))
+                }
+            })
+            .collect();
+
+        Ok(paths)
+    }
+}
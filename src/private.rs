@@ -0,0 +1,315 @@
+//! The items used by the code generated by the [`env_field_wrap`](crate::env_field_wrap)
+//! and the [`serde_env_field`](crate::serde_env_field).
+//! Not a public API.
+
+use std::{env::VarError, fmt};
+
+use serde::de::{value::StrDeserializer, Error, IgnoredAny};
+pub use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+pub use crate::expand::Trim;
+use crate::{
+    context, error::redacted, EnvField, EnvFieldError, ExpandStrategy, SomeDeserializer,
+    StaticTemplate,
+};
+
+/// Which value wins when both the document value and the environment variable are present.
+pub enum Precedence {
+    Env,
+    Document,
+}
+
+/// Deserializes the field, overriding it with the environment variable according to the precedence.
+pub fn deserialize_with_env<'de, D, F>(
+    deserializer: D,
+    var_name: &str,
+    precedence: Precedence,
+) -> Result<F, D::Error>
+where
+    D: Deserializer<'de>,
+    F: Deserialize<'de>,
+{
+    match precedence {
+        Precedence::Env => match env_value(var_name).map_err(D::Error::custom)? {
+            Some(value) => {
+                // The document value isn't expanded, so it can't fail the deserialization
+                IgnoredAny::deserialize(deserializer)?;
+                from_env_value(var_name, value).map_err(D::Error::custom)
+            }
+            None => F::deserialize(deserializer),
+        },
+        Precedence::Document => F::deserialize(deserializer),
+    }
+}
+
+/// Deserializes the `EnvField<T>` and converts the value into the field type.
+pub fn deserialize_try_into<'de, D, T, F>(deserializer: D) -> Result<F, D::Error>
+where
+    D: Deserializer<'de>,
+    EnvField<T>: Deserialize<'de>,
+    F: TryFrom<T>,
+    F::Error: fmt::Display,
+{
+    let value = EnvField::<T>::deserialize(deserializer)?.into_inner();
+    F::try_from(value).map_err(D::Error::custom)
+}
+
+/// Converts the type with the fields wrapped by the `EnvField` back into the original type.
+pub trait UnwrapEnvFields {
+    type Unwrapped;
+
+    fn unwrap_env_fields(self) -> Self::Unwrapped;
+}
+
+impl<T, V, R> UnwrapEnvFields for EnvField<T, V, R> {
+    type Unwrapped = T;
+
+    fn unwrap_env_fields(self) -> T {
+        self.into_inner()
+    }
+}
+
+impl<W: UnwrapEnvFields> UnwrapEnvFields for Option<W> {
+    type Unwrapped = Option<W::Unwrapped>;
+
+    fn unwrap_env_fields(self) -> Self::Unwrapped {
+        self.map(W::unwrap_env_fields)
+    }
+}
+
+impl<W: UnwrapEnvFields> UnwrapEnvFields for Vec<W> {
+    type Unwrapped = Vec<W::Unwrapped>;
+
+    fn unwrap_env_fields(self) -> Self::Unwrapped {
+        self.into_iter().map(W::unwrap_env_fields).collect()
+    }
+}
+
+impl<W: UnwrapEnvFields, E> UnwrapEnvFields for Result<W, E> {
+    type Unwrapped = Result<W::Unwrapped, E>;
+
+    fn unwrap_env_fields(self) -> Self::Unwrapped {
+        self.map(W::unwrap_env_fields)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, W, S> UnwrapEnvFields for indexmap::IndexMap<K, W, S>
+where
+    K: std::hash::Hash + Eq,
+    W: UnwrapEnvFields,
+    S: std::hash::BuildHasher + Default,
+{
+    type Unwrapped = indexmap::IndexMap<K, W::Unwrapped, S>;
+
+    fn unwrap_env_fields(self) -> Self::Unwrapped {
+        self.into_iter()
+            .map(|(key, value)| (key, value.unwrap_env_fields()))
+            .collect()
+    }
+}
+
+macro_rules! unwrap_tuple {
+    ($($w:ident)+) => {
+        impl<$($w: UnwrapEnvFields),+> UnwrapEnvFields for ($($w,)+) {
+            type Unwrapped = ($($w::Unwrapped,)+);
+
+            #[allow(non_snake_case)]
+            fn unwrap_env_fields(self) -> Self::Unwrapped {
+                let ($($w,)+) = self;
+                ($($w.unwrap_env_fields(),)+)
+            }
+        }
+    };
+}
+
+unwrap_tuple!(W0);
+unwrap_tuple!(W0 W1);
+unwrap_tuple!(W0 W1 W2);
+unwrap_tuple!(W0 W1 W2 W3);
+unwrap_tuple!(W0 W1 W2 W3 W4);
+unwrap_tuple!(W0 W1 W2 W3 W4 W5);
+unwrap_tuple!(W0 W1 W2 W3 W4 W5 W6);
+unwrap_tuple!(W0 W1 W2 W3 W4 W5 W6 W7);
+unwrap_tuple!(W0 W1 W2 W3 W4 W5 W6 W7 W8);
+unwrap_tuple!(W0 W1 W2 W3 W4 W5 W6 W7 W8 W9);
+unwrap_tuple!(W0 W1 W2 W3 W4 W5 W6 W7 W8 W9 W10);
+unwrap_tuple!(W0 W1 W2 W3 W4 W5 W6 W7 W8 W9 W10 W11);
+
+/// Deserializes the field type wrapped by the `EnvField`s and unwraps it back.
+pub fn deserialize_unwrapped<'de, D, W>(deserializer: D) -> Result<W::Unwrapped, D::Error>
+where
+    D: Deserializer<'de>,
+    W: Deserialize<'de> + UnwrapEnvFields,
+{
+    W::deserialize(deserializer).map(W::unwrap_env_fields)
+}
+
+/// The strategy of the `skip_if_empty` fields, see the `serde_env_field`.
+///
+/// The strings expanded to nothing are taken as the absent values.
+pub struct NonEmpty;
+
+impl<'de, W: Deserialize<'de>> ExpandStrategy<'de, Option<W>> for NonEmpty {
+    fn from_expanded(expanded: &str) -> Result<Option<W>, EnvFieldError> {
+        if expanded.is_empty() {
+            return Ok(None);
+        }
+
+        // The string is already expanded and trimmed, and its length is already recorded
+        let (result, _) = context::scoped(
+            |context| &mut context.literal,
+            true,
+            || {
+                let _trim = context::ScopeGuard::new(|context| &mut context.trim, None);
+                let _metrics = context::ScopeGuard::new(|context| &mut context.metrics, None);
+
+                W::deserialize(SomeDeserializer(StrDeserializer::<EnvFieldError>::new(
+                    expanded,
+                )))
+            },
+        );
+
+        result.map(Some)
+    }
+
+    fn from_nested<D>(deserializer: D) -> Result<Option<W>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        W::deserialize(deserializer).map(Some)
+    }
+}
+
+/// Deserializes the optional field, taking the strings expanded to nothing as `None`.
+pub fn deserialize_non_empty<'de, D, W>(deserializer: D) -> Result<Option<W>, D::Error>
+where
+    D: Deserializer<'de>,
+    W: Deserialize<'de>,
+{
+    EnvField::<Option<W>, NonEmpty>::deserialize(deserializer).map(EnvField::into_inner)
+}
+
+/// Same as the `deserialize_non_empty`, but the field type wrapped by the `EnvField`s is unwrapped back.
+pub fn deserialize_non_empty_unwrapped<'de, D, W>(
+    deserializer: D,
+) -> Result<Option<W::Unwrapped>, D::Error>
+where
+    D: Deserializer<'de>,
+    W: Deserialize<'de> + UnwrapEnvFields,
+{
+    deserialize_non_empty::<D, W>(deserializer).map(|value| value.map(W::unwrap_env_fields))
+}
+
+/// A field of the shadow type the fields with the fallible defaults are deserialized into.
+///
+/// The serde `default` functions can't fail the deserialization,
+/// so the absent fields are constructed from their defaults afterward,
+/// while converting the shadow type into the original one.
+#[derive(Default)]
+pub enum Deferred<F> {
+    Present(F),
+    #[default]
+    Absent,
+}
+
+impl<F> Deferred<F> {
+    /// Returns the present value or constructs the absent one from the default.
+    pub fn resolve(
+        self,
+        default: impl FnOnce() -> Result<F, EnvFieldError>,
+    ) -> Result<F, EnvFieldError> {
+        match self {
+            Self::Present(value) => Ok(value),
+            Self::Absent => default(),
+        }
+    }
+}
+
+/// Constructs the absent field from the environment variable.
+///
+/// The field defaults to `F::default()` if the variable is unset,
+/// or if the variable value is invalid while the errors are being collected
+/// via the [`EnvField::collect_errors`](crate::EnvField::collect_errors).
+pub fn env_default<F>(var_name: &str) -> Result<F, EnvFieldError>
+where
+    F: DeserializeOwned + Default,
+{
+    let value = env_value(var_name).and_then(|value| {
+        value
+            .map(|value| from_env_value(var_name, value))
+            .transpose()
+    });
+
+    match value {
+        Ok(value) => Ok(value.unwrap_or_default()),
+        Err(err) if context::collect_error(&err) => Ok(F::default()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Constructs the absent field from the template returned by the `default_expand` function,
+/// expanding it the same way as the document values.
+pub fn expand_default<F>(template: impl AsRef<str>) -> Result<F, EnvFieldError>
+where
+    F: DeserializeOwned,
+{
+    let template = template.as_ref();
+    F::deserialize(SomeDeserializer(StrDeserializer::<EnvFieldError>::new(
+        template,
+    )))
+    .map_err(|err| EnvFieldError::Parse(format!("invalid default template '{template}': {err}")))
+}
+
+/// Constructs the absent field from the static template given via the `default` option,
+/// expanding it with the strategy of the template.
+pub fn template_default<F, T, V>(template: StaticTemplate<T, V>) -> Result<F, EnvFieldError>
+where
+    V: for<'de> ExpandStrategy<'de, T>,
+    F: From<T>,
+{
+    let template = template.template();
+    EnvField::<T, V>::env_expand_and_construct(template)
+        .map(|value| F::from(value.into_inner()))
+        .map_err(|err| {
+            EnvFieldError::Parse(format!("invalid default template '{template}': {err}"))
+        })
+}
+
+fn env_value(var_name: &str) -> Result<Option<String>, EnvFieldError> {
+    match context::var(var_name) {
+        Ok(value) => Ok(Some(value)),
+        Err(VarError::NotPresent) => Ok(None),
+        Err(cause) => Err(EnvFieldError::Lookup {
+            var_name: var_name.to_owned(),
+            cause,
+            suggestion: None,
+        }),
+    }
+}
+
+fn from_env_value<'de, F: Deserialize<'de>>(
+    var_name: &str,
+    value: String,
+) -> Result<F, EnvFieldError> {
+    // The variable value is taken literally, so the expansion is disabled
+    let (result, _) = context::scoped(
+        |context| &mut context.literal,
+        true,
+        || {
+            F::deserialize(SomeDeserializer(StrDeserializer::<EnvFieldError>::new(
+                &value,
+            )))
+        },
+    );
+
+    result.map_err(|err| {
+        let err = redacted(err, &value);
+        EnvFieldError::Parse(format!("invalid value of the '{var_name}' variable: {err}"))
+    })
+}
+
+/// Runs the closure with the expanded strings trimmed.
+pub fn with_trim<R>(trim: Trim, f: impl FnOnce() -> R) -> R {
+    context::scoped(|context| &mut context.trim, Some(trim), f).0
+}
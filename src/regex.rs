@@ -0,0 +1,69 @@
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    always_string::deserialize_string,
+    error::{redacted, truncated},
+    expand, EnvField, EnvFieldError,
+};
+
+/// A marker type for passing into the [`EnvField<Regex>`] type as a second parameter.
+///
+/// The `EnvField` will compile the [`Regex`] from the string with all environment variables expanded.
+/// The errors include both the invalid pattern and the compile error.
+///
+/// Only the strings are accepted.
+/// Since the `Regex` doesn't implement the `Serialize` trait,
+/// use the [`AlwaysString`](crate::AlwaysString) representation to serialize the pattern back.
+///
+/// If the deserializer is not human-readable (e.g., `bincode`),
+/// the pattern is used as is without the expansion.
+///
+/// Requires the `regex` feature.
+///
+/// ### Example
+///
+/// ```
+/// # use regex::Regex;
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{AlwaysString, EnvField, UseRegex};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     filter: EnvField<Regex, UseRegex, AlwaysString>,
+/// }
+///
+/// std::env::set_var("FILTER_PATTERN", r"^\d+$");
+/// let de: Example = toml::from_str(r#"
+///     filter = "${FILTER_PATTERN:-.*}"
+/// "#).unwrap();
+///
+/// assert!(de.filter.is_match("12345"));
+/// assert_eq!(toml::to_string(&de).unwrap(), "filter = '^\\d+$'\n");
+/// ```
+pub struct UseRegex;
+
+impl<R> EnvField<Regex, UseRegex, R> {
+    fn env_expand_and_compile(str_data: &str) -> Result<Self, EnvFieldError> {
+        Self::compile(&expand::env(str_data)?)
+    }
+
+    fn compile(pattern: &str) -> Result<Self, EnvFieldError> {
+        Regex::new(pattern).map(Self::new).map_err(|err| {
+            redacted(
+                EnvFieldError::Parse(format!("invalid pattern '{}': {err}", truncated(pattern))),
+                pattern,
+            )
+        })
+    }
+}
+
+impl<'de, R> Deserialize<'de> for EnvField<Regex, UseRegex, R> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_string(deserializer, Self::env_expand_and_compile, |pattern| {
+            Self::compile(&pattern)
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use std::{any, str::FromStr};
+
+use secrecy::{zeroize::Zeroize, SecretBox, SecretString};
+use serde::Deserialize;
+
+use crate::{always_string::deserialize_string, expand, EnvField, EnvFieldError};
+
+/// A marker type for passing into the [`EnvField<T>`] type as a second parameter.
+///
+/// The `EnvField` will construct the [`SecretString`] or the [`SecretBox<T>`] value
+/// from the string with all environment variables expanded.
+/// The `T` type is constructed using the [`FromStr`] trait.
+///
+/// Only the strings are accepted.
+/// The error messages never include the expanded string.
+/// The expanded string is moved into the `SecretString`,
+/// or zeroized after parsing the `SecretBox<T>` value.
+/// However, the buffers used during the expansion (e.g., the values of the variables)
+/// and the original document string aren't zeroized.
+///
+/// If the deserializer is not human-readable (e.g., `bincode`),
+/// the string is used as is without the expansion.
+///
+/// Requires the `secrecy` feature.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use secrecy::{ExposeSecret, SecretBox, SecretString};
+/// # use serde_env_field::{EnvField, UseSecret};
+/// #[derive(Deserialize)]
+/// struct Example {
+///     password: EnvField<SecretString, UseSecret>,
+///     pin: EnvField<SecretBox<u32>, UseSecret>,
+/// }
+///
+/// std::env::set_var("DB_PASSWORD", "hunter2");
+/// std::env::set_var("DB_PIN", "1234");
+/// let de: Example = toml::from_str(r#"
+///     password = "$DB_PASSWORD"
+///     pin = "$DB_PIN"
+/// "#).unwrap();
+///
+/// assert_eq!(de.password.expose_secret(), "hunter2");
+/// assert_eq!(*de.pin.expose_secret(), 1234);
+///
+/// // The secrets are redacted
+/// assert!(!format!("{:?}", *de.password).contains("hunter2"));
+/// ```
+pub struct UseSecret;
+
+impl EnvField<SecretString, UseSecret> {
+    fn env_expand_secret_string(str_data: &str) -> Result<Self, EnvFieldError> {
+        let expanded = expand::env(str_data)?;
+        Ok(Self::new(expanded.into_owned().into()))
+    }
+}
+
+impl<T: FromStr + Zeroize> EnvField<SecretBox<T>, UseSecret> {
+    fn env_expand_and_parse_secret(str_data: &str) -> Result<Self, EnvFieldError> {
+        Self::parse_secret(expand::env(str_data)?.into_owned())
+    }
+
+    fn parse_secret(mut value: String) -> Result<Self, EnvFieldError> {
+        let parsed = value.parse();
+        value.zeroize();
+
+        // The parsing error isn't included since it might contain the secret
+        parsed
+            .map(|parsed| Self::new(SecretBox::new(Box::new(parsed))))
+            .map_err(|_| {
+                EnvFieldError::Parse(format!(
+                    "invalid secret value: the string can't be parsed as `{}`",
+                    any::type_name::<T>()
+                ))
+            })
+    }
+}
+
+impl<'de> Deserialize<'de> for EnvField<SecretString, UseSecret> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_string(deserializer, Self::env_expand_secret_string, |s| {
+            Ok(Self::new(s.into()))
+        })
+    }
+}
+
+impl<'de, T: FromStr + Zeroize> Deserialize<'de> for EnvField<SecretBox<T>, UseSecret> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_string(
+            deserializer,
+            Self::env_expand_and_parse_secret,
+            Self::parse_secret,
+        )
+    }
+}
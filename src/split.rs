@@ -0,0 +1,90 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de::Error, Deserialize};
+use serde_untagged::UntaggedEnumVisitor;
+
+use crate::{
+    error::{redacted, truncated},
+    expand, EnvField, EnvFieldError,
+};
+
+/// A marker type for passing into the [`EnvField<Vec<T>>`] type as a second parameter.
+///
+/// The `EnvField` will split the string with all environment variables expanded
+/// by the `SEP` separator (the comma by default),
+/// and then construct each `T` element using the [`FromStr`] trait.
+///
+/// An empty string yields an empty vector, and a trailing separator is ignored.
+///
+/// If the supplied data was not a string, the `EnvField` will accept a sequence of the `T` values.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvField, UseSplit};
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     hosts: EnvField<Vec<String>, UseSplit>,
+///     ports: EnvField<Vec<u16>, UseSplit<';'>>,
+/// }
+///
+/// std::env::set_var("HOSTS", "a.com,b.com,c.com");
+/// let de: Example = toml::from_str(r#"
+///     hosts = "$HOSTS"
+///     ports = "80;443"
+/// "#).unwrap();
+///
+/// assert_eq!(*de.hosts, ["a.com", "b.com", "c.com"]);
+/// assert_eq!(*de.ports, [80, 443]);
+/// ```
+pub struct UseSplit<const SEP: char = ','>;
+
+impl<T, const SEP: char> EnvField<Vec<T>, UseSplit<SEP>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    fn env_expand_and_split(str_data: &str) -> Result<Self, EnvFieldError> {
+        let expanded = expand::env(str_data)?;
+        let expanded = expanded.strip_suffix(SEP).unwrap_or(&expanded);
+
+        if expanded.is_empty() {
+            return Ok(Self::new(vec![]));
+        }
+
+        expanded
+            .split(SEP)
+            .map(|item| {
+                item.parse().map_err(|err| {
+                    redacted(
+                        EnvFieldError::Parse(format!("invalid value '{}': {err}", truncated(item))),
+                        item,
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self::new)
+    }
+}
+
+impl<'de, T, const SEP: char> Deserialize<'de> for EnvField<Vec<T>, UseSplit<SEP>>
+where
+    T: Deserialize<'de> + FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return Vec::deserialize(deserializer).map(Self::new);
+        }
+
+        UntaggedEnumVisitor::new()
+            .string(|s| Self::env_expand_and_split(s).map_err(Error::custom))
+            .borrowed_str(|s| Self::env_expand_and_split(s).map_err(Error::custom))
+            .seq(|seq| seq.deserialize().map(Self::new))
+            .deserialize(deserializer)
+    }
+}
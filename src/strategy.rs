@@ -0,0 +1,189 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug},
+    str::FromStr,
+};
+
+use serde::{de::value::StringDeserializer, Deserialize, Deserializer};
+
+use crate::{
+    error::{redacted, truncated},
+    expand, EnvFieldError, ExpandingDeserializer, SomeDeserializer, UseDeserialize,
+    UseFromExpanded, UseFromStr, UseFromStrDebug, UseLenient,
+};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::UseFromStr {}
+    impl Sealed for super::UseFromStrDebug {}
+    impl Sealed for super::UseFromExpanded {}
+    impl Sealed for super::UseLenient {}
+    impl Sealed for super::UseDeserialize {}
+    impl Sealed for crate::UsePathList {}
+    impl Sealed for crate::UseByteSize {}
+    impl Sealed for crate::UseBoolish {}
+    impl<const SEP: char> Sealed for crate::UseGrouped<SEP> {}
+    impl Sealed for crate::UseCaseTransforms {}
+    impl Sealed for crate::NoExpand {}
+    impl Sealed for crate::__private::NonEmpty {}
+}
+
+/// The way the [`EnvField`](crate::EnvField) constructs the `T` type
+/// from the string with all environment variables expanded.
+///
+/// It is implemented by the [`UseFromStr`], [`UseFromStrDebug`], [`UseFromExpanded`],
+/// [`UseLenient`], [`UseDeserialize`], [`UsePathList`](crate::UsePathList),
+/// [`UseByteSize`](crate::UseByteSize), [`UseBoolish`](crate::UseBoolish),
+/// [`UseGrouped`](crate::UseGrouped), [`UseCaseTransforms`](crate::UseCaseTransforms),
+/// and [`NoExpand`](crate::NoExpand) markers,
+/// so generic code can name and constrain the `Variant` of an `EnvField`.
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use serde_env_field::{EnvField, ExpandStrategy, UseDeserialize, UseFromStr};
+/// fn parse<'de, T, V>(json: &'de str) -> T
+/// where
+///     T: Deserialize<'de>,
+///     V: ExpandStrategy<'de, T>,
+/// {
+///     serde_json::from_str::<EnvField<T, V>>(json)
+///         .unwrap()
+///         .into_inner()
+/// }
+///
+/// std::env::set_var("STRATEGY_NUMBER", "42");
+/// assert_eq!(parse::<u8, UseFromStr>(r#""$STRATEGY_NUMBER""#), 42);
+/// assert_eq!(parse::<String, UseDeserialize>(r#""$STRATEGY_NUMBER""#), "42");
+/// ```
+pub trait ExpandStrategy<'de, T>: sealed::Sealed {
+    /// Expands the environment variables in the string.
+    #[doc(hidden)]
+    fn expand(str_data: &str) -> Result<Cow<'_, str>, EnvFieldError> {
+        expand::env(str_data)
+    }
+
+    /// Constructs the `T` from the expanded string.
+    #[doc(hidden)]
+    fn from_expanded(expanded: &str) -> Result<T, EnvFieldError>;
+
+    /// Deserializes the `T` from a sequence or a map.
+    #[doc(hidden)]
+    fn from_nested<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+impl<'de, T> ExpandStrategy<'de, T> for UseFromStr
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    fn from_expanded(expanded: &str) -> Result<T, EnvFieldError> {
+        expanded.parse::<T>().map_err(|err| {
+            let mut message = format!("invalid value '{}': {err}", truncated(expanded));
+            if let Some(hint) = parse_hint::<T>(expanded) {
+                message = format!("{message}; {hint}");
+            }
+
+            redacted(EnvFieldError::Parse(message), expanded)
+        })
+    }
+}
+
+/// Explains the standard parsing errors that don't tell what was wrong with the string.
+fn parse_hint<T>(expanded: &str) -> Option<String> {
+    // The `char` parsing error doesn't tell how many characters were there
+    if typeid::of::<T>() == typeid::of::<char>() {
+        return Some(format!(
+            "expanded to {} characters, expected exactly one",
+            expanded.chars().count()
+        ));
+    }
+
+    // The `bool` parsing error doesn't hint at the boolish values
+    (typeid::of::<T>() == typeid::of::<bool>())
+        .then(|| "expected 'true'/'false' for bool field (enable UseBoolish for 1/0)".to_owned())
+}
+
+impl<'de, T> ExpandStrategy<'de, T> for UseFromStrDebug
+where
+    T: FromStr,
+    <T as FromStr>::Err: Debug,
+{
+    fn from_expanded(expanded: &str) -> Result<T, EnvFieldError> {
+        expanded.parse().map_err(|err| {
+            redacted(
+                EnvFieldError::Parse(format!("invalid value '{}': {err:?}", truncated(expanded))),
+                expanded,
+            )
+        })
+    }
+}
+
+/// Constructs the type from the string with all environment variables expanded.
+///
+/// The [`EnvField`](crate::EnvField) uses it instead of the [`FromStr`]
+/// with the [`UseFromExpanded`] marker, see its documentation for an example.
+pub trait FromExpandedStr: Sized {
+    /// Constructs the value from the expanded string,
+    /// returning the error message if the string is invalid.
+    fn from_expanded(expanded: &str) -> Result<Self, String>;
+}
+
+impl<'de, T: FromExpandedStr> ExpandStrategy<'de, T> for UseFromExpanded {
+    fn from_expanded(expanded: &str) -> Result<T, EnvFieldError> {
+        T::from_expanded(expanded).map_err(|err| {
+            redacted(
+                EnvFieldError::Parse(format!("invalid value '{}': {err}", truncated(expanded))),
+                expanded,
+            )
+        })
+    }
+}
+
+impl<'de, T> ExpandStrategy<'de, Result<T, String>> for UseLenient
+where
+    T: Deserialize<'de> + FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    fn from_expanded(expanded: &str) -> Result<Result<T, String>, EnvFieldError> {
+        Ok(UseFromStr::from_expanded(expanded).map_err(|err: EnvFieldError| err.to_string()))
+    }
+
+    fn from_nested<D>(deserializer: D) -> Result<Result<T, String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Ok)
+    }
+}
+
+impl<'de, T> ExpandStrategy<'de, T> for UseDeserialize
+where
+    T: Deserialize<'de>,
+{
+    fn from_expanded(expanded: &str) -> Result<T, EnvFieldError> {
+        T::deserialize(SomeDeserializer(StringDeserializer::new(
+            expanded.to_string(),
+        )))
+        .map_err(|err| redacted(err, expanded))
+    }
+
+    fn from_nested<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        // The string leaves of the sequences and the maps are expanded too
+        T::deserialize(ExpandingDeserializer::new(deserializer))
+    }
+}
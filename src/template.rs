@@ -0,0 +1,309 @@
+use std::{
+    borrow::Cow,
+    cell::OnceCell,
+    fmt::{self, Debug},
+    marker::PhantomData,
+};
+
+use serde::{
+    de::{self, value::UnitDeserializer},
+    Deserialize, Serialize,
+};
+use serde_untagged::UntaggedEnumVisitor;
+
+use crate::{optional::SomeDeserializer, EnvField, EnvFieldError, ExpandStrategy, UseFromStr};
+
+/// A field that preserves the original string template
+/// and expands the environment variables only when the value is requested.
+///
+/// The reusable default templates are declared as the [`StaticTemplate`] constants,
+/// which are converted into the `EnvTemplate` where used.
+///
+/// When deserialized from a string, the `EnvTemplate` stores the string as is.
+/// The environment variables are expanded on the first access via [`EnvTemplate::try_get`],
+/// and the constructed value is cached afterward.
+/// Hence, the variables referenced by the fields that are never accessed
+/// don't have to be set at all.
+/// If the supplied data was not a string, the `T` value is deserialized directly from the data.
+///
+/// The `EnvTemplate` serializes as the original template if it has one,
+/// and transparently as the `T` type otherwise.
+///
+/// The second type parameter has the same meaning as the one of the `EnvField`.
+///
+/// ### Example
+///
+/// ```
+/// # use serde::{Serialize, Deserialize};
+/// # use serde_env_field::{EnvTemplate, StaticTemplate};
+/// const DEFAULT_HOST: StaticTemplate<String> = StaticTemplate::new("${HOST:-localhost}");
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Example {
+///     #[serde(default = "default_host")]
+///     host: EnvTemplate<String>,
+/// }
+///
+/// fn default_host() -> EnvTemplate<String> {
+///     DEFAULT_HOST.into()
+/// }
+///
+/// let de: Example = toml::from_str("").unwrap();
+/// assert_eq!(de.host.try_get().unwrap(), "localhost");
+///
+/// std::env::set_var("HOST", "example.com");
+/// let de: Example = toml::from_str("").unwrap();
+/// assert_eq!(de.host.try_get().unwrap(), "example.com");
+///
+/// // The template is preserved during serialization
+/// assert_eq!(
+///     toml::to_string(&de).unwrap(),
+///     "host = \"${HOST:-localhost}\"\n",
+/// );
+/// ```
+pub struct EnvTemplate<T, Variant = UseFromStr> {
+    template: Option<Cow<'static, str>>,
+    value: OnceCell<T>,
+    _variant: PhantomData<Variant>,
+}
+
+impl<T, V> EnvTemplate<T, V> {
+    /// Creates a new env template.
+    ///
+    /// The environment variables will be expanded on the first access to the value.
+    ///
+    /// Since the env template caches its value, declare the reusable templates
+    /// as the [`StaticTemplate`] constants instead of the `EnvTemplate` ones.
+    pub const fn new(template: &'static str) -> Self {
+        Self {
+            template: Some(Cow::Borrowed(template)),
+            value: OnceCell::new(),
+            _variant: PhantomData,
+        }
+    }
+
+    /// Returns the original template if the env template has one.
+    ///
+    /// The env templates constructed from a value (or deserialized from non-string data)
+    /// have no template.
+    ///
+    /// ```
+    /// # use serde_env_field::EnvTemplate;
+    /// let template: EnvTemplate<u16> = EnvTemplate::new("${PORT:-8080}");
+    /// assert_eq!(template.template(), Some("${PORT:-8080}"));
+    ///
+    /// let value: EnvTemplate<u16> = EnvTemplate::from(8080);
+    /// assert_eq!(value.template(), None);
+    /// ```
+    pub fn template(&self) -> Option<&str> {
+        self.template.as_deref()
+    }
+
+    fn from_template(template: String) -> Self {
+        Self {
+            template: Some(Cow::Owned(template)),
+            value: OnceCell::new(),
+            _variant: PhantomData,
+        }
+    }
+}
+
+impl<T, V> EnvTemplate<T, V>
+where
+    V: for<'de> ExpandStrategy<'de, T>,
+{
+    /// Returns the value, expanding the template and constructing the value from the result if needed.
+    pub fn try_get(&self) -> Result<&T, EnvFieldError> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+
+        let template = self
+            .template
+            .as_deref()
+            .expect("an env template without a value always has a template");
+
+        let value = EnvField::<T, V>::env_expand_and_construct(template)?.into_inner();
+        Ok(self.value.get_or_init(|| value))
+    }
+
+    /// Expands the template using the current environment and constructs the value again,
+    /// replacing the cached value.
+    ///
+    /// If the expansion or the construction fails, the previous value remains untouched.
+    /// Does nothing if the env template has no template.
+    pub fn reexpand(&mut self) -> Result<(), EnvFieldError> {
+        if let Some(template) = self.template.as_deref() {
+            let value = EnvField::<T, V>::env_expand_and_construct(template)?.into_inner();
+            self.value = OnceCell::from(value);
+        }
+
+        Ok(())
+    }
+
+    /// Re-expands all the env templates, see the `reexpand` method.
+    ///
+    /// Every template is processed even if some of them fail,
+    /// and the errors of all the failed ones are returned.
+    /// The failed templates keep their previous values.
+    pub fn resolve_all<'a>(
+        templates: impl IntoIterator<Item = &'a mut Self>,
+    ) -> Result<(), Vec<EnvFieldError>>
+    where
+        Self: 'a,
+    {
+        let errors: Vec<_> = templates
+            .into_iter()
+            .filter_map(|template| template.reexpand().err())
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<T, V> From<T> for EnvTemplate<T, V> {
+    fn from(value: T) -> Self {
+        Self {
+            template: None,
+            value: OnceCell::from(value),
+            _variant: PhantomData,
+        }
+    }
+}
+
+/// A `&'static str` template of the [`EnvTemplate`] without the cached value.
+///
+/// Unlike the `EnvTemplate`, it has no interior mutability,
+/// so it can be declared as a `const` or a `static` and reused across the items.
+/// Every [`EnvTemplate`] converted from it expands the template on its own.
+/// It can also be used as the default of a field via the `#[serde_env_field(default = CONST)]`,
+/// see the [`serde_env_field`](crate::serde_env_field).
+///
+/// ### Example
+///
+/// ```
+/// # use serde_env_field::{EnvTemplate, StaticTemplate};
+/// static DEFAULT_PORT: StaticTemplate<u16> = StaticTemplate::new("${PORT:-8080}");
+///
+/// let port: EnvTemplate<u16> = DEFAULT_PORT.into();
+/// assert_eq!(port.template(), Some("${PORT:-8080}"));
+/// assert_eq!(*port.try_get().unwrap(), 8080);
+/// ```
+pub struct StaticTemplate<T, Variant = UseFromStr> {
+    template: &'static str,
+    _marker: PhantomData<fn() -> (T, Variant)>,
+}
+
+impl<T, V> StaticTemplate<T, V> {
+    /// Creates a new static template.
+    pub const fn new(template: &'static str) -> Self {
+        Self {
+            template,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the template.
+    pub const fn template(&self) -> &'static str {
+        self.template
+    }
+}
+
+impl<T, V> From<StaticTemplate<T, V>> for EnvTemplate<T, V> {
+    fn from(template: StaticTemplate<T, V>) -> Self {
+        Self::new(template.template)
+    }
+}
+
+impl<T, V> Clone for StaticTemplate<T, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> Copy for StaticTemplate<T, V> {}
+
+impl<T, V> Debug for StaticTemplate<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StaticTemplate")
+            .field(&self.template)
+            .finish()
+    }
+}
+
+impl<T: Serialize, V> Serialize for EnvTemplate<T, V> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match (&self.template, self.value.get()) {
+            (Some(template), _) => serializer.serialize_str(template),
+            (None, Some(value)) => value.serialize(serializer),
+            (None, None) => unreachable!("an env template without a template always has a value"),
+        }
+    }
+}
+
+macro_rules! deserialize_value {
+    ($de:ident) => {
+        |v| T::deserialize(SomeDeserializer(de::value::$de::new(v))).map(Self::from)
+    };
+}
+
+impl<'de, T, V> Deserialize<'de> for EnvTemplate<T, V>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        UntaggedEnumVisitor::new()
+            .string(|s| Ok(Self::from_template(s.to_owned())))
+            .borrowed_str(|s| Ok(Self::from_template(s.to_owned())))
+            .bool(deserialize_value!(BoolDeserializer))
+            .i8(deserialize_value!(I8Deserializer))
+            .i16(deserialize_value!(I16Deserializer))
+            .i32(deserialize_value!(I32Deserializer))
+            .i64(deserialize_value!(I64Deserializer))
+            .i128(deserialize_value!(I128Deserializer))
+            .u8(deserialize_value!(U8Deserializer))
+            .u16(deserialize_value!(U16Deserializer))
+            .u32(deserialize_value!(U32Deserializer))
+            .u64(deserialize_value!(U64Deserializer))
+            .u128(deserialize_value!(U128Deserializer))
+            .f32(deserialize_value!(F32Deserializer))
+            .f64(deserialize_value!(F64Deserializer))
+            .char(deserialize_value!(CharDeserializer))
+            .bytes(deserialize_value!(BytesDeserializer))
+            .borrowed_bytes(deserialize_value!(BorrowedBytesDeserializer))
+            .unit(|| T::deserialize(UnitDeserializer::new()).map(Self::from))
+            .none(|| T::deserialize(UnitDeserializer::new()).map(Self::from))
+            .seq(|seq| seq.deserialize::<T>().map(Self::from))
+            .map(|map| map.deserialize::<T>().map(Self::from))
+            .deserialize(deserializer)
+    }
+}
+
+impl<T: Clone, V> Clone for EnvTemplate<T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            template: self.template.clone(),
+            value: self.value.clone(),
+            _variant: PhantomData,
+        }
+    }
+}
+
+impl<T: Debug, V> Debug for EnvTemplate<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnvTemplate")
+            .field("template", &self.template)
+            .field("value", &self.value.get())
+            .finish()
+    }
+}
@@ -0,0 +1,106 @@
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use serde_env_field::{AlwaysString, EnvField, UseDeserialize, UseFromStr};
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Test {
+    secret: EnvField<String, UseFromStr, AlwaysString>,
+    pin: EnvField<u32, UseFromStr, AlwaysString>,
+}
+
+#[test]
+fn test_always_string_toml() {
+    env::set_var("SECRET_test_always_string_toml", "12345");
+    let de: Test = toml::from_str(
+        r#"
+            secret = "$SECRET_test_always_string_toml"
+            pin = "${PIN_test_always_string_toml:-42}"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(&*de.secret, "12345");
+    assert_eq!(*de.pin, 42);
+
+    assert_eq!(
+        toml::to_string(&de).unwrap(),
+        "secret = \"12345\"\npin = \"42\"\n"
+    );
+
+    toml::from_str::<Test>(
+        r#"
+            secret = 12345
+            pin = "42"
+        "#,
+    )
+    .unwrap_err();
+
+    toml::from_str::<Test>(
+        r#"
+            secret = "12345"
+            pin = 42
+        "#,
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_always_string_json() {
+    env::set_var("PIN_test_always_string_json", "7");
+    let de: Test =
+        serde_json::from_str(r#"{ "secret": "s3cr3t", "pin": "$PIN_test_always_string_json" }"#)
+            .unwrap();
+
+    assert_eq!(&*de.secret, "s3cr3t");
+    assert_eq!(*de.pin, 7);
+
+    assert_eq!(
+        serde_json::to_string(&de).unwrap(),
+        r#"{"secret":"s3cr3t","pin":"7"}"#
+    );
+
+    serde_json::from_str::<Test>(r#"{ "secret": "s3cr3t", "pin": 7 }"#).unwrap_err();
+    serde_json::from_str::<Test>(r#"{ "secret": ["s3cr3t"], "pin": "7" }"#).unwrap_err();
+}
+
+#[test]
+fn test_always_string_use_deserialize() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "kebab-case")]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    impl std::fmt::Display for Mode {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.serialize(f)
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        mode: EnvField<Mode, UseDeserialize, AlwaysString>,
+    }
+
+    env::set_var("MODE_test_always_string", "fast");
+    let de: Test = toml::from_str(r#"mode = "$MODE_test_always_string""#).unwrap();
+
+    assert_eq!(*de.mode, Mode::Fast);
+    assert_eq!(toml::to_string(&de).unwrap(), "mode = \"fast\"\n");
+}
+
+#[test]
+fn test_always_string_not_human_readable() {
+    let de = Test {
+        secret: "$NOT_EXPANDED".to_string().into(),
+        pin: 5.into(),
+    };
+
+    let bytes = bincode::serialize(&de).unwrap();
+    let de: Test = bincode::deserialize(&bytes).unwrap();
+
+    assert_eq!(&*de.secret, "$NOT_EXPANDED");
+    assert_eq!(*de.pin, 5);
+}
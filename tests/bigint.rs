@@ -0,0 +1,47 @@
+#![cfg(feature = "num-bigint")]
+
+use std::env;
+
+use num_bigint::BigInt;
+use serde::Deserialize;
+use serde_env_field::EnvField;
+
+#[derive(Deserialize, Debug)]
+struct Test {
+    value: EnvField<BigInt>,
+}
+
+fn value(source: &str) -> Result<BigInt, toml::de::Error> {
+    toml::from_str::<Test>(source).map(|de| de.value.into_inner())
+}
+
+#[test]
+fn test_bigint() {
+    const HUGE: &str = "-123456789012345678901234567890";
+
+    assert_eq!(value("value = 42").unwrap(), BigInt::from(42));
+    assert_eq!(
+        value(&format!(r#"value = "{HUGE}""#)).unwrap(),
+        HUGE.parse().unwrap()
+    );
+
+    env::set_var("VALUE_test_bigint", HUGE);
+    assert_eq!(
+        value(r#"value = "${VALUE_test_bigint}0""#).unwrap(),
+        format!("{HUGE}0").parse().unwrap()
+    );
+}
+
+#[test]
+fn test_bigint_errors() {
+    env::set_var("VALUE_test_bigint_errors", "12x");
+    let err = value(r#"value = "$VALUE_test_bigint_errors""#).unwrap_err();
+    assert!(err
+        .message()
+        .contains("invalid value '12x': invalid digit found in string"));
+
+    let err = value(r#"value = """#).unwrap_err();
+    assert!(err
+        .message()
+        .contains("invalid value '': cannot parse integer from empty string"));
+}
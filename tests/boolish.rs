@@ -0,0 +1,51 @@
+use std::env;
+
+use serde::Deserialize;
+use serde_env_field::{EnvField, UseBoolish};
+
+#[derive(Deserialize)]
+struct Test {
+    flag: EnvField<bool, UseBoolish>,
+}
+
+fn flag(source: &str) -> Result<bool, toml::de::Error> {
+    toml::from_str::<Test>(source).map(|de| *de.flag)
+}
+
+#[test]
+fn test_boolish_values() {
+    env::set_var("FLAG_test_boolish_1", "1");
+    assert!(flag(r#"flag = "$FLAG_test_boolish_1""#).unwrap());
+
+    env::set_var("FLAG_test_boolish_on", "ON");
+    assert!(flag(r#"flag = "$FLAG_test_boolish_on""#).unwrap());
+
+    env::set_var("FLAG_test_boolish_no", "No");
+    assert!(!flag(r#"flag = "$FLAG_test_boolish_no""#).unwrap());
+
+    assert!(flag(r#"flag = "yes""#).unwrap());
+    assert!(!flag(r#"flag = "off""#).unwrap());
+    assert!(!flag(r#"flag = "FALSE""#).unwrap());
+    assert!(!flag(r#"flag = "${FLAG_test_boolish_missing:-0}""#).unwrap());
+}
+
+#[test]
+fn test_boolish_not_string() {
+    assert!(flag("flag = true").unwrap());
+    assert!(!flag("flag = false").unwrap());
+    assert!(flag("flag = 1").unwrap());
+    assert!(!flag("flag = 0").unwrap());
+
+    flag("flag = 2").unwrap_err();
+}
+
+#[test]
+fn test_boolish_invalid() {
+    env::set_var("FLAG_test_boolish_invalid", "enabled");
+    let err = flag(r#"flag = "$FLAG_test_boolish_invalid""#).unwrap_err();
+
+    assert!(err.message().contains(
+        "invalid boolean value 'enabled': \
+        expected one of true/false, 1/0, yes/no, on/off (case-insensitive)"
+    ));
+}
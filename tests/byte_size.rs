@@ -0,0 +1,79 @@
+use std::env;
+
+use serde::Deserialize;
+use serde_env_field::{EnvField, UseByteSize};
+
+#[derive(Deserialize)]
+struct Test {
+    size: EnvField<u64, UseByteSize>,
+}
+
+fn size(source: &str) -> Result<u64, toml::de::Error> {
+    toml::from_str::<Test>(source).map(|de| *de.size)
+}
+
+#[test]
+fn test_byte_size_units() {
+    env::set_var("SIZE_test_byte_size_mb", "10MB");
+    assert_eq!(
+        size(r#"size = "$SIZE_test_byte_size_mb""#).unwrap(),
+        10_000_000
+    );
+
+    env::set_var("SIZE_test_byte_size_gib", "1GiB");
+    assert_eq!(
+        size(r#"size = "$SIZE_test_byte_size_gib""#).unwrap(),
+        1 << 30
+    );
+
+    assert_eq!(size(r#"size = "512""#).unwrap(), 512);
+    assert_eq!(size(r#"size = "512B""#).unwrap(), 512);
+    assert_eq!(size(r#"size = "4 kib""#).unwrap(), 4096);
+    assert_eq!(size(r#"size = "1.5KB""#).unwrap(), 1500);
+    assert_eq!(size(r#"size = "0.5 MiB""#).unwrap(), 512 * 1024);
+    assert_eq!(
+        size(r#"size = "${SIZE_test_byte_size_missing:-2TB}""#).unwrap(),
+        2_000_000_000_000
+    );
+}
+
+#[test]
+fn test_byte_size_not_string() {
+    assert_eq!(size("size = 1024").unwrap(), 1024);
+    size("size = -1").unwrap_err();
+}
+
+#[test]
+fn test_byte_size_invalid() {
+    env::set_var("SIZE_test_byte_size_invalid", "10XB");
+    let err = size(r#"size = "$SIZE_test_byte_size_invalid""#).unwrap_err();
+    assert!(err
+        .message()
+        .starts_with("invalid byte size '10XB': unknown unit 'XB', expected one of B, KB, MB"));
+
+    // The long units are truncated as well
+    let unit = "X".repeat(100);
+    let err = size(&format!(r#"size = "10{unit}""#)).unwrap_err();
+    assert!(err
+        .message()
+        .contains(&format!("unknown unit '{}...'", &unit[..32])));
+    assert!(!err.message().contains(&unit));
+
+    let err = size(r#"size = "MB""#).unwrap_err();
+    assert!(err
+        .message()
+        .contains("invalid byte size 'MB': expected a number"));
+
+    let err = size(r#"size = "1.2.3MB""#).unwrap_err();
+    assert!(err.message().contains("expected a number"));
+
+    let err = size(r#"size = "16EiB""#).unwrap_err();
+    assert!(err
+        .message()
+        .contains("invalid byte size '16EiB': the size is too large"));
+
+    let err = size(r#"size = "1.5B""#).unwrap_err();
+    assert!(err
+        .message()
+        .contains("invalid byte size '1.5B': the size is not a whole number of bytes"));
+}
@@ -0,0 +1,81 @@
+use std::env;
+
+use serde::Deserialize;
+use serde_env_field::{EnvField, UseCaseTransforms};
+
+#[derive(Deserialize)]
+struct Test {
+    value: EnvField<String, UseCaseTransforms>,
+}
+
+#[derive(Deserialize)]
+struct TestDefault {
+    value: EnvField<String>,
+}
+
+fn value(source: &str) -> String {
+    toml::from_str::<Test>(source).unwrap().value.into_inner()
+}
+
+#[test]
+fn test_case_transforms() {
+    env::set_var("VALUE_test_case_transforms", "Mixed Case");
+
+    assert_eq!(
+        value(r#"value = "${VALUE_test_case_transforms^^}""#),
+        "MIXED CASE"
+    );
+    assert_eq!(
+        value(r#"value = "${VALUE_test_case_transforms,,}""#),
+        "mixed case"
+    );
+    assert_eq!(
+        value(r#"value = "${VALUE_test_case_transforms^^}/$VALUE_test_case_transforms""#),
+        "MIXED CASE/Mixed Case"
+    );
+
+    // The transforms apply to the variable value only
+    assert_eq!(
+        value(r#"value = "Prefix-${VALUE_test_case_transforms,,}""#),
+        "Prefix-mixed case"
+    );
+
+    // The transformed variable is still required
+    let err = toml::from_str::<Test>(r#"value = "${UNSET_test_case_transforms^^}""#)
+        .err()
+        .unwrap();
+    assert!(err.message().contains("UNSET_test_case_transforms"));
+}
+
+#[test]
+fn test_case_transforms_literal_chars() {
+    env::set_var("VALUE_test_case_transforms_literal_chars", "a^^b,,c");
+
+    // The `^` and `,` outside of the braces are taken as is in both modes
+    assert_eq!(value(r#"value = "x^^y,,z""#), "x^^y,,z");
+    assert_eq!(
+        value(r#"value = "$VALUE_test_case_transforms_literal_chars""#),
+        "a^^b,,c"
+    );
+
+    let de: TestDefault =
+        toml::from_str(r#"value = "x^^y,,z/$VALUE_test_case_transforms_literal_chars""#).unwrap();
+    assert_eq!(&de.value, "x^^y,,z/a^^b,,c");
+
+    // Without the transforms, the operators are a part of the variable name
+    env::set_var(
+        "VALUE_test_case_transforms_literal_chars^^",
+        "not transformed",
+    );
+    let de: TestDefault =
+        toml::from_str(r#"value = "${VALUE_test_case_transforms_literal_chars^^}""#).unwrap();
+    assert_eq!(&de.value, "not transformed");
+
+    let err =
+        toml::from_str::<TestDefault>(r#"value = "${VALUE_test_case_transforms_literal_chars,,}""#)
+            .err()
+            .unwrap();
+    assert!(err
+        .message()
+        .contains("VALUE_test_case_transforms_literal_chars,,"));
+}
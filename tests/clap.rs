@@ -0,0 +1,78 @@
+use std::env;
+
+use clap::Parser;
+use serde_env_field::{EnvField, UseDeserialize};
+
+#[test]
+fn test_clap_expanded_args() {
+    #[derive(Parser)]
+    struct Cli {
+        #[arg(long, value_parser = EnvField::<String>::parse_expanded)]
+        host: EnvField<String>,
+
+        #[arg(long, value_parser = EnvField::<u16>::parse_expanded)]
+        port: EnvField<u16>,
+
+        #[arg(long, value_parser = EnvField::<String, UseDeserialize>::parse_expanded)]
+        name: Option<EnvField<String, UseDeserialize>>,
+    }
+
+    env::set_var("HOST_test_clap_expanded", "localhost");
+    env::set_var("PORT_test_clap_expanded", "8080");
+    let cli = Cli::parse_from([
+        "app",
+        "--host",
+        "${HOST_test_clap_expanded}.local",
+        "--port",
+        "$PORT_test_clap_expanded",
+    ]);
+
+    assert_eq!(&cli.host, "localhost.local");
+    assert_eq!(cli.port, 8080);
+    assert!(cli.name.is_none());
+
+    let cli = Cli::parse_from([
+        "app",
+        "--host",
+        "${HOST2_test_clap_expanded:-example.com}",
+        "--port",
+        "${PORT2_test_clap_expanded:-443}",
+        "--name",
+        "$HOST_test_clap_expanded",
+    ]);
+
+    assert_eq!(&cli.host, "example.com");
+    assert_eq!(cli.port, 443);
+    assert_eq!(cli.name.as_deref().unwrap(), "localhost");
+}
+
+#[test]
+fn test_clap_expansion_errors() {
+    #[derive(Parser, Debug)]
+    struct Cli {
+        #[arg(long, value_parser = EnvField::<u16>::parse_expanded)]
+        port: EnvField<u16>,
+    }
+
+    let err = Cli::try_parse_from(["app", "--port", "$PORT_test_clap_errors"]).unwrap_err();
+    assert!(err.to_string().contains("PORT_test_clap_errors"));
+
+    env::set_var("PORT_test_clap_errors", "not-a-port");
+    let err = Cli::try_parse_from(["app", "--port", "$PORT_test_clap_errors"]).unwrap_err();
+    assert!(err.to_string().contains("invalid value 'not-a-port'"));
+}
+
+#[test]
+fn test_clap_from_str_args() {
+    #[derive(Parser)]
+    struct Cli {
+        #[arg(long)]
+        pattern: EnvField<String>,
+    }
+
+    // The `FromStr` implementation takes the argument as is
+    env::set_var("HOME_test_clap_from_str", "/home/user");
+    let cli = Cli::parse_from(["app", "--pattern", "$HOME_test_clap_from_str/*.log"]);
+
+    assert_eq!(&cli.pattern, "$HOME_test_clap_from_str/*.log");
+}
@@ -0,0 +1,90 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+};
+
+use indoc::indoc;
+use serde::Deserialize;
+use serde_env_field::{EnvField, UseDotenvBlock};
+
+#[derive(Deserialize)]
+struct Test {
+    settings: EnvField<HashMap<String, String>, UseDotenvBlock>,
+}
+
+#[test]
+fn test_dotenv_block() {
+    env::set_var(
+        "SETTINGS_test_dotenv_block",
+        indoc! {r#"
+            # The connection settings
+            HOST=localhost
+              PORT = 5432
+
+            URL="postgres://localhost/db?ssl=true"
+            GREETING='Hello, World!'
+            EMPTY=
+            # DISABLED=1
+        "#},
+    );
+    env::set_var("DB_USER_test_dotenv_block", "admin");
+
+    let de: Test = toml::from_str(
+        r#"
+            settings = "USER=$DB_USER_test_dotenv_block\n$SETTINGS_test_dotenv_block"
+        "#,
+    )
+    .unwrap();
+
+    let expected = HashMap::from(
+        [
+            ("USER", "admin"),
+            ("HOST", "localhost"),
+            ("PORT", "5432"),
+            ("URL", "postgres://localhost/db?ssl=true"),
+            ("GREETING", "Hello, World!"),
+            ("EMPTY", ""),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string())),
+    );
+    assert_eq!(*de.settings, expected);
+}
+
+#[test]
+fn test_dotenv_block_not_string() {
+    #[derive(Deserialize)]
+    struct Test {
+        settings: EnvField<BTreeMap<String, String>, UseDotenvBlock>,
+    }
+
+    let de: Test = toml::from_str(
+        r#"
+            [settings]
+            HOST = "localhost"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        de.settings.into_inner(),
+        BTreeMap::from([("HOST".to_string(), "localhost".to_string())])
+    );
+}
+
+#[test]
+fn test_dotenv_block_invalid_line() {
+    let err = toml::from_str::<Test>(
+        r#"
+            settings = """
+                HOST=localhost
+                PORT
+            """
+        "#,
+    )
+    .err()
+    .unwrap();
+
+    assert!(err
+        .message()
+        .contains("invalid line 'PORT': expected KEY=VALUE"));
+}
@@ -0,0 +1,99 @@
+#![cfg(any(feature = "base64", feature = "hex"))]
+
+use std::env;
+
+use serde::{
+    de::value::{BytesDeserializer, Error as ValueError},
+    Deserialize, Serialize,
+};
+use serde_env_field::EnvField;
+
+#[cfg(feature = "base64")]
+mod base64 {
+    use super::*;
+    use serde_env_field::UseBase64;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        key: EnvField<Vec<u8>, UseBase64>,
+    }
+
+    #[test]
+    fn test_base64() {
+        env::set_var("KEY_test_base64", "aGVsbG8=");
+        let de: Test = toml::from_str(r#"key = "$KEY_test_base64""#).unwrap();
+        assert_eq!(*de.key, b"hello");
+
+        let de: Test = toml::from_str(r#"key = "${NO_KEY_test_base64:-AAE=}""#).unwrap();
+        assert_eq!(*de.key, [0, 1]);
+    }
+
+    #[test]
+    fn test_base64_bytes() {
+        let de: Test = serde_json::from_str(r#"{ "key": [1, 2, 3] }"#).unwrap();
+        assert_eq!(*de.key, [1, 2, 3]);
+
+        let serialized = serde_json::to_string(&de).unwrap();
+        let de: Test = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(*de.key, [1, 2, 3]);
+
+        let de =
+            EnvField::<Vec<u8>, UseBase64>::deserialize(BytesDeserializer::<ValueError>::new(&[
+                4, 5,
+            ]))
+            .unwrap();
+        assert_eq!(*de, [4, 5]);
+    }
+
+    #[test]
+    fn test_base64_invalid() {
+        env::set_var("KEY_test_base64_invalid", "not base64!");
+        let err = toml::from_str::<Test>(r#"key = "$KEY_test_base64_invalid""#).unwrap_err();
+
+        assert!(err
+            .message()
+            .contains("invalid base64 string 'not base64!'"));
+    }
+}
+
+#[cfg(feature = "hex")]
+mod hex {
+    use super::*;
+    use serde_env_field::UseHex;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        key: EnvField<Vec<u8>, UseHex>,
+    }
+
+    #[test]
+    fn test_hex() {
+        env::set_var("KEY_test_hex", "deadBEEF");
+        let de: Test = toml::from_str(r#"key = "$KEY_test_hex""#).unwrap();
+        assert_eq!(*de.key, [0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn test_hex_bytes() {
+        let de: Test = serde_json::from_str(r#"{ "key": [255, 0] }"#).unwrap();
+        assert_eq!(*de.key, [255, 0]);
+
+        let de =
+            EnvField::<Vec<u8>, UseHex>::deserialize(BytesDeserializer::<ValueError>::new(&[6]))
+                .unwrap();
+        assert_eq!(*de, [6]);
+    }
+
+    #[test]
+    fn test_hex_invalid() {
+        env::set_var(
+            "KEY_test_hex_invalid",
+            "0123456789abcdef0123456789abcdef0123456789abcdefzz",
+        );
+        let err = toml::from_str::<Test>(r#"key = "$KEY_test_hex_invalid""#).unwrap_err();
+
+        assert!(err
+            .message()
+            .contains("invalid hex string '0123456789abcdef0123456789abcdef...'"));
+    }
+}
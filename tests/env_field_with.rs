@@ -0,0 +1,64 @@
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use serde_env_field::{env_field_with, UseDeserialize};
+
+env_field_with!(mod size_env: u32);
+env_field_with!(mod hosts_env: Vec<String>, UseDeserialize);
+env_field_with!(
+    /// A module for the external type.
+    pub(crate) mod ip_env: std::net::IpAddr,
+);
+
+#[test]
+fn test_env_field_with() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        #[serde(with = "size_env")]
+        size: u32,
+
+        #[serde(with = "hosts_env")]
+        hosts: Vec<String>,
+
+        #[serde(with = "ip_env")]
+        ip: std::net::IpAddr,
+    }
+
+    env::set_var("SIZE_test_with", "42");
+    env::set_var("HOST_test_with", "example.com");
+    env::set_var("IP_test_with", "127.0.0.1");
+
+    let de: Test = toml::from_str(
+        r#"
+            size = "$SIZE_test_with"
+            hosts = ["$HOST_test_with", "localhost"]
+            ip = "$IP_test_with"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(de.size, 42);
+    assert_eq!(de.hosts, ["example.com", "localhost"]);
+    assert_eq!(de.ip.to_string(), "127.0.0.1");
+
+    // Serialized as is
+    assert_eq!(
+        toml::to_string(&de).unwrap(),
+        "size = 42\nhosts = [\"example.com\", \"localhost\"]\nip = \"127.0.0.1\"\n",
+    );
+}
+
+#[test]
+fn test_env_field_with_literal() {
+    #[derive(Deserialize)]
+    struct Test {
+        #[serde(with = "size_env")]
+        size: u32,
+    }
+
+    let de: Test = toml::from_str("size = 7").unwrap();
+    assert_eq!(de.size, 7);
+
+    let err = toml::from_str::<Test>(r#"size = "seven""#).err().unwrap();
+    assert!(err.message().contains("invalid value 'seven'"));
+}
@@ -3,7 +3,7 @@ use std::{assert_eq, env, str::FromStr};
 use derive_more::FromStr;
 use indoc::indoc;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_env_field::{EnvField, UseDeserialize};
+use serde_env_field::{EnvField, EnvFieldError, EnvFieldMap, UseDeserialize, UseFromStrDebug};
 
 fn de_se_de_test<T: Serialize + DeserializeOwned>(
     source_text: &'static str,
@@ -332,7 +332,7 @@ fn test_map_fields() {
         |de| {
             assert_eq!(de.map.n, 44);
             assert_eq!(&de.map.s, "Hello World");
-            assert_eq!(de.map.b, false);
+            assert!(!de.map.b);
         },
         indoc! {r#"
             [map]
@@ -350,7 +350,7 @@ fn test_map_fields() {
         |de| {
             assert_eq!(de.map.n, 1111);
             assert_eq!(&de.map.s, "Test Env String");
-            assert_eq!(de.map.b, true);
+            assert!(de.map.b);
         },
         indoc! {r#"
             [map]
@@ -411,6 +411,20 @@ fn test_not_existing_env_var() {
     );
 }
 
+#[test]
+fn test_not_existing_env_var_typed() {
+    env::remove_var("NOT_EXISTING_VAR_typed");
+    let err = EnvField::<i32>::parse_expanded("$NOT_EXISTING_VAR_typed").unwrap_err();
+
+    assert!(err.is_missing_var());
+    assert_eq!(err.missing_var_name(), Some("NOT_EXISTING_VAR_typed"));
+
+    let err = EnvField::<i32>::parse_expanded("NaN").unwrap_err();
+
+    assert!(!err.is_missing_var());
+    assert_eq!(err.missing_var_name(), None);
+}
+
 #[test]
 fn test_primitives() {
     #[derive(Serialize, Deserialize)]
@@ -447,7 +461,7 @@ fn test_primitives() {
             nf64 = 64.0
         "#,
         |de| {
-            assert_eq!(de.b, true);
+            assert!(*de.b);
             assert_eq!(de.c, 'A');
             assert_eq!(&de.s, "Hello");
             assert_eq!(de.ni8, -128);
@@ -509,7 +523,7 @@ fn test_primitives() {
             nf64 = "$F64_test_primitive"
         "#,
         |de| {
-            assert_eq!(de.b, false);
+            assert!(!*de.b);
             assert_eq!(de.c, 'S');
             assert_eq!(&de.s, "Goodbye");
             assert_eq!(de.ni8, -100);
@@ -541,6 +555,51 @@ fn test_primitives() {
     );
 }
 
+#[test]
+fn test_bool_expanded_to_number() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        verbose: EnvField<bool>,
+    }
+
+    env::set_var("BOOL_test_bool_number", "1");
+    let err = toml::from_str::<Test>(r#"verbose = "$BOOL_test_bool_number""#).unwrap_err();
+    assert!(err.message().contains(
+        "invalid value '1': provided string was not `true` or `false`; \
+        expected 'true'/'false' for bool field (enable UseBoolish for 1/0)"
+    ));
+
+    env::set_var("BOOL_test_bool_number", "true");
+    let de = toml::from_str::<Test>(r#"verbose = "$BOOL_test_bool_number""#).unwrap();
+    assert!(*de.verbose);
+}
+
+#[test]
+fn test_char_expanded_to_many() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        sep: EnvField<char>,
+    }
+
+    env::set_var("CHAR_test_char_many", "AB");
+    let err = toml::from_str::<Test>(r#"sep = "$CHAR_test_char_many""#).unwrap_err();
+    assert!(err.message().contains(
+        "invalid value 'AB': too many characters in string; \
+        expanded to 2 characters, expected exactly one"
+    ));
+
+    env::set_var("CHAR_test_char_many", "");
+    let err = toml::from_str::<Test>(r#"sep = "$CHAR_test_char_many""#).unwrap_err();
+    assert!(err.message().contains(
+        "invalid value '': cannot parse char from empty string; \
+        expanded to 0 characters, expected exactly one"
+    ));
+
+    env::set_var("CHAR_test_char_many", "é");
+    let de = toml::from_str::<Test>(r#"sep = "$CHAR_test_char_many""#).unwrap();
+    assert_eq!(de.sep, 'é');
+}
+
 #[test]
 fn test_use_deserialize() {
     #[derive(Serialize, Deserialize)]
@@ -580,3 +639,734 @@ fn test_use_deserialize() {
         "#},
     );
 }
+
+#[test]
+fn test_use_deserialize_unit_variants() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Test {
+        level: EnvField<Level, UseDeserialize>,
+        backend: EnvField<Backend, UseDeserialize>,
+        fallback: EnvField<Option<Backend>, UseDeserialize>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    enum Level {
+        Debug,
+        NotSoVerbose,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Backend {
+        #[serde(rename = "pg", alias = "postgres")]
+        Postgres,
+        #[serde(rename = "in-memory")]
+        InMemory,
+        File(String),
+    }
+
+    de_se_de_test::<Test>(
+        r#"
+            level = "NOT_SO_VERBOSE"
+            backend = "postgres"
+            fallback = "in-memory"
+        "#,
+        |de| {
+            assert_eq!(*de.level, Level::NotSoVerbose);
+            assert_eq!(*de.backend, Backend::Postgres);
+            assert_eq!(*de.fallback, Some(Backend::InMemory));
+        },
+        indoc! {r#"
+            level = "NOT_SO_VERBOSE"
+            backend = "pg"
+            fallback = "in-memory"
+        "#},
+    );
+
+    env::set_var("LEVEL_test_unit_variants", "DEBUG");
+    env::set_var("BACKEND_test_unit_variants", "memory");
+    let de: Test = serde_json::from_str(
+        r#"{
+            "level": "$LEVEL_test_unit_variants",
+            "backend": "in-${BACKEND_test_unit_variants}",
+            "fallback": "${FALLBACK_test_unit_variants:-pg}"
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(*de.level, Level::Debug);
+    assert_eq!(*de.backend, Backend::InMemory);
+    assert_eq!(*de.fallback, Some(Backend::Postgres));
+
+    env::set_var("BACKEND_test_unit_variants", "File");
+    let err = serde_json::from_str::<Test>(
+        r#"{
+            "level": "DEBUG",
+            "backend": "$BACKEND_test_unit_variants",
+            "fallback": null
+        }"#,
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("invalid type: unit variant, expected newtype variant"));
+
+    let err = serde_json::from_str::<Test>(
+        r#"{
+            "level": "Debug",
+            "backend": "pg",
+            "fallback": null
+        }"#,
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("unknown variant `Debug`, expected `DEBUG` or `NOT_SO_VERBOSE`"));
+}
+
+#[test]
+fn test_use_deserialize_inline_table() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        server: EnvField<Server, UseDeserialize>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Server {
+        host: String,
+        aliases: Vec<String>,
+        mode: Mode,
+        port: EnvField<u16>,
+        comment: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(rename_all = "kebab-case")]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    env::set_var("HOST_use_de_table", "localhost");
+    env::set_var("MODE_use_de_table", "slow");
+    env::set_var("PORT_use_de_table", "8080");
+    env::set_var("COMMENT_use_de_table", "costs $$5");
+
+    let de: Test = toml::from_str(
+        r#"
+            server = { host = "$HOST_use_de_table", aliases = ["${HOST_use_de_table}.com", "$$LITERAL"], mode = "$MODE_use_de_table", port = "$PORT_use_de_table", comment = "$COMMENT_use_de_table" }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(de.server.host, "localhost");
+    assert_eq!(de.server.aliases, ["localhost.com", "$LITERAL"]);
+    assert_eq!(de.server.mode, Mode::Slow);
+    assert_eq!(de.server.port, 8080);
+
+    // The expanded value isn't expanded once again
+    assert_eq!(de.server.comment.as_deref(), Some("costs $$5"));
+
+    let err = toml::from_str::<Test>(
+        r#"
+            server = { host = "$NOT_SET_use_de_table", aliases = [], mode = "fast", port = 80 }
+        "#,
+    )
+    .err()
+    .unwrap();
+
+    assert!(err.message().contains("NOT_SET_use_de_table"));
+}
+
+#[test]
+fn test_use_deserialize_enum_representations() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum External {
+        Unit,
+        Newtype(String),
+        Tuple(u16, String),
+        Struct { host: String, port: u16 },
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(tag = "type")]
+    enum Internal {
+        Unit,
+        Struct { host: String },
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(tag = "t", content = "c")]
+    enum Adjacent {
+        Unit,
+        Newtype(String),
+        Struct { host: String },
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(untagged)]
+    enum Untagged {
+        Pair(String, u16),
+        Named { host: String },
+    }
+
+    #[derive(Deserialize)]
+    struct Test {
+        external: Vec<EnvField<External, UseDeserialize>>,
+        internal: Vec<EnvField<Internal, UseDeserialize>>,
+        adjacent: Vec<EnvField<Adjacent, UseDeserialize>>,
+        untagged: Vec<EnvField<Untagged, UseDeserialize>>,
+    }
+
+    env::set_var("HOST_use_de_enums", "localhost");
+    let de: Test = serde_json::from_str(
+        r#"
+            {
+                "external": [
+                    "Unit",
+                    { "Newtype": "$HOST_use_de_enums" },
+                    { "Tuple": [80, "$HOST_use_de_enums"] },
+                    { "Struct": { "host": "$HOST_use_de_enums", "port": 80 } }
+                ],
+                "internal": [
+                    { "type": "Unit" },
+                    { "type": "Struct", "host": "$HOST_use_de_enums" }
+                ],
+                "adjacent": [
+                    { "t": "Unit" },
+                    { "t": "Newtype", "c": "$HOST_use_de_enums" },
+                    { "t": "Struct", "c": { "host": "$HOST_use_de_enums" } }
+                ],
+                "untagged": [
+                    ["$HOST_use_de_enums", 80],
+                    { "host": "$HOST_use_de_enums" }
+                ]
+            }
+        "#,
+    )
+    .unwrap();
+
+    let host = || "localhost".to_owned();
+    assert!(de.external.iter().map(|e| &**e).eq(&[
+        External::Unit,
+        External::Newtype(host()),
+        External::Tuple(80, host()),
+        External::Struct {
+            host: host(),
+            port: 80
+        },
+    ]));
+    assert!(de
+        .internal
+        .iter()
+        .map(|e| &**e)
+        .eq(&[Internal::Unit, Internal::Struct { host: host() }]));
+    assert!(de.adjacent.iter().map(|e| &**e).eq(&[
+        Adjacent::Unit,
+        Adjacent::Newtype(host()),
+        Adjacent::Struct { host: host() },
+    ]));
+    assert!(de
+        .untagged
+        .iter()
+        .map(|e| &**e)
+        .eq(&[Untagged::Pair(host(), 80), Untagged::Named { host: host() }]));
+}
+
+#[test]
+fn test_use_deserialize_adjacently_tagged() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(tag = "t", content = "c", rename_all = "lowercase")]
+    enum Source {
+        Off,
+        Unix(String),
+        Tcp {
+            host: String,
+            port: EnvField<u16>,
+            tags: Vec<EnvField<String>>,
+        },
+    }
+
+    #[derive(Deserialize)]
+    struct Test {
+        sources: Vec<EnvField<Source, UseDeserialize>>,
+    }
+
+    env::set_var("KIND_use_de_adjacent", "tcp");
+    env::set_var("HOST_use_de_adjacent", "example.com");
+    env::set_var("PORT_use_de_adjacent", "8080");
+
+    let tcp = || Source::Tcp {
+        host: "example.com".into(),
+        port: 8080.into(),
+        tags: vec!["example.com".to_owned().into(), "$HOME".to_owned().into()],
+    };
+
+    // The tag comes either before or after the content
+    let de: Test = serde_json::from_str(
+        r#"
+            {
+                "sources": [
+                    {
+                        "t": "$KIND_use_de_adjacent",
+                        "c": {
+                            "host": "$HOST_use_de_adjacent",
+                            "port": "$PORT_use_de_adjacent",
+                            "tags": ["${HOST_use_de_adjacent}", "$$HOME"]
+                        }
+                    },
+                    {
+                        "c": {
+                            "host": "$HOST_use_de_adjacent",
+                            "port": "$PORT_use_de_adjacent",
+                            "tags": ["${HOST_use_de_adjacent}", "$$HOME"]
+                        },
+                        "t": "$KIND_use_de_adjacent"
+                    },
+                    { "t": "unix", "c": "/run/$HOST_use_de_adjacent.sock" },
+                    { "c": "/run/$HOST_use_de_adjacent.sock", "t": "unix" },
+                    { "t": "${OFF_use_de_adjacent:-off}" }
+                ]
+            }
+        "#,
+    )
+    .unwrap();
+
+    let unix = || Source::Unix("/run/example.com.sock".into());
+    assert!(de.sources.iter().map(|source| &**source).eq(&[
+        tcp(),
+        tcp(),
+        unix(),
+        unix(),
+        Source::Off
+    ]));
+
+    let de: Test = toml::from_str(
+        r#"
+            [[sources]]
+            c = { host = "$HOST_use_de_adjacent", port = 8080, tags = ["$HOST_use_de_adjacent", "$$HOME"] }
+            t = "$KIND_use_de_adjacent"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(*de.sources[0], tcp());
+
+    let err = serde_json::from_str::<Test>(
+        r#"{ "sources": [{ "c": "$UNSET_use_de_adjacent", "t": "unix" }] }"#,
+    )
+    .err()
+    .unwrap();
+    assert!(err.to_string().contains("UNSET_use_de_adjacent"), "{err}");
+}
+
+#[test]
+fn test_use_from_str_debug() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        ratio: EnvField<Ratio, UseFromStrDebug>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Ratio(u32, u32);
+
+    #[derive(Debug)]
+    struct RatioError {
+        #[allow(dead_code)]
+        input: String,
+    }
+
+    impl FromStr for Ratio {
+        type Err = RatioError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let err = || RatioError {
+                input: s.to_string(),
+            };
+            let (num, denom) = s.split_once(':').ok_or_else(err)?;
+
+            Ok(Self(
+                num.parse().map_err(|_| err())?,
+                denom.parse().map_err(|_| err())?,
+            ))
+        }
+    }
+
+    env::set_var("RATIO_from_str_debug", "16:9");
+    let de: Test = toml::from_str(
+        r#"
+            ratio = "$RATIO_from_str_debug"
+        "#,
+    )
+    .unwrap();
+    assert_eq!((de.ratio.0, de.ratio.1), (16, 9));
+
+    let de: Test = toml::from_str(
+        r#"
+            ratio = [4, 3]
+        "#,
+    )
+    .unwrap();
+    assert_eq!((de.ratio.0, de.ratio.1), (4, 3));
+
+    env::set_var("RATIO_from_str_debug", "16/9");
+    let err = toml::from_str::<Test>(
+        r#"
+            ratio = "$RATIO_from_str_debug"
+        "#,
+    )
+    .err()
+    .unwrap();
+    assert!(err
+        .message()
+        .contains(r#"invalid value '16/9': RatioError { input: "16/9" }"#));
+}
+
+#[test]
+fn test_not_human_readable() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        num: EnvField<i32>,
+        s: EnvField<String>,
+        option: EnvField<Options, UseDeserialize>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    enum Options {
+        First,
+        Second,
+    }
+
+    env::set_var("STR_not_human_readable", "from env");
+    let test = Test {
+        num: (-42).into(),
+        s: "$STR_not_human_readable".to_string().into(),
+        option: Options::Second.into(),
+    };
+
+    let serialized = bincode::serialize(&test).unwrap();
+    let de: Test = bincode::deserialize(&serialized).unwrap();
+
+    assert_eq!(de.num, -42);
+    assert_eq!(&de.s, "$STR_not_human_readable");
+    assert!(matches!(*de.option, Options::Second));
+}
+
+#[test]
+fn test_toml_datetime() {
+    use toml::value::Datetime;
+
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        when: EnvField<Datetime>,
+    }
+
+    de_se_de_test::<Test>(
+        "when = 1979-05-27T07:32:00Z",
+        |de| {
+            assert_eq!(de.when.to_string(), "1979-05-27T07:32:00Z");
+        },
+        indoc! {r#"
+            when = 1979-05-27T07:32:00Z
+        "#},
+    );
+
+    env::set_var("WHEN_test_toml_datetime", "2024-01-02T03:04:05+02:00");
+    de_se_de_test::<Test>(
+        r#"when = "$WHEN_test_toml_datetime""#,
+        |de| {
+            assert_eq!(de.when.to_string(), "2024-01-02T03:04:05+02:00");
+        },
+        indoc! {r#"
+            when = 2024-01-02T03:04:05+02:00
+        "#},
+    );
+}
+
+#[test]
+fn test_url() {
+    use url::Url;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        url: EnvField<Url>,
+    }
+
+    env::remove_var("BASE_test_url");
+    de_se_de_test::<Test>(
+        r#"url = "${BASE_test_url:-http://localhost}/path""#,
+        |de| {
+            assert_eq!(de.url.as_str(), "http://localhost/path");
+            assert_eq!(de.url.path(), "/path");
+        },
+        indoc! {r#"
+            url = "http://localhost/path"
+        "#},
+    );
+
+    env::set_var("BASE_test_url", "https://example.com:8443");
+    de_se_de_test::<Test>(
+        r#"url = "${BASE_test_url:-http://localhost}/path""#,
+        |de| {
+            assert_eq!(de.url.as_str(), "https://example.com:8443/path");
+            assert_eq!(de.url.port(), Some(8443));
+        },
+        indoc! {r#"
+            url = "https://example.com:8443/path"
+        "#},
+    );
+
+    env::set_var("BASE_test_url", "not a url");
+    let err = toml::from_str::<Test>(r#"url = "${BASE_test_url}/path""#).unwrap_err();
+    assert!(err
+        .message()
+        .contains("invalid value 'not a url/path': relative URL without a base"));
+}
+
+#[test]
+fn test_option_inside() {
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        num: Option<EnvField<i32>>,
+        str: EnvField<Option<String>, UseDeserialize>,
+        inner_num: EnvField<Option<u32>, UseDeserialize>,
+    }
+
+    let de: Test = serde_json::from_str(
+        r#"{
+            "num": null,
+            "str": null,
+            "inner_num": null
+        }"#,
+    )
+    .unwrap();
+
+    assert!(de.num.is_none());
+    assert!(de.str.is_none());
+    assert!(de.inner_num.is_none());
+
+    env::set_var("NUM_test_option_inside", "-5");
+    env::set_var("STR_test_option_inside", "from env");
+    let de: Test = serde_json::from_str(
+        r#"{
+            "num": "$NUM_test_option_inside",
+            "str": "${STR_test_option_inside}!",
+            "inner_num": 5
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(de.num.unwrap(), -5);
+    assert_eq!(de.str.as_deref(), Some("from env!"));
+    assert_eq!(*de.inner_num, Some(5));
+
+    let de: Test = serde_json::from_str(
+        r#"{
+            "num": 17,
+            "str": "",
+            "inner_num": null
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(de.num.unwrap(), 17);
+    assert_eq!(de.str.as_deref(), Some(""));
+    assert!(de.inner_num.is_none());
+}
+
+#[test]
+fn test_unit() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Marker;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        unit: EnvField<(), UseDeserialize>,
+        marker: EnvField<Marker, UseDeserialize>,
+        sometimes: Option<EnvField<Marker, UseDeserialize>>,
+    }
+
+    let de: Test = serde_json::from_str(
+        r#"{
+            "unit": null,
+            "marker": null
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(*de.unit, ());
+    assert_eq!(*de.marker, Marker);
+    assert!(de.sometimes.is_none());
+
+    let de: Test = serde_json::from_str(
+        r#"{
+            "unit": null,
+            "marker": null,
+            "sometimes": null
+        }"#,
+    )
+    .unwrap();
+
+    assert!(de.sometimes.is_none());
+    assert_eq!(
+        serde_json::to_string(&de).unwrap(),
+        r#"{"unit":null,"marker":null,"sometimes":null}"#
+    );
+}
+
+#[test]
+fn test_flatten_map_keys() {
+    use std::collections::HashMap;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        name: EnvField<String>,
+
+        #[serde(flatten)]
+        extra: HashMap<EnvField<String>, EnvField<String>>,
+    }
+
+    env::set_var("DYNAMIC_KEY_test_flatten", "dynamic");
+    env::set_var("VALUE_test_flatten", "from env");
+
+    let de: Test = toml::from_str(
+        r#"
+            name = "$VALUE_test_flatten"
+            "$DYNAMIC_KEY_test_flatten" = "$VALUE_test_flatten"
+            "${MISSING_KEY_test_flatten:-static}" = "value"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(&de.name, "from env");
+    assert_eq!(de.extra.len(), 2);
+    assert_eq!(&de.extra["dynamic"], "from env");
+    assert_eq!(&de.extra["static"], "value");
+
+    let de: Test = serde_json::from_str(
+        r#"{
+            "name": "json",
+            "$DYNAMIC_KEY_test_flatten": "$VALUE_test_flatten"
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(&de.extra["dynamic"], "from env");
+}
+
+#[test]
+fn test_env_field_map() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        name: EnvField<String>,
+
+        #[serde(flatten)]
+        limits: EnvFieldMap<String, u32>,
+    }
+
+    env::set_var("FIRST_KEY_test_env_field_map", "requests");
+    env::set_var("SECOND_KEY_test_env_field_map", "connections");
+    env::set_var("LIMIT_test_env_field_map", "100");
+
+    let de: Test = toml::from_str(
+        r#"
+            name = "limits"
+            "$FIRST_KEY_test_env_field_map" = "$LIMIT_test_env_field_map"
+            "${SECOND_KEY_test_env_field_map}_max" = "${LIMIT_test_env_field_map}0"
+            static = 1
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(&de.name, "limits");
+    assert_eq!(de.limits.len(), 3);
+    assert_eq!(de.limits["requests"], 100);
+    assert_eq!(de.limits["connections_max"], 1000);
+    assert_eq!(de.limits["static"], 1);
+
+    let de: EnvFieldMap<String, String> = serde_json::from_str(
+        r#"{
+            "$FIRST_KEY_test_env_field_map": "$SECOND_KEY_test_env_field_map",
+            "$SECOND_KEY_test_env_field_map": "$FIRST_KEY_test_env_field_map"
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(&de["requests"], "connections");
+    assert_eq!(&de["connections"], "requests");
+}
+
+#[test]
+fn test_flatten_ignored_content() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        name: EnvField<String>,
+
+        #[serde(flatten)]
+        inner: Inner,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Inner {
+        port: EnvField<u16>,
+
+        #[serde(skip)]
+        skipped: EnvField<String>,
+    }
+
+    env::set_var("PORT_test_flatten_ignored", "8080");
+
+    // The unknown and skipped content is ignored without reaching the `EnvField`s
+    let de: Test = serde_json::from_str(
+        r#"{
+            "name": "example",
+            "port": "$PORT_test_flatten_ignored",
+            "skipped": "$MISSING_test_flatten_ignored",
+            "unknown": { "nested": ["$MISSING_test_flatten_ignored", 1, null] },
+            "other": 4.5
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(&de.name, "example");
+    assert_eq!(de.inner.port, 8080);
+    assert_eq!(&de.inner.skipped, "");
+
+    let de: Test = serde_yaml::from_str(indoc! {r#"
+        name: example
+        port: $PORT_test_flatten_ignored
+        skipped: [1, 2]
+        unknown:
+          nested: $MISSING_test_flatten_ignored
+    "#})
+    .unwrap();
+
+    assert_eq!(de.inner.port, 8080);
+    assert_eq!(&de.inner.skipped, "");
+}
+
+#[test]
+fn test_from_env_var() {
+    env::set_var("PORT_test_from_env_var", "8080");
+    let port: EnvField<u16> = EnvField::from_env_var("PORT_test_from_env_var").unwrap();
+    assert_eq!(port, 8080);
+
+    env::set_var("LITERAL_test_from_env_var", "$PORT_test_from_env_var");
+    let literal: EnvField<String> = EnvField::from_env_var("LITERAL_test_from_env_var").unwrap();
+    assert_eq!(&literal, "$PORT_test_from_env_var");
+
+    env::remove_var("MISSING_test_from_env_var");
+    assert!(matches!(
+        EnvField::<u16>::from_env_var("MISSING_test_from_env_var"),
+        Err(EnvFieldError::Lookup { var_name, .. }) if var_name == "MISSING_test_from_env_var"
+    ));
+    let port = EnvField::<u16>::from_env_var_or("MISSING_test_from_env_var", 80).unwrap();
+    assert_eq!(port, 80);
+
+    env::set_var("INVALID_test_from_env_var", "port");
+    let err = EnvField::<u16>::from_env_var_or("INVALID_test_from_env_var", 80).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value 'port' of the 'INVALID_test_from_env_var' variable: \
+            invalid digit found in string"
+    );
+}
@@ -0,0 +1,160 @@
+use std::env;
+
+use serde::Deserialize;
+use serde_env_field::{EnvField, EnvFieldError};
+
+fn expand(source: &str) -> String {
+    #[derive(Deserialize)]
+    struct Test {
+        s: EnvField<String>,
+    }
+
+    let de: Test = serde_json::from_value(serde_json::json!({ "s": source })).unwrap();
+    de.s.into_inner()
+}
+
+#[test]
+fn test_colon_default() {
+    env::remove_var("VAR_test_colon_default");
+    assert_eq!(expand("${VAR_test_colon_default:-default}"), "default");
+
+    env::set_var("VAR_test_colon_default", "");
+    assert_eq!(expand("${VAR_test_colon_default:-default}"), "default");
+
+    env::set_var("VAR_test_colon_default", "value");
+    assert_eq!(expand("${VAR_test_colon_default:-default}"), "value");
+}
+
+#[test]
+fn test_unset_default() {
+    env::remove_var("VAR_test_unset_default");
+    assert_eq!(expand("${VAR_test_unset_default-default}"), "default");
+
+    env::set_var("VAR_test_unset_default", "");
+    assert_eq!(expand("${VAR_test_unset_default-default}"), "");
+
+    env::set_var("VAR_test_unset_default", "value");
+    assert_eq!(expand("${VAR_test_unset_default-default}"), "value");
+}
+
+#[test]
+fn test_defaults_with_special_chars() {
+    env::remove_var("VAR_test_default_chars");
+    assert_eq!(expand("${VAR_test_default_chars:-a-b:-c}"), "a-b:-c");
+    assert_eq!(expand("${VAR_test_default_chars-}"), "");
+    assert_eq!(expand("${VAR_test_default_chars:-}"), "");
+}
+
+#[test]
+fn test_literals() {
+    env::set_var("VAR_test_literals", "value");
+
+    assert_eq!(expand("no variables"), "no variables");
+    assert_eq!(expand("$$VAR_test_literals"), "$VAR_test_literals");
+    assert_eq!(expand("cost: 5$"), "cost: 5$");
+    assert_eq!(expand("$ $VAR_test_literals"), "$ value");
+    assert_eq!(expand("${unbalanced"), "${unbalanced");
+    assert_eq!(expand("[${VAR_test_literals}]"), "[value]");
+}
+
+#[test]
+fn test_unbraced_name_suggestion() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Test {
+        s: EnvField<String>,
+    }
+
+    env::set_var("PREFIX_test_suggestion", "value");
+    env::remove_var("PREFIX_test_suggestion_dir");
+
+    let err = serde_json::from_str::<Test>(r#"{ "s": "$PREFIX_test_suggestion_dir/file" }"#)
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains(
+        "variable 'PREFIX_test_suggestion_dir' not found; \
+        did you mean '${PREFIX_test_suggestion}_dir'?"
+    ));
+
+    // The braced names are taken as is
+    let err = serde_json::from_str::<Test>(r#"{ "s": "${PREFIX_test_suggestion_dir}" }"#)
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("error looking key 'PREFIX_test_suggestion_dir' up"));
+    assert_eq!(expand("${PREFIX_test_suggestion}_dir"), "value_dir");
+}
+
+#[test]
+fn test_unbraced_name_suggestion_is_missing_var() {
+    env::set_var("PREFIX_test_suggestion_missing", "value");
+    env::remove_var("PREFIX_test_suggestion_missing_dir");
+    env::remove_var("OTHER_test_suggestion_missing");
+
+    let source = "$PREFIX_test_suggestion_missing_dir/$OTHER_test_suggestion_missing";
+
+    let err = EnvField::<String>::parse_expanded(source).unwrap_err();
+    assert!(err.is_missing_var());
+    assert_eq!(
+        err.missing_var_name(),
+        Some("PREFIX_test_suggestion_missing_dir")
+    );
+
+    let (result, errors) = EnvField::collect_errors(|| EnvField::<String>::parse_expanded(source));
+    assert_eq!(&result.unwrap(), "/");
+    assert_eq!(
+        errors,
+        [
+            EnvFieldError::Lookup {
+                var_name: "PREFIX_test_suggestion_missing_dir".to_owned(),
+                cause: env::VarError::NotPresent,
+                suggestion: Some("${PREFIX_test_suggestion_missing}_dir".to_owned()),
+            },
+            EnvFieldError::Lookup {
+                var_name: "OTHER_test_suggestion_missing".to_owned(),
+                cause: env::VarError::NotPresent,
+                suggestion: None,
+            },
+        ]
+    );
+
+    let err =
+        EnvField::report_all_missing(|| EnvField::<String>::parse_expanded(source)).unwrap_err();
+    let EnvFieldError::MultipleLookups(errors) = &err else {
+        panic!("expected all the missing variables, got: {err}");
+    };
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        err.to_string(),
+        "error looking keys up: \
+        'PREFIX_test_suggestion_missing_dir' (did you mean '${PREFIX_test_suggestion_missing}_dir'?), \
+        'OTHER_test_suggestion_missing' (environment variable not found)"
+    );
+}
+
+#[test]
+fn test_nested_defaults() {
+    let source = "${PRIMARY_test_nested:-${SECONDARY_test_nested:-localhost}}";
+
+    env::remove_var("PRIMARY_test_nested");
+    env::remove_var("SECONDARY_test_nested");
+    assert_eq!(expand(source), "localhost");
+
+    env::set_var("SECONDARY_test_nested", "secondary.host");
+    assert_eq!(expand(source), "secondary.host");
+
+    env::set_var("PRIMARY_test_nested", "primary.host");
+    assert_eq!(expand(source), "primary.host");
+
+    env::remove_var("PRIMARY_test_nested");
+    assert_eq!(
+        expand("${PRIMARY_test_nested-$SECONDARY_test_nested:8080}/api"),
+        "secondary.host:8080/api"
+    );
+    assert_eq!(
+        expand("${PRIMARY_test_nested:-${A_test_nested:-${B_test_nested-$$}}}"),
+        "$"
+    );
+}
@@ -0,0 +1,119 @@
+use std::env;
+
+use serde::{Deserialize, Deserializer};
+use serde_env_field::{EnvField, ExpandingDeserializer};
+
+fn expanding<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(ExpandingDeserializer::new(deserializer))
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Section {
+    name: String,
+    values: Vec<Option<String>>,
+    nested: Option<Box<Section>>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    #[serde(deserialize_with = "expanding")]
+    expanded: Section,
+
+    literal: Section,
+}
+
+#[test]
+fn test_expanding_region() {
+    env::set_var("NAME_test_expanding_region", "expanded name");
+    env::set_var("VALUE_test_expanding_region", "expanded value");
+
+    let config: Config = toml::from_str(
+        r#"
+            [expanded]
+            name = "$NAME_test_expanding_region"
+            values = ["$VALUE_test_expanding_region", "$$escaped"]
+            nested = { name = "${NAME_test_expanding_region}!", values = [] }
+
+            [literal]
+            name = "$NAME_test_expanding_region"
+            values = ["$VALUE_test_expanding_region", "$$escaped"]
+            nested = { name = "${NAME_test_expanding_region}!", values = [] }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            expanded: Section {
+                name: "expanded name".into(),
+                values: vec![Some("expanded value".into()), Some("$escaped".into())],
+                nested: Some(Box::new(Section {
+                    name: "expanded name!".into(),
+                    values: vec![],
+                    nested: None,
+                })),
+            },
+            literal: Section {
+                name: "$NAME_test_expanding_region".into(),
+                values: vec![
+                    Some("$VALUE_test_expanding_region".into()),
+                    Some("$$escaped".into()),
+                ],
+                nested: Some(Box::new(Section {
+                    name: "${NAME_test_expanding_region}!".into(),
+                    values: vec![],
+                    nested: None,
+                })),
+            },
+        }
+    );
+}
+
+#[test]
+fn test_expanding_region_env_fields() {
+    #[derive(Deserialize)]
+    struct Config {
+        #[serde(deserialize_with = "expanding")]
+        expanded: Section,
+    }
+
+    #[derive(Deserialize)]
+    struct Section {
+        port: EnvField<u16>,
+        escaped: EnvField<String>,
+    }
+
+    env::set_var("PORT_test_expanding_region_env_fields", "8080");
+
+    // The strings are expanded once, not by both the region and the `EnvField`
+    let config: Config = toml::from_str(
+        r#"
+            [expanded]
+            port = "$PORT_test_expanding_region_env_fields"
+            escaped = "$$$$HOME"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(*config.expanded.port, 8080);
+    assert_eq!(&config.expanded.escaped, "$$HOME");
+
+    let err = toml::from_str::<Config>(
+        r#"
+            [expanded]
+            port = "$UNSET_test_expanding_region_env_fields"
+            escaped = ""
+        "#,
+    )
+    .err()
+    .unwrap();
+    assert!(
+        err.message()
+            .contains("UNSET_test_expanding_region_env_fields"),
+        "{err}"
+    );
+}
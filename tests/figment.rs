@@ -0,0 +1,130 @@
+use std::env;
+
+use figment::{
+    providers::{Env, Format, Json, Toml},
+    Figment,
+};
+use serde::Deserialize;
+use serde_env_field::{env_field_wrap, EnvField, UseDeserialize};
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Deserialize, Debug)]
+struct Config {
+    name: EnvField<String>,
+    port: EnvField<u16>,
+    ratio: EnvField<f64>,
+    enabled: EnvField<bool>,
+    tags: Vec<EnvField<String>>,
+    timeout: Option<EnvField<u32>>,
+    server: EnvField<Server, UseDeserialize>,
+}
+
+#[test]
+fn test_figment_toml() {
+    env::set_var("NAME_test_figment_toml", "figment");
+    env::set_var("PORT_test_figment_toml", "8080");
+    env::set_var("ENABLED_test_figment_toml", "true");
+
+    let config: Config = Figment::from(Toml::string(
+        r#"
+            name = "${NAME_test_figment_toml}-app"
+            port = "$PORT_test_figment_toml"
+            ratio = 0.5
+            enabled = "$ENABLED_test_figment_toml"
+            tags = ["$NAME_test_figment_toml", "$$literal"]
+            server = { host = "$NAME_test_figment_toml.local", port = 443 }
+        "#,
+    ))
+    .extract()
+    .unwrap();
+
+    assert_eq!(&config.name, "figment-app");
+    assert_eq!(*config.port, 8080);
+    assert_eq!(*config.ratio, 0.5);
+    assert!(*config.enabled);
+    assert!(config
+        .tags
+        .iter()
+        .map(|tag| tag.as_str())
+        .eq(["figment", "$literal"]));
+    assert!(config.timeout.is_none());
+    assert_eq!(
+        *config.server,
+        Server {
+            host: "figment.local".into(),
+            port: 443,
+        }
+    );
+}
+
+#[test]
+fn test_figment_json() {
+    env::set_var("NAME_test_figment_json", "figment");
+    env::set_var("TIMEOUT_test_figment_json", "30");
+
+    let config: Config = Figment::from(Json::string(
+        r#"{
+            "name": "$NAME_test_figment_json",
+            "port": 8080,
+            "ratio": "${RATIO_test_figment_json:-0.25}",
+            "enabled": false,
+            "tags": [],
+            "timeout": "$TIMEOUT_test_figment_json",
+            "server": { "host": "${NAME_test_figment_json}.local", "port": 443 }
+        }"#,
+    ))
+    .extract()
+    .unwrap();
+
+    assert_eq!(&config.name, "figment");
+    assert_eq!(*config.port, 8080);
+    assert_eq!(*config.ratio, 0.25);
+    assert!(!*config.enabled);
+    assert!(config.tags.is_empty());
+    assert_eq!(config.timeout.as_deref(), Some(&30));
+    assert_eq!(&config.server.host, "figment.local");
+
+    let err = Figment::from(Json::string(r#"{ "name": "$UNSET_test_figment_json" }"#))
+        .extract::<Config>()
+        .unwrap_err();
+    assert!(err.to_string().contains("UNSET_test_figment_json"), "{err}");
+}
+
+#[test]
+fn test_figment_env() {
+    #[env_field_wrap]
+    #[derive(Deserialize)]
+    struct Config {
+        name: String,
+        port: u16,
+        url: String,
+        timeout: Option<u32>,
+    }
+
+    env::set_var("HOST_test_figment_env", "example.com");
+    env::set_var("FIGMENT_ENV_NAME", "from-env");
+    env::set_var("FIGMENT_ENV_URL", "https://${HOST_test_figment_env}");
+    env::set_var("FIGMENT_ENV_TIMEOUT", "${TIMEOUT_test_figment_env:-60}");
+
+    // The environment overrides the document, and its values are expanded too
+    let config: Config = Figment::from(Toml::string(
+        r#"
+            name = "from-toml"
+            port = "${PORT_test_figment_env:-8080}"
+            url = "http://localhost"
+        "#,
+    ))
+    .merge(Env::prefixed("FIGMENT_ENV_"))
+    .extract()
+    .unwrap();
+
+    assert_eq!(&config.name, "from-env");
+    assert_eq!(*config.port, 8080);
+    assert_eq!(&config.url, "https://example.com");
+    assert_eq!(config.timeout.as_deref(), Some(&60));
+}
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, env};
+
+use serde::Deserialize;
+use serde_env_field::{env_field_wrap, from_env, serde_env_field};
+
+#[test]
+fn test_from_env() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "kebab-case")]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    #[derive(Deserialize)]
+    struct Test {
+        name: String,
+        size: usize,
+        ratio: f64,
+        verbose: bool,
+        mode: Mode,
+        ports: Vec<u16>,
+        comment: Option<String>,
+        #[serde(default)]
+        retries: u32,
+    }
+
+    env::set_var("TEST_FROM_ENV_NAME", "$literal");
+    env::set_var("TEST_FROM_ENV_SIZE", "64");
+    env::set_var("TEST_FROM_ENV_RATIO", "0.5");
+    env::set_var("TEST_FROM_ENV_VERBOSE", "true");
+    env::set_var("TEST_FROM_ENV_MODE", "slow");
+    env::set_var("TEST_FROM_ENV_PORTS", "80,443");
+    env::remove_var("TEST_FROM_ENV_COMMENT");
+    env::remove_var("TEST_FROM_ENV_RETRIES");
+
+    let de: Test = from_env("TEST_FROM_ENV_").unwrap();
+
+    assert_eq!(de.name, "$literal");
+    assert_eq!(de.size, 64);
+    assert_eq!(de.ratio, 0.5);
+    assert!(de.verbose);
+    assert_eq!(de.mode, Mode::Slow);
+    assert_eq!(de.ports, [80, 443]);
+    assert_eq!(de.comment, None);
+    assert_eq!(de.retries, 0);
+
+    // The sequences are split the same way as by the `UseSplit`
+    env::set_var("TEST_FROM_ENV_PORTS", "80,443,");
+    let de: Test = from_env("TEST_FROM_ENV_").unwrap();
+    assert_eq!(de.ports, [80, 443]);
+
+    env::set_var("TEST_FROM_ENV_PORTS", "");
+    let de: Test = from_env("TEST_FROM_ENV_").unwrap();
+    assert!(de.ports.is_empty());
+
+    env::set_var("TEST_FROM_ENV_PORTS", "80,,443");
+    assert!(from_env::<Test>("TEST_FROM_ENV_").is_err());
+}
+
+#[test]
+fn test_from_env_expands_env_fields() {
+    #[env_field_wrap]
+    #[derive(Deserialize)]
+    struct Test {
+        host: String,
+        url: String,
+        port: u16,
+    }
+
+    env::set_var("TEST_EXPAND_HOST", "example.com");
+    env::set_var(
+        "TEST_EXPAND_URL",
+        "https://${TEST_EXPAND_HOST}:${TEST_EXPAND_PORT}",
+    );
+    env::set_var("TEST_EXPAND_PORT", "8443");
+
+    let de: Test = from_env("TEST_EXPAND_").unwrap();
+
+    assert_eq!(&de.host, "example.com");
+    assert_eq!(&de.url, "https://example.com:8443");
+    assert_eq!(de.port, 8443);
+}
+
+#[test]
+fn test_from_env_rename_env() {
+    #[serde_env_field]
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "kebab-case")]
+    struct Test {
+        name: String,
+
+        #[serde_env_field(rename_env = "LEGACY_DB_URL_test_rename_env")]
+        database_url: String,
+
+        #[serde(rename = "retries")]
+        #[serde_env_field(rename_env = "LEGACY_RETRIES_test_rename_env")]
+        max_retries: Option<u32>,
+    }
+
+    env::set_var("TEST_RENAME_ENV_NAME", "name");
+    env::set_var("LEGACY_DB_URL_test_rename_env", "postgres://legacy");
+    env::remove_var("LEGACY_RETRIES_test_rename_env");
+
+    // The derived variables of the renamed fields are ignored
+    env::set_var("TEST_RENAME_ENV_DATABASE-URL", "postgres://derived");
+    env::set_var("TEST_RENAME_ENV_RETRIES", "5");
+
+    let de: Test = from_env("TEST_RENAME_ENV_").unwrap();
+    assert_eq!(de.name, "name");
+    assert_eq!(de.database_url, "postgres://legacy");
+    assert_eq!(de.max_retries, None);
+
+    env::set_var("LEGACY_RETRIES_test_rename_env", "many");
+    assert_eq!(
+        from_env::<Test>("TEST_RENAME_ENV_")
+            .unwrap_err()
+            .to_string(),
+        "invalid value of the 'LEGACY_RETRIES_test_rename_env' variable: invalid value 'many': invalid digit found in string",
+    );
+
+    // The documents keep using the serde names
+    let de: Test = toml::from_str(
+        r#"
+            name = "name"
+            database-url = "postgres://localhost"
+            retries = 3
+        "#,
+    )
+    .unwrap();
+    assert_eq!(de.database_url, "postgres://localhost");
+    assert_eq!(de.max_retries, Some(3));
+}
+
+#[test]
+fn test_from_env_map() {
+    env::set_var("TEST_FROM_ENV_MAP_FIRST", "1");
+    env::set_var("TEST_FROM_ENV_MAP_SECOND", "2");
+
+    let de: HashMap<String, u8> = from_env("TEST_FROM_ENV_MAP_").unwrap();
+
+    assert_eq!(
+        de,
+        HashMap::from([("first".to_string(), 1), ("second".to_string(), 2)])
+    );
+}
+
+#[test]
+fn test_from_env_errors() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Test {
+        name: String,
+        size: usize,
+    }
+
+    env::set_var("TEST_FROM_ENV_ERR_NAME", "name");
+    env::remove_var("TEST_FROM_ENV_ERR_SIZE");
+    assert_eq!(
+        from_env::<Test>("TEST_FROM_ENV_ERR_")
+            .unwrap_err()
+            .to_string(),
+        "missing field `size`",
+    );
+
+    env::set_var("TEST_FROM_ENV_ERR_SIZE", "large");
+    assert_eq!(
+        from_env::<Test>("TEST_FROM_ENV_ERR_")
+            .unwrap_err()
+            .to_string(),
+        "invalid value of the 'TEST_FROM_ENV_ERR_SIZE' variable: invalid digit found in string",
+    );
+}
@@ -0,0 +1,91 @@
+use std::{env, str::FromStr};
+
+use serde::Deserialize;
+use serde_env_field::{EnvField, FromExpandedStr, UseFromExpanded};
+
+/// A list of the `key=value` pairs.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(transparent)]
+struct Labels(Vec<(String, String)>);
+
+impl FromStr for Labels {
+    type Err = String;
+
+    fn from_str(_: &str) -> Result<Self, Self::Err> {
+        Err("FromStr isn't used".into())
+    }
+}
+
+impl FromExpandedStr for Labels {
+    fn from_expanded(expanded: &str) -> Result<Self, String> {
+        expanded
+            .split(',')
+            .filter(|label| !label.is_empty())
+            .map(|label| {
+                let (key, value) = label
+                    .split_once('=')
+                    .ok_or_else(|| format!("no value of the '{label}' label"))?;
+
+                Ok((key.trim().to_owned(), value.trim().to_owned()))
+            })
+            .collect::<Result<_, String>>()
+            .map(Self)
+    }
+}
+
+fn labels(pairs: &[(&str, &str)]) -> Labels {
+    Labels(
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+    )
+}
+
+#[derive(Deserialize, Debug)]
+struct Test {
+    labels: EnvField<Labels, UseFromExpanded>,
+}
+
+#[test]
+fn test_from_expanded() {
+    env::set_var("ENV_test_from_expanded", "prod");
+    env::set_var("LABELS_test_from_expanded", "team=core, tier=1");
+
+    let de: Test = toml::from_str(
+        r#"
+            labels = "env=$ENV_test_from_expanded,$LABELS_test_from_expanded"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        *de.labels,
+        labels(&[("env", "prod"), ("team", "core"), ("tier", "1")])
+    );
+
+    // The data the `T` is deserialized from directly is handed over to it
+    let de: Test = toml::from_str(r#"labels = [["env", "$ENV_test_from_expanded"]]"#).unwrap();
+    assert_eq!(*de.labels, labels(&[("env", "$ENV_test_from_expanded")]));
+}
+
+#[test]
+fn test_from_expanded_errors() {
+    env::set_var("LABELS_test_from_expanded_errors", "team=core,tier");
+
+    let err = toml::from_str::<Test>(r#"labels = "$LABELS_test_from_expanded_errors""#)
+        .err()
+        .unwrap();
+    assert!(
+        err.message()
+            .contains("invalid value 'team=core,tier': no value of the 'tier' label"),
+        "{err}"
+    );
+
+    let err = toml::from_str::<Test>(r#"labels = "$UNSET_test_from_expanded_errors""#)
+        .err()
+        .unwrap();
+    assert!(
+        err.message().contains("UNSET_test_from_expanded_errors"),
+        "{err}"
+    );
+}
@@ -0,0 +1,68 @@
+//! Property-based tests making sure no input can panic the expansion.
+
+use serde::Deserialize;
+use serde_env_field::{EnvField, UseDeserialize};
+
+use proptest::prelude::*;
+
+#[derive(Deserialize)]
+struct Test {
+    _s: EnvField<String>,
+    _n: EnvField<i32>,
+    _d: EnvField<String, UseDeserialize>,
+}
+
+/// Strings dense with the expansion syntax characters.
+fn template() -> impl Strategy<Value = String> {
+    "[${}:\\-_aZ0é🦀 ]{0,64}"
+}
+
+fn try_expand(source: &str) -> Result<String, serde_json::Error> {
+    #[derive(Deserialize)]
+    struct Test {
+        s: EnvField<String>,
+    }
+
+    serde_json::from_value::<Test>(serde_json::json!({ "s": source })).map(|de| de.s.into_inner())
+}
+
+proptest! {
+    #[test]
+    fn test_any_template_never_panics(source in template()) {
+        let value = serde_json::json!({ "_s": source, "_n": source, "_d": source });
+        let _ = serde_json::from_value::<Test>(value);
+    }
+
+    #[test]
+    fn test_any_bytes_never_panic(bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+        let source = String::from_utf8_lossy(&bytes);
+        let _ = try_expand(&source);
+
+        let mut json = br#"{"_s": ""#.to_vec();
+        json.extend_from_slice(&bytes);
+        json.extend_from_slice(br#"", "_n": 0, "_d": ""}"#);
+        let _ = serde_json::from_slice::<Test>(&json);
+    }
+
+    #[test]
+    fn test_no_dollar_is_identity(source in "[^$]{0,64}") {
+        prop_assert_eq!(try_expand(&source).unwrap(), source);
+    }
+
+    #[test]
+    fn test_escaped_dollar_is_literal(source in template()) {
+        let escaped = source.replace('$', "$$");
+        prop_assert_eq!(try_expand(&escaped).unwrap(), source);
+    }
+
+    #[test]
+    fn test_default_of_unset_var(default in "[^$}]{0,32}") {
+        std::env::remove_var("UNSET_test_fuzz");
+
+        let colon_default = try_expand(&format!("${{UNSET_test_fuzz:-{default}}}")).unwrap();
+        prop_assert_eq!(colon_default, default.clone());
+
+        let unset_default = try_expand(&format!("${{UNSET_test_fuzz-{default}}}")).unwrap();
+        prop_assert_eq!(unset_default, default);
+    }
+}
@@ -0,0 +1,73 @@
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use serde_env_field::{EnvField, UseGrouped};
+
+#[derive(Serialize, Deserialize)]
+struct Test {
+    size: EnvField<u64, UseGrouped>,
+    offset: EnvField<i32, UseGrouped<','>>,
+    ratio: EnvField<f64, UseGrouped<','>>,
+}
+
+fn grouped(source: &str) -> Result<(u64, i32, f64), toml::de::Error> {
+    toml::from_str::<Test>(source).map(|de| (*de.size, *de.offset, *de.ratio))
+}
+
+#[test]
+fn test_grouped() {
+    env::set_var("SIZE_test_grouped", "1_000");
+    env::set_var("OFFSET_test_grouped", "-1,000");
+
+    assert_eq!(
+        grouped(
+            r#"
+                size = "${SIZE_test_grouped}_000"
+                offset = "$OFFSET_test_grouped"
+                ratio = "1,234.5"
+            "#
+        )
+        .unwrap(),
+        (1_000_000, -1_000, 1_234.5),
+    );
+
+    assert_eq!(
+        grouped(
+            r#"
+                size = "42"
+                offset = 7
+                ratio = 0.5
+            "#
+        )
+        .unwrap(),
+        (42, 7, 0.5),
+    );
+}
+
+#[test]
+fn test_grouped_misplaced_separator() {
+    for size in ["_1000", "1000_", "1__000"] {
+        let err = grouped(&format!(
+            r#"
+                size = "{size}"
+                offset = 0
+                ratio = 0.0
+            "#
+        ))
+        .unwrap_err();
+
+        assert!(err.message().contains(&format!(
+            "invalid value '{size}': invalid digit found in string"
+        )));
+    }
+
+    // The underscore isn't a separator when another one is configured
+    grouped(
+        r#"
+            size = 1
+            offset = "1_000"
+            ratio = 0.0
+        "#,
+    )
+    .unwrap_err();
+}
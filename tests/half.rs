@@ -0,0 +1,40 @@
+#![cfg(feature = "half")]
+
+use std::env;
+
+use half::f16;
+use serde::Deserialize;
+use serde_env_field::EnvField;
+
+#[derive(Deserialize, Debug)]
+struct Test {
+    value: EnvField<f16>,
+}
+
+fn value(source: &str) -> Result<f16, toml::de::Error> {
+    toml::from_str::<Test>(source).map(|de| de.value.into_inner())
+}
+
+#[test]
+fn test_f16() {
+    assert_eq!(value("value = 1.5").unwrap(), f16::from_f32(1.5));
+    assert_eq!(value("value = 2").unwrap(), f16::from_f32(2.0));
+    assert_eq!(value(r#"value = "0.25""#).unwrap(), f16::from_f32(0.25));
+
+    env::set_var("VALUE_test_f16", "-3.5");
+    assert_eq!(
+        value(r#"value = "$VALUE_test_f16""#).unwrap(),
+        f16::from_f32(-3.5)
+    );
+}
+
+#[test]
+fn test_f16_errors() {
+    env::set_var("VALUE_test_f16_errors", "fast");
+    let err = value(r#"value = "$VALUE_test_f16_errors""#).unwrap_err();
+    assert!(err.message().contains("invalid value 'fast'"));
+
+    // The overflowing values saturate to the infinity, just like the `f16::from_f32`
+    assert_eq!(value(r#"value = "1e10""#).unwrap(), f16::INFINITY);
+    assert_eq!(value("value = -1e10").unwrap(), f16::NEG_INFINITY);
+}
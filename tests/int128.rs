@@ -0,0 +1,83 @@
+use std::env;
+
+use serde::Deserialize;
+use serde_env_field::EnvField;
+
+#[derive(Deserialize, Debug)]
+struct Test {
+    signed: EnvField<i128>,
+    unsigned: EnvField<u128>,
+}
+
+fn json(source: &str) -> Result<(i128, u128), serde_json::Error> {
+    serde_json::from_str::<Test>(source).map(|de| (*de.signed, *de.unsigned))
+}
+
+#[test]
+fn test_int128_strings() {
+    env::set_var("SIGNED_test_int128_strings", i128::MIN.to_string());
+    env::set_var("UNSIGNED_test_int128_strings", u128::MAX.to_string());
+
+    let source = r#"{
+        "signed": "$SIGNED_test_int128_strings",
+        "unsigned": "${UNSIGNED_test_int128_strings}"
+    }"#;
+    assert_eq!(json(source).unwrap(), (i128::MIN, u128::MAX));
+
+    let de: Test = toml::from_str(&format!(
+        r#"
+            signed = "{}"
+            unsigned = "{}"
+        "#,
+        i128::MAX,
+        u128::MAX - 1
+    ))
+    .unwrap();
+    assert_eq!(*de.signed, i128::MAX);
+    assert_eq!(*de.unsigned, u128::MAX - 1);
+
+    // One past the bounds
+    let err = json(r#"{"signed": "170141183460469231731687303715884105728", "unsigned": "0"}"#)
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("number too large to fit in target type"));
+}
+
+#[test]
+fn test_int128_native() {
+    // The integers fitting into 64 bits are native in all the formats
+    assert_eq!(
+        json(&format!(
+            r#"{{"signed": {}, "unsigned": {}}}"#,
+            i64::MIN,
+            u64::MAX
+        ))
+        .unwrap(),
+        (i64::MIN.into(), u64::MAX.into())
+    );
+
+    let de: Test =
+        serde_yaml::from_str(&format!("signed: {}\nunsigned: {}", i128::MIN, u128::MAX)).unwrap();
+    assert_eq!(*de.signed, i128::MIN);
+    assert_eq!(*de.unsigned, u128::MAX);
+
+    // JSON reads the larger integers as the floats, so the precision is already lost
+    let err = json(&format!(r#"{{"signed": {}, "unsigned": 0}}"#, i128::MAX)).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("the 128-bit integer was read as the float `1.7014118346046923e38`"));
+
+    let err = json(&format!(r#"{{"signed": 0, "unsigned": {}}}"#, u128::MAX)).unwrap_err();
+    assert!(err.to_string().contains("pass it as a string instead"));
+
+    // The other types aren't affected by the large floats
+    let de: EnvField<u64> = serde_json::from_str("1e19").unwrap();
+    assert_eq!(de, 10_000_000_000_000_000_000);
+
+    // The small integral floats are still accepted
+    assert_eq!(
+        json(r#"{"signed": -1e3, "unsigned": 1e3}"#).unwrap(),
+        (-1000, 1000)
+    );
+}
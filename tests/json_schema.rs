@@ -0,0 +1,49 @@
+#![cfg(feature = "schemars")]
+
+use schemars::{schema_for, JsonSchema};
+use serde::Deserialize;
+use serde_env_field::{env_field_wrap, AlwaysString, EnvField, UseFromStr};
+use serde_json::json;
+
+#[test]
+fn test_env_field_schema() {
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Test {
+        num: EnvField<i32>,
+        name: Option<EnvField<String>>,
+        pin: EnvField<u32, UseFromStr, AlwaysString>,
+    }
+
+    let schema = schema_for!(Test);
+    let properties = &schema.as_value()["properties"];
+
+    assert_eq!(
+        properties["num"]["anyOf"][0],
+        json!({ "type": "integer", "format": "int32" })
+    );
+    assert_eq!(properties["num"]["anyOf"][1]["type"], "string");
+    assert_eq!(properties["pin"]["type"], "string");
+    assert_eq!(schema.as_value()["required"], json!(["num", "pin"]));
+}
+
+#[test]
+fn test_wrapped_schema() {
+    #[env_field_wrap]
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Test {
+        size: usize,
+        seq: Vec<bool>,
+    }
+
+    let schema = schema_for!(Test);
+    let properties = &schema.as_value()["properties"];
+
+    assert_eq!(properties["size"]["anyOf"][1]["type"], "string");
+    assert_eq!(
+        properties["seq"]["items"]["anyOf"][0],
+        json!({ "type": "boolean" })
+    );
+    assert_eq!(properties["seq"]["items"]["anyOf"][1]["type"], "string");
+}
@@ -0,0 +1,102 @@
+use std::env;
+
+use serde::Deserialize;
+use serde_env_field::{EnvField, UseDeserialize};
+use serde_json::{json, Map, Value};
+
+#[derive(Deserialize)]
+struct Test {
+    value: EnvField<Value, UseDeserialize>,
+}
+
+fn value(source: &str) -> Value {
+    serde_json::from_str::<Test>(source)
+        .unwrap()
+        .value
+        .into_inner()
+}
+
+#[test]
+fn test_json_value_deep_expansion() {
+    env::set_var("HOST_test_json_value", "example.com");
+    env::set_var("PORT_test_json_value", "8080");
+
+    let expanded = value(
+        r#"{
+            "value": {
+                "$HOST_test_json_value": "keys are kept",
+                "servers": [
+                    {
+                        "host": "$HOST_test_json_value",
+                        "ports": ["$PORT_test_json_value", 443, null],
+                        "tags": [[["${TAG_test_json_value:-deep}"]]]
+                    },
+                    "http://${HOST_test_json_value}:$PORT_test_json_value"
+                ],
+                "nested": { "a": { "b": { "c": { "d": "$$PORT_test_json_value" } } } },
+                "flag": true,
+                "ratio": 0.5
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        expanded,
+        json!({
+            "$HOST_test_json_value": "keys are kept",
+            "servers": [
+                {
+                    "host": "example.com",
+                    "ports": ["8080", 443, null],
+                    "tags": [[["deep"]]]
+                },
+                "http://example.com:8080"
+            ],
+            "nested": { "a": { "b": { "c": { "d": "$PORT_test_json_value" } } } },
+            "flag": true,
+            "ratio": 0.5
+        })
+    );
+
+    // The strings stay strings, even if they look like numbers after the expansion
+    assert_eq!(
+        value(r#"{ "value": "$PORT_test_json_value" }"#),
+        json!("8080")
+    );
+    assert_eq!(
+        value(r#"{ "value": ["$PORT_test_json_value"] }"#),
+        json!(["8080"])
+    );
+}
+
+#[test]
+fn test_json_map_expansion() {
+    #[derive(Deserialize)]
+    struct Test {
+        map: EnvField<Map<String, Value>, UseDeserialize>,
+    }
+
+    env::set_var("NAME_test_json_map", "from env");
+
+    let de: Test = toml::from_str(
+        r#"
+            [map]
+            name = "$NAME_test_json_map"
+            list = [{ inner = "${NAME_test_json_map}!" }]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(de.map["name"], "from env");
+    assert_eq!(de.map["list"], json!([{ "inner": "from env!" }]));
+
+    let err = toml::from_str::<Test>(
+        r#"
+            [map]
+            list = [{ inner = "$MISSING_test_json_map" }]
+        "#,
+    )
+    .err()
+    .unwrap();
+    assert!(err.message().contains("MISSING_test_json_map"));
+}
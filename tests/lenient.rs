@@ -0,0 +1,73 @@
+use std::{env, net::IpAddr};
+
+use serde::Deserialize;
+use serde_env_field::{EnvField, UseLenient};
+
+#[derive(Deserialize, Debug)]
+struct Test {
+    port: EnvField<Result<u16, String>, UseLenient>,
+    address: EnvField<Result<IpAddr, String>, UseLenient>,
+    workers: Vec<EnvField<Result<u8, String>, UseLenient>>,
+}
+
+#[test]
+fn test_lenient() {
+    env::set_var("PORT_test_lenient", "8080");
+    env::set_var("ADDRESS_test_lenient", "127.0.0.1");
+
+    let de: Test = toml::from_str(
+        r#"
+            port = "$PORT_test_lenient"
+            address = "$ADDRESS_test_lenient"
+            workers = [4, "${WORKERS_test_lenient:-8}"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(*de.port, Ok(8080));
+    assert_eq!(*de.address, Ok([127, 0, 0, 1].into()));
+    assert!(de.workers.iter().map(|w| &**w).eq(&[Ok(4), Ok(8)]));
+}
+
+#[test]
+fn test_lenient_invalid_values() {
+    env::set_var("PORT_test_lenient_invalid_values", "80a");
+
+    // The struct still deserializes, holding the errors
+    let de: Test = toml::from_str(
+        r#"
+            port = "$PORT_test_lenient_invalid_values"
+            address = "localhost"
+            workers = [4, 1000, "many"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        de.port.as_ref().unwrap_err(),
+        "invalid value '80a': invalid digit found in string"
+    );
+    assert_eq!(
+        de.address.as_ref().unwrap_err(),
+        "invalid value 'localhost': invalid IP address syntax"
+    );
+    assert!(de.workers.iter().map(|w| &**w).eq(&[
+        Ok(4),
+        Err("invalid value '1000': number too large to fit in target type".into()),
+        Err("invalid value 'many': invalid digit found in string".into()),
+    ]));
+
+    // The lookup errors still fail the deserialization
+    let err = toml::from_str::<Test>(
+        r#"
+            port = "$UNSET_test_lenient_invalid_values"
+            address = "::1"
+            workers = []
+        "#,
+    )
+    .unwrap_err();
+    assert!(
+        err.message().contains("UNSET_test_lenient_invalid_values"),
+        "{err}"
+    );
+}
@@ -4,8 +4,7 @@ use serde_env_field::EnvField;
 fn test_eq() {
     let field: EnvField<i32> = 10.into();
 
-    // assert_eq!(10, field); -- can't implement
-
+    assert_eq!(10, field);
     assert_eq!(field, 10);
     assert_eq!(10, *field);
 }
@@ -14,19 +13,38 @@ fn test_eq() {
 fn test_ord() {
     let field: EnvField<i32> = 10.into();
 
-    // assert!(<num> <op> field); -- can't implement
-
     assert!(field > 9);
     assert!(9 < *field);
+    assert!(9 < field);
 
     assert!(field >= 9);
     assert!(9 <= *field);
+    assert!(9 <= field);
 
     assert!(field < 11);
     assert!(11 > *field);
+    assert!(11 > field);
 
     assert!(field <= 10);
     assert!(10 >= *field);
+    assert!(10 >= field);
+}
+
+#[test]
+fn test_reverse_cmp() {
+    let float: EnvField<f64> = 1.5.into();
+    assert!(2.0 > float);
+    assert_eq!(1.5, float);
+
+    let unsigned: EnvField<u64> = 7.into();
+    assert!(7 <= unsigned);
+    assert_ne!(8, unsigned);
+
+    let string: EnvField<String> = "b".to_string().into();
+    let lesser = "a".to_string();
+    let equal = "b".to_string();
+    assert!(lesser < string);
+    assert_eq!(equal, string);
 }
 
 #[test]
@@ -44,6 +62,15 @@ fn test_add() {
     assert_eq!(field + field, 20);
 }
 
+#[test]
+fn test_add_str() {
+    let field: EnvField<String> = "http://".to_string().into();
+    let host = "example.com".to_string();
+
+    assert_eq!(field.clone() + "localhost", "http://localhost");
+    assert_eq!(field + &host + ":8080", "http://example.com:8080");
+}
+
 #[test]
 fn test_sub() {
     let field: EnvField<i32> = 10.into();
@@ -76,6 +103,27 @@ fn test_rem() {
     assert_eq!(field % field, 0);
 }
 
+#[test]
+fn test_reverse_arithmetic() {
+    let field: EnvField<i32> = 10.into();
+
+    assert_eq!(12 + field, 22);
+    assert_eq!(12 - field, 2);
+    assert_eq!(12 * field, 120);
+    assert_eq!(12 / field, 1);
+    assert_eq!(12 % field, 2);
+
+    let float: EnvField<f64> = 0.5.into();
+    assert_eq!(2.0 + float, 2.5);
+    assert_eq!(2.0 - float, 1.5);
+    assert_eq!(2.0 * float, 1.0);
+    assert_eq!(2.0 / float, 4.0);
+    assert_eq!(2.0 % float, 0.0);
+
+    let unsigned: EnvField<u64> = 3.into();
+    assert_eq!(7u64 + unsigned, 10);
+}
+
 #[test]
 fn test_neg() {
     let field: EnvField<i32> = 10.into();
@@ -123,6 +171,17 @@ fn test_shr() {
     assert_eq!(field >> field, 0x0);
 }
 
+#[test]
+fn test_reverse_bit_ops() {
+    let field: EnvField<i32> = 0x2.into();
+
+    assert_eq!(0xA & field, 0x2);
+    assert_eq!(0x1 | field, 0x3);
+    assert_eq!(0xA ^ field, 0x8);
+    assert_eq!(0xA << field, 0x28);
+    assert_eq!(0xA >> field, 0x2);
+}
+
 #[test]
 fn test_bit_not() {
     let field: EnvField<i32> = 0xA.into();
@@ -139,6 +198,16 @@ fn test_add_assign() {
     assert_eq!(field, 32);
 }
 
+#[test]
+fn test_add_assign_str() {
+    let mut field: EnvField<String> = "http://".to_string().into();
+    let port = ":8080".to_string();
+    field += "example.com";
+    field += &port;
+
+    assert_eq!(&field, "http://example.com:8080");
+}
+
 #[test]
 fn test_sub_assign() {
     let mut field: EnvField<i32> = 10.into();
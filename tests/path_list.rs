@@ -0,0 +1,83 @@
+use std::{env, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_env_field::{EnvField, UsePathList};
+
+#[derive(Serialize, Deserialize)]
+struct Test {
+    paths: EnvField<Vec<PathBuf>, UsePathList>,
+}
+
+fn paths(source: &str) -> Vec<PathBuf> {
+    toml::from_str::<Test>(source).unwrap().paths.into_inner()
+}
+
+fn path_bufs<const N: usize>(paths: [&str; N]) -> Vec<PathBuf> {
+    paths.into_iter().map(PathBuf::from).collect()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_path_list_unix() {
+    env::set_var("PATHS_test_path_list_unix", "/usr/bin:/bin");
+
+    assert_eq!(
+        paths(r#"paths = "${PATHS_test_path_list_unix}:/opt/bin""#),
+        path_bufs(["/usr/bin", "/bin", "/opt/bin"])
+    );
+
+    // The empty segments mean the current directory
+    assert_eq!(
+        paths(r#"paths = ":/usr/bin::/bin:""#),
+        path_bufs([".", "/usr/bin", ".", "/bin", "."])
+    );
+
+    // The semicolon isn't a separator
+    assert_eq!(paths(r#"paths = "a;b""#), path_bufs(["a;b"]));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_path_list_windows() {
+    env::set_var("PATHS_test_path_list_windows", r"C:\Windows;C:\Tools");
+
+    assert_eq!(
+        paths(r#"paths = '${PATHS_test_path_list_windows};D:\bin'"#),
+        path_bufs([r"C:\Windows", r"C:\Tools", r"D:\bin"])
+    );
+
+    // The empty segments are ignored
+    assert_eq!(
+        paths(r#"paths = ';C:\Windows;;C:\Tools;'"#),
+        path_bufs([r"C:\Windows", r"C:\Tools"])
+    );
+
+    // The quoted segments may contain the separator
+    assert_eq!(
+        paths(r#"paths = '"C:\My;Tools";C:\bin'"#),
+        path_bufs([r"C:\My;Tools", r"C:\bin"])
+    );
+}
+
+#[test]
+fn test_path_list_joined() {
+    let joined = env::join_paths(["first", "second/dir"]).unwrap();
+    env::set_var("PATHS_test_path_list_joined", joined);
+
+    assert_eq!(
+        paths(r#"paths = "$PATHS_test_path_list_joined""#),
+        path_bufs(["first", "second/dir"])
+    );
+
+    env::set_var("EMPTY_test_path_list_joined", "");
+    assert!(paths(r#"paths = "$EMPTY_test_path_list_joined""#).is_empty());
+}
+
+#[test]
+fn test_path_list_not_string() {
+    let de: Test = toml::from_str(r#"paths = ["a", "b/c"]"#).unwrap();
+    assert_eq!(*de.paths, path_bufs(["a", "b/c"]));
+
+    // Serialized as a sequence
+    assert_eq!(toml::to_string(&de).unwrap(), "paths = [\"a\", \"b/c\"]\n");
+}
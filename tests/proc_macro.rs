@@ -5,7 +5,7 @@ use std::{assert_eq, env, str::FromStr, unreachable};
 use derive_more::FromStr;
 use indoc::indoc;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_env_field::{env_field_wrap, EnvField, UseDeserialize};
+use serde_env_field::{env_field_wrap, EnvField, NoExpand, UseDeserialize};
 
 fn de_se_de_test<T: Serialize + DeserializeOwned>(
     source_text: &'static str,
@@ -185,6 +185,58 @@ fn test_wrap_optional_fields() {
     );
 }
 
+#[test]
+fn test_wrap_double_option_fields() {
+    use serde::Deserializer;
+
+    // Tells the present null (`Some(None)`) from the absent field (`None`)
+    fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        Option::deserialize(deserializer).map(Some)
+    }
+
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        #[serde(default, deserialize_with = "double_option")]
+        timeout: Option<Option<u32>>,
+
+        #[serde(default, deserialize_with = "double_option")]
+        name: Option<std::option::Option<String>>,
+    }
+
+    env::set_var("TIMEOUT_test_wrap_double_option", "30");
+    de_se_de_json_test::<Test>(
+        r#"{
+            "timeout": "$TIMEOUT_test_wrap_double_option",
+            "name": "${NAME_test_wrap_double_option:-default}"
+        }"#,
+        |de| {
+            let timeout: &Option<Option<EnvField<u32>>> = &de.timeout;
+            let name: &Option<Option<EnvField<String>>> = &de.name;
+
+            assert!(matches!(timeout, Some(Some(timeout)) if **timeout == 30));
+            assert!(matches!(name, Some(Some(name)) if name == "default"));
+        },
+        indoc! {r#"
+            {
+              "timeout": 30,
+              "name": "default"
+            }"#},
+    );
+
+    let de: Test = serde_json::from_str(r#"{ "timeout": null, "name": null }"#).unwrap();
+    assert!(matches!(de.timeout, Some(None)));
+    assert!(matches!(de.name, Some(None)));
+
+    let de: Test = serde_json::from_str("{}").unwrap();
+    assert!(de.timeout.is_none());
+    assert!(de.name.is_none());
+}
+
 #[test]
 fn test_wrap_default_fields() {
     #[env_field_wrap]
@@ -245,6 +297,71 @@ fn test_wrap_default_fields() {
     );
 }
 
+#[test]
+fn test_wrap_default_attr_order() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        #[serde(default)]
+        optional: Option<String>,
+
+        #[serde(default)]
+        seq: Vec<i32>,
+
+        #[serde(default)]
+        plain: String,
+
+        #[serde(default)]
+        #[env_field_wrap(skip)]
+        skipped: String,
+
+        #[serde(default)]
+        #[env_field_wrap(generics_only)]
+        default_first: Option<u16>,
+
+        #[env_field_wrap(generics_only)]
+        #[serde(default)]
+        wrap_first: Vec<u16>,
+    }
+
+    let de: Test = toml::from_str("").unwrap();
+    assert!(de.optional.is_none());
+    assert!(de.seq.is_empty());
+    assert!(de.plain.is_empty());
+    assert!(de.skipped.is_empty());
+    assert!(de.default_first.is_none());
+    assert!(de.wrap_first.is_empty());
+
+    env::set_var("STR_test_default_attr_order", "from env");
+    env::set_var("NUM_test_default_attr_order", "3");
+    de_se_de_test::<Test>(
+        r#"
+            optional = "$STR_test_default_attr_order"
+            seq = ["$NUM_test_default_attr_order"]
+            plain = "$STR_test_default_attr_order"
+            skipped = "$STR_test_default_attr_order"
+            default_first = "$NUM_test_default_attr_order"
+            wrap_first = ["$NUM_test_default_attr_order"]
+        "#,
+        |de| {
+            assert_eq!(de.optional.as_ref().unwrap(), "from env");
+            assert!(de.seq.iter().eq([3].iter()));
+            assert_eq!(&de.plain, "from env");
+            assert_eq!(de.skipped, "$STR_test_default_attr_order");
+            assert_eq!(de.default_first.unwrap(), 3);
+            assert!(de.wrap_first.iter().eq([3].iter()));
+        },
+        indoc! {r#"
+            optional = "from env"
+            seq = [3]
+            plain = "from env"
+            skipped = "$STR_test_default_attr_order"
+            default_first = 3
+            wrap_first = [3]
+        "#},
+    );
+}
+
 #[test]
 fn test_wrap_seq_fields() {
     #[env_field_wrap]
@@ -436,7 +553,7 @@ fn test_wrap_map_fields() {
         |de| {
             assert_eq!(de.map.n, 44);
             assert_eq!(&de.map.s, "Hello World");
-            assert_eq!(de.map.b, false);
+            assert!(!de.map.b);
         },
         indoc! {r#"
             [map]
@@ -454,7 +571,7 @@ fn test_wrap_map_fields() {
         |de| {
             assert_eq!(de.map.n, 1111);
             assert_eq!(&de.map.s, "Test Env String");
-            assert_eq!(de.map.b, true);
+            assert!(de.map.b);
         },
         indoc! {r#"
             [map]
@@ -502,7 +619,7 @@ fn test_wrap_primitives() {
             nf64 = 64.0
         "#,
         |de| {
-            assert_eq!(de.b, true);
+            assert!(de.b);
             assert_eq!(de.c, 'A');
             assert_eq!(&de.s, "Hello");
             assert_eq!(de.ni8, -128);
@@ -564,7 +681,7 @@ fn test_wrap_primitives() {
             nf64 = "$F64_test_primitive"
         "#,
         |de| {
-            assert_eq!(de.b, false);
+            assert!(!de.b);
             assert_eq!(de.c, 'S');
             assert_eq!(&de.s, "Goodbye");
             assert_eq!(de.ni8, -100);
@@ -624,6 +741,188 @@ fn test_wrap_skip() {
     );
 }
 
+#[test]
+fn test_wrap_skip_expand() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        wrapped: String,
+
+        #[env_field_wrap(skip_expand)]
+        literal: String,
+
+        #[env_field_wrap(skip_expand)]
+        literal_num: Option<i32>,
+    }
+
+    env::set_var("WRAPPED_test_skip_expand", "From Env");
+    env::set_var("LITERAL_test_skip_expand", "From Env");
+    de_se_de_test::<Test>(
+        r#"
+            wrapped = "$WRAPPED_test_skip_expand"
+            literal = "$LITERAL_test_skip_expand and ${LITERAL_test_skip_expand:-default}"
+            literal_num = "42"
+        "#,
+        |de| {
+            let literal: &EnvField<String, NoExpand> = &de.literal;
+            let literal_num: &Option<EnvField<i32, NoExpand>> = &de.literal_num;
+
+            assert_eq!(&de.wrapped, "From Env");
+            assert_eq!(
+                literal.as_str(),
+                "$LITERAL_test_skip_expand and ${LITERAL_test_skip_expand:-default}"
+            );
+            assert_eq!(literal_num.as_deref(), Some(&42));
+        },
+        indoc! {r#"
+            wrapped = "From Env"
+            literal = "$LITERAL_test_skip_expand and ${LITERAL_test_skip_expand:-default}"
+            literal_num = 42
+        "#},
+    );
+
+    let err = toml::from_str::<Test>(
+        r#"
+            wrapped = ""
+            literal = ""
+            literal_num = "$NUM_test_skip_expand"
+        "#,
+    )
+    .err()
+    .unwrap();
+
+    assert!(err
+        .message()
+        .contains("invalid value '$NUM_test_skip_expand'"));
+}
+
+#[test]
+fn test_wrap_into() {
+    #[env_field_wrap]
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        #[env_field_wrap(into = Mode)]
+        mode: String,
+
+        #[env_field_wrap(into = "Vec<u8>")]
+        bytes: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    impl TryFrom<String> for Mode {
+        type Error = String;
+
+        fn try_from(value: String) -> Result<Self, Self::Error> {
+            match value.as_str() {
+                "fast" => Ok(Self::Fast),
+                "slow" => Ok(Self::Slow),
+                _ => Err(format!("unknown mode '{value}'")),
+            }
+        }
+    }
+
+    env::set_var("MODE_test_wrap_into", "slow");
+    let de: Test = toml::from_str(
+        r#"
+            mode = "$MODE_test_wrap_into"
+            bytes = "ab"
+        "#,
+    )
+    .unwrap();
+
+    let mode: Mode = de.mode;
+    assert_eq!(mode, Mode::Slow);
+    assert_eq!(de.bytes, b"ab");
+
+    let err = toml::from_str::<Test>(
+        r#"
+            mode = "average"
+            bytes = ""
+        "#,
+    )
+    .unwrap_err();
+
+    assert!(err.message().contains("unknown mode 'average'"));
+}
+
+#[test]
+fn test_wrap_serialize_with_env() {
+    #[env_field_wrap(serialize_with_env)]
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        host: String,
+        port: u16,
+        aliases: Vec<String>,
+        comment: Option<String>,
+
+        #[env_field_wrap(skip)]
+        skipped: String,
+    }
+
+    env::set_var("HOST_test_serialize_with_env", "localhost");
+    env::set_var("PORT_test_serialize_with_env", "8080");
+
+    let source = indoc! {r#"
+        host = "$HOST_test_serialize_with_env"
+        port = "${PORT_test_serialize_with_env}"
+        aliases = ["${HOST_test_serialize_with_env}.com", "example.com"]
+        skipped = "$SKIPPED"
+    "#};
+
+    let de: Test = toml::from_str(source).unwrap();
+
+    assert_eq!(de.host.try_get().unwrap(), "localhost");
+    assert_eq!(*de.port.try_get().unwrap(), 8080);
+    assert_eq!(de.aliases[0].try_get().unwrap(), "localhost.com");
+    assert_eq!(de.aliases[1].try_get().unwrap(), "example.com");
+    assert!(de.comment.is_none());
+    assert_eq!(de.skipped, "$SKIPPED");
+
+    // The templates are serialized back instead of the values
+    assert_eq!(
+        toml::to_string(&de).unwrap(),
+        indoc! {r#"
+            host = "$HOST_test_serialize_with_env"
+            port = "${PORT_test_serialize_with_env}"
+            aliases = ["${HOST_test_serialize_with_env}.com", "example.com"]
+            skipped = "$SKIPPED"
+        "#},
+    );
+}
+
+#[test]
+fn test_wrap_derive() {
+    use std::collections::HashSet;
+
+    #[env_field_wrap(derive(PartialEq, Eq, Hash))]
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        host: String,
+        port: u16,
+    }
+
+    env::set_var("HOST_test_wrap_derive", "localhost");
+    let servers: Vec<Test> = serde_json::from_str(
+        r#"
+            [
+                { "host": "$HOST_test_wrap_derive", "port": 80 },
+                { "host": "localhost", "port": "80" },
+                { "host": "localhost", "port": 443 }
+            ]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(servers[0], servers[1]);
+    assert_ne!(servers[0], servers[2]);
+    assert_eq!(servers.iter().collect::<HashSet<_>>().len(), 2);
+}
+
 #[test]
 fn test_wrap_generics_only() {
     #[env_field_wrap]
@@ -678,6 +977,55 @@ fn test_wrap_generics_only() {
     .unwrap_err();
 }
 
+#[test]
+fn test_wrap_generics_only_paths() {
+    mod nested {
+        pub mod generic {
+            #[derive(serde::Serialize, serde::Deserialize)]
+            pub struct Pair<A, B> {
+                pub first: A,
+                pub second: B,
+            }
+        }
+    }
+
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        #[env_field_wrap(generics_only)]
+        map: ::std::collections::BTreeMap<String, u32>,
+
+        #[env_field_wrap(generics_only)]
+        pair: nested::generic::Pair<i64, bool>,
+    }
+
+    env::set_var("MAP_test_wrap_generics_only_paths", "7");
+    env::set_var("PAIR_test_wrap_generics_only_paths", "-7");
+    de_se_de_test::<Test>(
+        r#"
+            [map]
+            answer = "$MAP_test_wrap_generics_only_paths"
+
+            [pair]
+            first = "$PAIR_test_wrap_generics_only_paths"
+            second = "true"
+        "#,
+        |de| {
+            assert_eq!(de.map["answer"], 7);
+            assert_eq!(de.pair.first, -7);
+            assert!(*de.pair.second);
+        },
+        indoc! {r#"
+            [map]
+            answer = 7
+
+            [pair]
+            first = -7
+            second = true
+        "#},
+    );
+}
+
 #[test]
 fn test_wrap_tuple_struct() {
     #[env_field_wrap]
@@ -711,7 +1059,7 @@ fn test_wrap_tuple_struct() {
         "#,
         |de| {
             assert_eq!(de.0.a, 333);
-            assert_eq!(de.0.b, true);
+            assert!(de.0.b);
             assert_eq!(&de.1, "$WRAPPED_tup");
             assert_eq!(de.2.unwrap(), 333);
             assert!(de.3.iter().eq([true, false, false].iter()));
@@ -736,92 +1084,915 @@ fn test_wrap_tuple_struct() {
 }
 
 #[test]
-fn test_wrap_enum() {
+fn test_wrap_tuple_fields() {
     #[env_field_wrap]
     #[derive(Serialize, Deserialize)]
-    enum Test {
-        Wrapped(String),
+    struct Test {
+        pair: (String, i32),
+        single: (u8,),
+        nested: (Option<bool>, Vec<i32>, (String, EnvField<u16>)),
+    }
 
-        #[env_field_wrap(skip)]
-        Skipped {
-            inner_str: String,
+    env::set_var("STR_test_tuple", "env string");
+    env::set_var("NUM_test_tuple", "-17");
+    env::set_var("BOOL_test_tuple", "true");
+    de_se_de_json_test::<Test>(
+        r#"
+            {
+                "pair": ["$STR_test_tuple", "$NUM_test_tuple"],
+                "single": ["${U8_test_tuple:-8}"],
+                "nested": ["$BOOL_test_tuple", [1, "$NUM_test_tuple"], ["$STR_test_tuple", 16]]
+            }
+        "#,
+        |de| {
+            assert_eq!(&de.pair.0, "env string");
+            assert_eq!(de.pair.1, -17);
+            assert_eq!(de.single.0, 8);
+            assert!(de.nested.0.unwrap().into_inner());
+            assert!(de.nested.1.iter().eq([1, -17].iter()));
+            assert_eq!(&de.nested.2 .0, "env string");
+            assert_eq!(de.nested.2 .1, 16);
         },
+        indoc! {r#"
+            {
+              "pair": [
+                "env string",
+                -17
+              ],
+              "single": [
+                8
+              ],
+              "nested": [
+                true,
+                [
+                  1,
+                  -17
+                ],
+                [
+                  "env string",
+                  16
+                ]
+              ]
+            }"#
+        },
+    );
+}
 
-        Inner(
-            #[env_field_wrap(skip)] String,
-            Option<i32>,
-            Vec<bool>,
-            #[env_field_wrap(generics_only)] TwoGenerics<i32, bool>,
-        ),
-    }
-
-    #[derive(Serialize, Deserialize)]
-    struct TwoGenerics<A, B> {
-        a: A,
-        b: B,
+#[test]
+fn test_wrap_result() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        port: Result<u16, String>,
+        fallback: std::result::Result<i32, String>,
     }
 
-    env::set_var("WRAPPED_enum", "Enum String From Env");
+    env::set_var("PORT_test_wrap_result", "8080");
     de_se_de_test::<Test>(
         r#"
-            Wrapped = "$WRAPPED_enum"
+            port = { Ok = "$PORT_test_wrap_result" }
+            fallback = { Err = "$NOT_EXPANDED" }
         "#,
         |de| {
-            assert!(matches![de, Test::Wrapped(s) if s == "Enum String From Env"]);
+            assert_eq!(de.port.as_ref().map(|port| **port), Ok(8080));
+            assert_eq!(de.fallback.as_ref().unwrap_err(), "$NOT_EXPANDED");
         },
         indoc! {r#"
-            Wrapped = "Enum String From Env"
+            [port]
+            Ok = 8080
+
+            [fallback]
+            Err = "$NOT_EXPANDED"
         "#},
     );
+}
 
-    de_se_de_json_test::<Test>(
+#[test]
+fn test_wrap_only() {
+    #[env_field_wrap(only(wrapped, also_wrapped))]
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        wrapped: String,
+        also_wrapped: Option<i32>,
+        not_listed: String,
+        not_listed_num: i32,
+
+        #[env_field_wrap(generics_only)]
+        explicit: Vec<u8>,
+    }
+
+    env::set_var("STR_test_only", "from env");
+    env::set_var("NUM_test_only", "10");
+    de_se_de_test::<Test>(
         r#"
-            {
-                "Skipped": {
-                    "inner_str": "$WRAPPED_enum"
-                }
-            }
+            wrapped = "$STR_test_only"
+            also_wrapped = "$NUM_test_only"
+            not_listed = "$STR_test_only"
+            not_listed_num = 5
+            explicit = ["$NUM_test_only"]
         "#,
         |de| {
-            assert!(matches![de, Test::Skipped { inner_str } if inner_str == "$WRAPPED_enum"]);
-        },
-        indoc! {
-            r#"
-            {
-              "Skipped": {
-                "inner_str": "$WRAPPED_enum"
-              }
-            }"#
+            let not_listed: &String = &de.not_listed;
+            let not_listed_num: i32 = de.not_listed_num;
+
+            assert_eq!(&de.wrapped, "from env");
+            assert_eq!(de.also_wrapped.unwrap(), 10);
+            assert_eq!(not_listed, "$STR_test_only");
+            assert_eq!(not_listed_num, 5);
+            assert!(de.explicit.iter().eq([10].iter()));
         },
+        indoc! {r#"
+            wrapped = "from env"
+            also_wrapped = 10
+            not_listed = "$STR_test_only"
+            not_listed_num = 5
+            explicit = [10]
+        "#},
     );
 
-    env::set_var("NUM_enum", "117");
-    env::set_var("BOOL_enum", "false");
-    de_se_de_json_test::<Test>(
+    toml::from_str::<Test>(
         r#"
-            {
-                "Inner": [
-                    "$WRAPPED_enum",
-                    "$NUM_enum",
-                    [true, true, "$BOOL_enum"],
-                    {
-                        "a": "$NUM_enum",
-                        "b": "$BOOL_enum"
-                    }
-                ]
-            }
+            wrapped = "x"
+            not_listed = "y"
+            not_listed_num = "$NUM_test_only"
+            explicit = []
         "#,
-        |de| {
-            assert!(matches![de, Test::Inner(_, _, _, _)]);
-            let Test::Inner(skipped, o, v, g) = de else {
-                unreachable!()
-            };
+    )
+    .unwrap_err();
+}
+
+#[test]
+#[cfg(feature = "indexmap")]
+fn test_wrap_index_map() {
+    use indexmap::IndexMap;
+
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        ports: IndexMap<String, u16>,
+        hosts: indexmap::IndexMap<String, String>,
+    }
+
+    env::set_var("PORT_test_index_map", "8080");
+    env::set_var("HOST_test_index_map", "example.com");
+    de_se_de_test::<Test>(
+        r#"
+            [ports]
+            zeta = "$PORT_test_index_map"
+            alpha = 80
+            mid = "${MISSING_PORT_test_index_map:-443}"
+
+            [hosts]
+            "$KEY_test_index_map" = "$HOST_test_index_map"
+            local = "localhost"
+        "#,
+        |de| {
+            assert!(de.ports.iter().map(|(k, v)| (k.as_str(), **v)).eq([
+                ("zeta", 8080),
+                ("alpha", 80),
+                ("mid", 443)
+            ]));
+
+            assert!(de.hosts.iter().map(|(k, v)| (k.as_str(), v.as_str())).eq([
+                ("$KEY_test_index_map", "example.com"),
+                ("local", "localhost")
+            ]));
+        },
+        indoc! {r#"
+            [ports]
+            zeta = 8080
+            alpha = 80
+            mid = 443
+
+            [hosts]
+            "$KEY_test_index_map" = "example.com"
+            local = "localhost"
+        "#},
+    );
+}
+
+#[test]
+fn test_wrap_expand_keys() {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        #[env_field_wrap(expand_keys)]
+        ports: BTreeMap<String, u16>,
+
+        #[env_field_wrap(expand_keys)]
+        weights: std::collections::HashMap<String, f64>,
+
+        // The keys already wrapped are kept as is
+        #[env_field_wrap(expand_keys)]
+        literal: BTreeMap<EnvField<String, NoExpand>, String>,
+    }
+
+    env::set_var("KEY_test_wrap_expand_keys", "http");
+    env::set_var("PORT_test_wrap_expand_keys", "8080");
+    env::set_var("NUM_test_wrap_expand_keys", "3");
+    de_se_de_test::<Test>(
+        r#"
+            [ports]
+            "$KEY_test_wrap_expand_keys" = "$PORT_test_wrap_expand_keys"
+            "${MISSING_KEY_test_wrap_expand_keys:-https}" = 443
+
+            [weights]
+            "node-$NUM_test_wrap_expand_keys" = "0.${NUM_test_wrap_expand_keys}"
+
+            [literal]
+            "$KEY_test_wrap_expand_keys" = "$KEY_test_wrap_expand_keys"
+        "#,
+        |de| {
+            let ports: &BTreeMap<EnvField<String>, EnvField<u16>> = &de.ports;
+            let weights: &HashMap<EnvField<String>, EnvField<f64>> = &de.weights;
+
+            assert!(ports
+                .iter()
+                .map(|(k, v)| (k.as_str(), **v))
+                .eq([("http", 8080), ("https", 443)]));
+            assert_eq!(weights["node-3"], 0.3);
+            assert_eq!(&de.literal["$KEY_test_wrap_expand_keys"], "http");
+        },
+        indoc! {r#"
+            [ports]
+            http = 8080
+            https = 443
+
+            [weights]
+            node-3 = 0.3
+
+            [literal]
+            "$KEY_test_wrap_expand_keys" = "http"
+        "#},
+    );
+}
+
+#[test]
+fn test_wrap_recursive() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    struct Node {
+        name: String,
+
+        // The `Self` isn't wrapped, its own fields are
+        children: Vec<Self>,
+        next: Option<Box<Self>>,
+
+        // Unless it is deserialized
+        #[env_field_wrap(use_deserialize)]
+        deserialized: Option<Box<Self>>,
+    }
+
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    enum Expr {
+        Num(i32),
+        Add(Box<Self>, Box<Self>),
+    }
+
+    env::set_var("NAME_test_wrap_recursive", "node");
+    env::set_var("NUM_test_wrap_recursive", "2");
+    de_se_de_json_test::<Node>(
+        r#"{
+            "name": "$NAME_test_wrap_recursive",
+            "children": [{ "name": "${NAME_test_wrap_recursive}-child", "children": [] }],
+            "next": { "name": "${NAME_test_wrap_recursive}-next", "children": [] },
+            "deserialized": {
+                "name": "${NAME_test_wrap_recursive}-deserialized",
+                "children": []
+            }
+        }"#,
+        |de| {
+            let children: &Vec<Node> = &de.children;
+            let next: &Option<Box<Node>> = &de.next;
+
+            assert_eq!(&de.name, "node");
+            assert_eq!(&children[0].name, "node-child");
+            assert_eq!(&next.as_ref().unwrap().name, "node-next");
+            assert_eq!(&de.deserialized.as_ref().unwrap().name, "node-deserialized");
+        },
+        indoc! {r#"
+            {
+              "name": "node",
+              "children": [
+                {
+                  "name": "node-child",
+                  "children": [],
+                  "next": null,
+                  "deserialized": null
+                }
+              ],
+              "next": {
+                "name": "node-next",
+                "children": [],
+                "next": null,
+                "deserialized": null
+              },
+              "deserialized": {
+                "name": "node-deserialized",
+                "children": [],
+                "next": null,
+                "deserialized": null
+              }
+            }"#},
+    );
+
+    let expr: Expr =
+        serde_json::from_str(r#"{ "Add": [{ "Num": "$NUM_test_wrap_recursive" }, { "Num": 3 }] }"#)
+            .unwrap();
+    let Expr::Add(lhs, rhs) = expr else {
+        unreachable!();
+    };
+    assert!(matches!(*lhs, Expr::Num(num) if *num == 2));
+    assert!(matches!(*rhs, Expr::Num(num) if *num == 3));
+}
+
+#[test]
+fn test_wrap_default() {
+    fn default_url() -> &'static str {
+        "http://${HOST_test_wrap_default:-localhost}"
+    }
+
+    fn default_retries() -> EnvField<u8> {
+        3.into()
+    }
+
+    #[env_field_wrap(default)]
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        name: String,
+        port: u16,
+        tags: Vec<String>,
+        timeout: Option<u32>,
+
+        #[env_field_wrap(skip)]
+        pattern: String,
+
+        // The own defaults are kept
+        #[serde(default = "default_retries")]
+        retries: u8,
+
+        #[env_field_wrap(default_expand = "default_url")]
+        url: String,
+    }
+
+    #[env_field_wrap(default)]
+    #[derive(Serialize, Deserialize)]
+    struct Tuple(String, u16);
+
+    env::remove_var("HOST_test_wrap_default");
+    de_se_de_test::<Test>(
+        "",
+        |de| {
+            assert_eq!(&de.name, "");
+            assert_eq!(*de.port, 0);
+            assert!(de.tags.is_empty());
+            assert!(de.timeout.is_none());
+            assert_eq!(de.pattern, "");
+            assert_eq!(*de.retries, 3);
+            assert_eq!(&de.url, "http://localhost");
+        },
+        indoc! {r#"
+            name = ""
+            port = 0
+            tags = []
+            pattern = ""
+            retries = 3
+            url = "http://localhost"
+        "#},
+    );
+
+    env::set_var("NAME_test_wrap_default", "name");
+    env::set_var("PORT_test_wrap_default", "8080");
+    de_se_de_test::<Test>(
+        r#"
+            name = "$NAME_test_wrap_default"
+            port = "$PORT_test_wrap_default"
+        "#,
+        |de| {
+            assert_eq!(&de.name, "name");
+            assert_eq!(*de.port, 8080);
+            assert!(de.tags.is_empty());
+        },
+        indoc! {r#"
+            name = "name"
+            port = 8080
+            tags = []
+            pattern = ""
+            retries = 3
+            url = "http://localhost"
+        "#},
+    );
+
+    let de: Tuple = serde_json::from_str(r#"["$NAME_test_wrap_default"]"#).unwrap();
+    assert_eq!(&de.0, "name");
+    assert_eq!(*de.1, 0);
+}
+
+#[test]
+fn test_wrap_bound() {
+    use std::fmt::Display;
+
+    #[env_field_wrap(bound = "T: FromStr, <T as FromStr>::Err: Display")]
+    #[derive(Serialize, Deserialize)]
+    struct Test<T> {
+        value: T,
+        values: Vec<T>,
+
+        #[env_field_wrap(skip)]
+        pair: Pair<T>,
+    }
+
+    // The original where-clause is preserved
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    struct Pair<T>(T, Vec<T>)
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display;
+
+    env::set_var("NUM_test_bound", "21");
+    de_se_de_test::<Test<u64>>(
+        r#"
+            value = "$NUM_test_bound"
+            values = [1, "$NUM_test_bound"]
+            pair = ["$NUM_test_bound", ["$NUM_test_bound"]]
+        "#,
+        |de| {
+            assert_eq!(de.value, 21);
+            assert!(de.values.iter().eq([1, 21].iter()));
+            assert_eq!(de.pair.0, 21);
+            assert!(de.pair.1.iter().eq([21].iter()));
+        },
+        indoc! {r#"
+            value = 21
+            values = [
+                1,
+                21,
+            ]
+            pair = [
+                21,
+                [21],
+            ]
+        "#},
+    );
+}
+
+#[test]
+fn test_wrap_crate() {
+    mod reexport {
+        pub use serde_env_field as config;
+    }
+
+    fn default_port() -> &'static str {
+        "${PORT_test_wrap_crate:-8080}"
+    }
+
+    #[env_field_wrap(crate = "reexport::config")]
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        host: String,
+        ports: Vec<u16>,
+        wrapped: reexport::config::EnvField<i32>,
+
+        #[env_field_wrap(default_expand = "default_port")]
+        default_port: u16,
+
+        #[env_field_wrap(into = "u16")]
+        converted: u64,
+    }
+
+    env::set_var("HOST_test_wrap_crate", "localhost");
+    env::set_var("NUM_test_wrap_crate", "42");
+    de_se_de_test::<Test>(
+        r#"
+            host = "$HOST_test_wrap_crate"
+            ports = ["$NUM_test_wrap_crate", 443]
+            wrapped = "-$NUM_test_wrap_crate"
+            converted = "$NUM_test_wrap_crate"
+        "#,
+        |de| {
+            let wrapped: &reexport::config::EnvField<i32> = &de.wrapped;
+
+            assert_eq!(&de.host, "localhost");
+            assert!(de.ports.iter().eq([42, 443].iter()));
+            assert_eq!(*wrapped, -42);
+            assert_eq!(de.default_port, 8080);
+            assert_eq!(de.converted, 42);
+        },
+        indoc! {r#"
+            host = "localhost"
+            ports = [
+                42,
+                443,
+            ]
+            wrapped = -42
+            default_port = 8080
+            converted = 42
+        "#},
+    );
+}
+
+#[test]
+fn test_wrap_env_precedence() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        #[env_field_wrap(env = "ENV_test_precedence")]
+        env_wins: String,
+
+        #[env_field_wrap(env = "DOC_test_precedence", precedence = "document")]
+        document_wins: u32,
+    }
+
+    env::remove_var("ENV_test_precedence");
+    env::remove_var("DOC_test_precedence");
+    env::set_var("TEMPLATE_test_precedence", "template");
+
+    let source = r#"
+        env_wins = "from ${TEMPLATE_test_precedence}"
+        document_wins = "${NUM_test_precedence:-5}"
+    "#;
+
+    let de: Test = toml::from_str(source).unwrap();
+    assert_eq!(&de.env_wins, "from template");
+    assert_eq!(de.document_wins, 5);
+
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(&de.env_wins, "");
+    assert_eq!(de.document_wins, 0);
+
+    env::set_var("ENV_test_precedence", "from $ENV");
+    env::set_var("DOC_test_precedence", "10");
+
+    let de: Test = toml::from_str(source).unwrap();
+    assert_eq!(&de.env_wins, "from $ENV");
+    assert_eq!(de.document_wins, 5);
+
+    // The document template isn't expanded if the env wins
+    let de: Test = toml::from_str(
+        r#"
+            env_wins = "$MISSING_test_precedence"
+            document_wins = 1
+        "#,
+    )
+    .unwrap();
+    assert_eq!(&de.env_wins, "from $ENV");
+    assert_eq!(de.document_wins, 1);
+
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(&de.env_wins, "from $ENV");
+    assert_eq!(de.document_wins, 10);
+
+    env::set_var("ENV_test_precedence", "from env");
+    env::set_var("DOC_test_precedence", "not a number");
+    let de: Test = toml::from_str(
+        r#"
+            env_wins = "x"
+            document_wins = 1
+        "#,
+    )
+    .unwrap();
+    assert_eq!(&de.env_wins, "from env");
+
+    let err = toml::from_str::<Test>(r#"document_wins = "x""#).unwrap_err();
+    assert!(err.message().contains("invalid value 'x'"));
+
+    let (de, errors) = EnvField::collect_errors(|| toml::from_str::<Test>("").unwrap());
+    assert_eq!(&de.env_wins, "from env");
+    assert_eq!(de.document_wins, 0);
+    assert_eq!(
+        errors[0].to_string(),
+        "invalid value of the 'DOC_test_precedence' variable: \
+        invalid value 'not a number': invalid digit found in string"
+    );
+}
+
+#[test]
+fn test_wrap_env_invalid_value() {
+    #[env_field_wrap]
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Test {
+        #[env_field_wrap(env = "PORT_test_env_invalid")]
+        port: u16,
+    }
+
+    #[env_field_wrap]
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    enum Variants {
+        Server {
+            #[env_field_wrap(env = "PORT_test_env_invalid")]
+            port: u16,
+        },
+    }
+
+    env::set_var("PORT_test_env_invalid", "not a port");
+    let expected = "invalid value of the 'PORT_test_env_invalid' variable: \
+        invalid value 'not a port': invalid digit found in string";
+
+    // The absent field fails the deserialization instead of panicking
+    let err = toml::from_str::<Test>("").unwrap_err();
+    assert!(err.message().contains(expected));
+
+    let err = serde_json::from_str::<Variants>(r#"{ "Server": {} }"#).unwrap_err();
+    assert!(err.to_string().contains(expected));
+
+    env::set_var("PORT_test_env_invalid", "8080");
+    let de = toml::from_str::<Test>("").unwrap();
+    assert_eq!(de.port, 8080);
+}
+
+#[test]
+fn test_wrap_env_with_serde_rename() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        #[serde(rename = "url")]
+        #[env_field_wrap(env = "LEGACY_DB_URL_test_rename")]
+        database_url: String,
+    }
+
+    env::remove_var("LEGACY_DB_URL_test_rename");
+    let de: Test = toml::from_str(r#"url = "postgres://localhost""#).unwrap();
+    assert_eq!(&de.database_url, "postgres://localhost");
+
+    env::set_var("LEGACY_DB_URL_test_rename", "postgres://legacy");
+    let de: Test = toml::from_str(r#"url = "postgres://localhost""#).unwrap();
+    assert_eq!(&de.database_url, "postgres://legacy");
+
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(&de.database_url, "postgres://legacy");
+    assert_eq!(
+        toml::to_string(&de).unwrap(),
+        "url = \"postgres://legacy\"\n"
+    );
+}
+
+#[test]
+fn test_wrap_trim() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        #[env_field_wrap(trim)]
+        port: u16,
+
+        #[env_field_wrap(trim_matches = "\"'")]
+        quoted: String,
+
+        #[env_field_wrap(trim, trim_matches = "\"")]
+        both: Vec<String>,
+
+        #[env_field_wrap(trim, env = "ENV_test_trim")]
+        from_env: i32,
+
+        untrimmed: String,
+    }
+
+    env::set_var("PORT_test_trim", "  8080\n");
+    env::set_var("QUOTED_test_trim", "\"'value'\"");
+    env::set_var("BOTH_test_trim", " \"value\" ");
+    env::set_var("ENV_test_trim", " -3 ");
+
+    let de: Test = toml::from_str(
+        r#"
+            port = "$PORT_test_trim"
+            quoted = "$QUOTED_test_trim"
+            both = ["$BOTH_test_trim", "  literal  "]
+            untrimmed = "$BOTH_test_trim"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(de.port, 8080);
+    assert_eq!(&de.quoted, "value");
+    assert!(de.both.iter().map(|s| s.as_str()).eq(["value", "literal"]));
+    assert_eq!(de.from_env, -3);
+    assert_eq!(&de.untrimmed, " \"value\" ");
+
+    // Non-string values aren't affected
+    let de: Test = toml::from_str(
+        r#"
+            port = 80
+            quoted = " 'x' "
+            both = []
+            from_env = 1
+            untrimmed = ""
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(de.port, 80);
+    assert_eq!(&de.quoted, " 'x' ");
+    assert_eq!(de.from_env, -3);
+}
+
+#[test]
+fn test_wrap_default_expand() {
+    fn default_host() -> &'static str {
+        "${HOST_test_default_expand:-localhost}"
+    }
+
+    fn default_port() -> String {
+        format!("${{PORT_test_default_expand:-{}}}", 8000 + 80)
+    }
+
+    fn default_name() -> &'static str {
+        "  $NAME_test_default_expand  "
+    }
+
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        #[env_field_wrap(default_expand = "default_host")]
+        host: String,
+
+        #[env_field_wrap(default_expand = "default_port")]
+        port: u16,
+
+        #[env_field_wrap(default_expand = "default_host")]
+        fallback: Option<String>,
+
+        #[env_field_wrap(trim, default_expand = "default_name")]
+        name: String,
+    }
+
+    env::remove_var("HOST_test_default_expand");
+    env::remove_var("PORT_test_default_expand");
+    env::set_var("NAME_test_default_expand", "name");
+
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(&de.host, "localhost");
+    assert_eq!(de.port, 8080);
+    assert_eq!(
+        de.fallback.as_deref().map(|s| s.as_str()),
+        Some("localhost")
+    );
+    assert_eq!(&de.name, "name");
+
+    env::set_var("HOST_test_default_expand", "example.com");
+    env::set_var("PORT_test_default_expand", "443");
+    let de: Test = toml::from_str(r#"fallback = "document""#).unwrap();
+    assert_eq!(&de.host, "example.com");
+    assert_eq!(de.port, 443);
+    assert_eq!(de.fallback.as_deref().map(|s| s.as_str()), Some("document"));
+
+    env::set_var("PORT_test_default_expand", "not a port");
+    let err = toml::from_str::<Test>("").unwrap_err();
+    assert!(err.message().contains(
+        "invalid default template '${PORT_test_default_expand:-8080}': invalid value 'not a port'"
+    ));
+}
+
+#[test]
+fn test_wrap_default_expand_unset_var() {
+    fn default_url() -> &'static str {
+        "http://${HOST_test_default_expand_unset}:8080"
+    }
+
+    #[env_field_wrap]
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        #[env_field_wrap(default_expand = "default_url")]
+        url: String,
+    }
+
+    env::remove_var("HOST_test_default_expand_unset");
+    let err = toml::from_str::<Test>("").unwrap_err();
+    assert!(err
+        .message()
+        .contains("error looking key 'HOST_test_default_expand_unset' up"));
+
+    // The document value doesn't need the default
+    let de: Test = toml::from_str(r#"url = "http://localhost""#).unwrap();
+    assert_eq!(&de.url, "http://localhost");
+}
+
+#[test]
+fn test_wrap_combined_options() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Test {
+        #[env_field_wrap(generics_only, trim, env = "HOST_test_combined")]
+        host: Option<String>,
+
+        #[env_field_wrap(skip, env = "RAW_test_combined", precedence = "document")]
+        raw: String,
+    }
+
+    env::set_var("HOST_test_combined", " example.com ");
+    env::set_var("RAW_test_combined", "$raw");
+
+    let de: Test = toml::from_str(
+        r#"
+            raw = "$RAW_test_combined"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(de.host.as_deref().map(|s| s.as_str()), Some("example.com"));
+    assert_eq!(de.raw, "$RAW_test_combined");
+
+    env::remove_var("HOST_test_combined");
+    let de: Test = toml::from_str(
+        r#"
+            host = "  ${HOST_test_combined:-localhost}  "
+        "#,
+    )
+    .unwrap();
+    assert_eq!(de.host.as_deref().map(|s| s.as_str()), Some("localhost"));
+    assert_eq!(de.raw, "$raw");
+}
+
+#[test]
+fn test_wrap_enum() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    enum Test {
+        Wrapped(String),
+
+        #[env_field_wrap(skip)]
+        Skipped {
+            inner_str: String,
+        },
+
+        Inner(
+            #[env_field_wrap(skip)] String,
+            Option<i32>,
+            Vec<bool>,
+            #[env_field_wrap(generics_only)] TwoGenerics<i32, bool>,
+        ),
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct TwoGenerics<A, B> {
+        a: A,
+        b: B,
+    }
+
+    env::set_var("WRAPPED_enum", "Enum String From Env");
+    de_se_de_test::<Test>(
+        r#"
+            Wrapped = "$WRAPPED_enum"
+        "#,
+        |de| {
+            assert!(matches![de, Test::Wrapped(s) if s == "Enum String From Env"]);
+        },
+        indoc! {r#"
+            Wrapped = "Enum String From Env"
+        "#},
+    );
+
+    de_se_de_json_test::<Test>(
+        r#"
+            {
+                "Skipped": {
+                    "inner_str": "$WRAPPED_enum"
+                }
+            }
+        "#,
+        |de| {
+            assert!(matches![de, Test::Skipped { inner_str } if inner_str == "$WRAPPED_enum"]);
+        },
+        indoc! {
+            r#"
+            {
+              "Skipped": {
+                "inner_str": "$WRAPPED_enum"
+              }
+            }"#
+        },
+    );
+
+    env::set_var("NUM_enum", "117");
+    env::set_var("BOOL_enum", "false");
+    de_se_de_json_test::<Test>(
+        r#"
+            {
+                "Inner": [
+                    "$WRAPPED_enum",
+                    "$NUM_enum",
+                    [true, true, "$BOOL_enum"],
+                    {
+                        "a": "$NUM_enum",
+                        "b": "$BOOL_enum"
+                    }
+                ]
+            }
+        "#,
+        |de| {
+            assert!(matches![de, Test::Inner(_, _, _, _)]);
+            let Test::Inner(skipped, o, v, g) = de else {
+                unreachable!()
+            };
 
             assert_eq!(skipped, "$WRAPPED_enum");
             assert_eq!(o.unwrap(), 117);
             assert!(v.iter().eq([true, true, false].iter()));
             assert_eq!(g.a, 117);
-            assert_eq!(g.b, false);
+            assert!(!g.b);
         },
         indoc! {
             r#"
@@ -843,3 +2014,64 @@ fn test_wrap_enum() {
         },
     );
 }
+
+#[test]
+fn test_wrap_enum_struct_variant_use_deserialize() {
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize, Debug)]
+    enum Test {
+        Server {
+            host: String,
+
+            #[env_field_wrap(use_deserialize)]
+            mode: Mode,
+
+            #[env_field_wrap(use_deserialize)]
+            fallback: Option<Mode>,
+        },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Plain,
+        Tls { cert: String },
+    }
+
+    env::set_var("HOST_test_variant_use_deserialize", "localhost");
+    env::set_var("MODE_test_variant_use_deserialize", "plain");
+    env::set_var("CERT_test_variant_use_deserialize", "/etc/cert.pem");
+
+    de_se_de_test::<Test>(
+        r#"
+            [Server]
+            host = "$HOST_test_variant_use_deserialize"
+            mode = "$MODE_test_variant_use_deserialize"
+            fallback = { tls = { cert = "$CERT_test_variant_use_deserialize" } }
+        "#,
+        |de| {
+            let Test::Server {
+                host,
+                mode,
+                fallback,
+            } = de;
+
+            assert_eq!(host, "localhost");
+            assert_eq!(**mode, Mode::Plain);
+            assert_eq!(
+                **fallback.as_ref().unwrap(),
+                Mode::Tls {
+                    cert: "/etc/cert.pem".into()
+                }
+            );
+        },
+        indoc! {r#"
+            [Server]
+            host = "localhost"
+            mode = "plain"
+
+            [Server.fallback.tls]
+            cert = "/etc/cert.pem"
+        "#},
+    );
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "regex")]
+
+use std::env;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_env_field::{AlwaysString, EnvField, UseFromStr, UseRegex};
+
+// The `Regex` implements neither the `PartialEq` nor the `Deserialize`
+#[derive(Serialize, Deserialize, Debug)]
+struct Test {
+    filter: EnvField<Regex, UseRegex, AlwaysString>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct FromStrTest {
+    filter: EnvField<Regex, UseFromStr, AlwaysString>,
+}
+
+#[test]
+fn test_regex_pattern() {
+    env::remove_var("PATTERN_test_regex");
+    let de: Test = toml::from_str(r#"filter = "${PATTERN_test_regex:-.*}""#).unwrap();
+    assert_eq!(de.filter.as_str(), ".*");
+    assert!(de.filter.is_match("anything"));
+
+    env::set_var("PATTERN_test_regex", r"^\d+$");
+    let de: Test = toml::from_str(r#"filter = "$PATTERN_test_regex""#).unwrap();
+    assert!(de.filter.is_match("12345"));
+    assert!(!de.filter.is_match("12a45"));
+
+    assert_eq!(toml::to_string(&de).unwrap(), "filter = '^\\d+$'\n");
+
+    // Only the strings are accepted
+    assert!(toml::from_str::<Test>("filter = 42").is_err());
+}
+
+#[test]
+fn test_regex_invalid_pattern() {
+    env::set_var("PATTERN_test_regex_invalid", "(unclosed");
+    let err = toml::from_str::<Test>(r#"filter = "$PATTERN_test_regex_invalid""#)
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("invalid pattern '(unclosed'"));
+    assert!(err.contains("unclosed group"));
+}
+
+#[test]
+fn test_regex_transparent() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        filter: EnvField<Regex, UseRegex>,
+    }
+
+    env::set_var("PATTERN_test_regex_transparent", "^[a-z]+$");
+    let de: Test =
+        serde_json::from_str(r#"{"filter": "$PATTERN_test_regex_transparent"}"#).unwrap();
+    assert!(de.filter.is_match("abc"));
+}
+
+#[test]
+fn test_regex_from_str() {
+    env::set_var("PATTERN_test_regex_from_str", r"^\d+$");
+    let de: FromStrTest = toml::from_str(r#"filter = "$PATTERN_test_regex_from_str""#).unwrap();
+    assert!(de.filter.is_match("12345"));
+
+    env::set_var("PATTERN_test_regex_from_str", "(unclosed");
+    let err = toml::from_str::<FromStrTest>(r#"filter = "$PATTERN_test_regex_from_str""#)
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("invalid value '(unclosed'"));
+    assert!(err.contains("unclosed group"));
+}
@@ -0,0 +1,442 @@
+use std::{cell::RefCell, env, rc::Rc};
+
+use serde::Deserialize;
+use serde_env_field::{
+    env_field_wrap, EnvField, EnvFieldError, Resolution, UseDeserialize, UseSplit,
+};
+
+fn missing_vars(errors: &[EnvFieldError]) -> Vec<&str> {
+    errors
+        .iter()
+        .map(|err| match err {
+            EnvFieldError::Lookup { var_name, .. } => var_name.as_str(),
+            err => panic!("unexpected error: {err}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_collect_errors() {
+    #[derive(Deserialize)]
+    struct Test {
+        name: EnvField<String>,
+        path: EnvField<String>,
+        port: EnvField<u16>,
+    }
+
+    env::remove_var("MISSING_A_test_collect");
+    env::remove_var("MISSING_B_test_collect");
+    env::remove_var("MISSING_C_test_collect");
+    env::set_var("PORT_test_collect", "8080");
+
+    let (de, errors) = EnvField::collect_errors(|| {
+        toml::from_str::<Test>(
+            r#"
+                name = "$MISSING_A_test_collect"
+                path = "${MISSING_B_test_collect}/${MISSING_C_test_collect}"
+                port = "$PORT_test_collect"
+            "#,
+        )
+    });
+
+    let de = de.unwrap();
+    assert_eq!(*de.name, "");
+    assert_eq!(*de.path, "/");
+    assert_eq!(de.port, 8080);
+
+    assert_eq!(
+        missing_vars(&errors),
+        [
+            "MISSING_A_test_collect",
+            "MISSING_B_test_collect",
+            "MISSING_C_test_collect",
+        ]
+    );
+}
+
+#[test]
+fn test_collect_errors_scope() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        _value: EnvField<String>,
+    }
+
+    env::remove_var("MISSING_test_collect_scope");
+    let source = r#"_value = "$MISSING_test_collect_scope""#;
+
+    let (_, errors) = EnvField::collect_errors(|| {
+        let (de, inner_errors) = EnvField::collect_errors(|| toml::from_str::<Test>(source));
+        de.unwrap();
+        assert_eq!(missing_vars(&inner_errors), ["MISSING_test_collect_scope"]);
+
+        // The outer scope is restored after the inner one
+        toml::from_str::<Test>(source).unwrap();
+    });
+
+    assert_eq!(missing_vars(&errors), ["MISSING_test_collect_scope"]);
+
+    // The errors aren't collected outside the scope
+    toml::from_str::<Test>(source).unwrap_err();
+}
+
+#[test]
+fn test_observe_resolutions() {
+    #[derive(Deserialize)]
+    struct Test {
+        set: EnvField<String>,
+        empty: EnvField<String>,
+        unset: EnvField<String>,
+        missing: Option<EnvField<String>>,
+    }
+
+    env::set_var("SET_test_observe", "value");
+    env::set_var("EMPTY_test_observe", "");
+    env::remove_var("UNSET_test_observe");
+    env::remove_var("MISSING_test_observe");
+
+    let resolutions = Rc::new(RefCell::new(vec![]));
+    let observed = resolutions.clone();
+    let (de, errors) = EnvField::collect_errors(|| {
+        EnvField::observe_resolutions(
+            move |var_name, resolution| {
+                observed
+                    .borrow_mut()
+                    .push((var_name.to_owned(), resolution));
+            },
+            || {
+                toml::from_str::<Test>(
+                    r#"
+                        set = "${SET_test_observe:-default}"
+                        empty = "${EMPTY_test_observe:-default}/${EMPTY_test_observe-default}"
+                        unset = "${UNSET_test_observe-default}"
+                        missing = "$MISSING_test_observe"
+                    "#,
+                )
+            },
+        )
+    });
+
+    let de = de.unwrap();
+    assert_eq!(&de.set, "value");
+    assert_eq!(&de.empty, "default/");
+    assert_eq!(&de.unset, "default");
+    assert_eq!(de.missing.as_deref().map(String::as_str), Some(""));
+    assert_eq!(missing_vars(&errors), ["MISSING_test_observe"]);
+
+    let var = |name: &str| format!("{name}_test_observe");
+    assert_eq!(
+        *resolutions.borrow(),
+        [
+            (var("SET"), Resolution::VarSet),
+            (var("EMPTY"), Resolution::DefaultUsed),
+            (var("EMPTY"), Resolution::VarSet),
+            (var("UNSET"), Resolution::DefaultUsed),
+            (var("MISSING"), Resolution::Missing),
+        ]
+    );
+
+    // The observer is no longer notified outside of the closure
+    let _: Test = toml::from_str(
+        r#"
+            set = "$SET_test_observe"
+            empty = ""
+            unset = ""
+        "#,
+    )
+    .unwrap();
+    assert_eq!(resolutions.borrow().len(), 5);
+}
+
+#[test]
+fn test_with_metrics() {
+    #[derive(Deserialize)]
+    struct Test {
+        host: EnvField<String>,
+        port: EnvField<u16>,
+        number: EnvField<u32>,
+        tags: EnvField<Vec<String>, UseSplit>,
+    }
+
+    env::set_var("HOST_test_with_metrics", "example.com");
+    env::set_var("PORT_test_with_metrics", "8080");
+
+    let lengths = Rc::new(RefCell::new(vec![]));
+    let recorded = lengths.clone();
+    let de = EnvField::with_metrics(
+        move |len| recorded.borrow_mut().push(len),
+        || {
+            toml::from_str::<Test>(
+                r#"
+                    host = "https://$HOST_test_with_metrics"
+                    port = "$PORT_test_with_metrics"
+                    number = 42
+                    tags = "a,bc"
+                "#,
+            )
+        },
+    )
+    .unwrap();
+
+    assert_eq!(&de.host, "https://example.com");
+    assert_eq!(de.port, 8080);
+    assert_eq!(de.number, 42);
+    assert_eq!(*de.tags, ["a", "bc"]);
+
+    // The non-string values aren't expanded, so they aren't reported
+    assert_eq!(*lengths.borrow(), ["https://example.com".len(), 4, 4]);
+
+    // The hook is no longer notified outside of the closure
+    let _: Test = toml::from_str(
+        r#"
+            host = "$HOST_test_with_metrics"
+            port = 1
+            number = 2
+            tags = ""
+        "#,
+    )
+    .unwrap();
+    assert_eq!(lengths.borrow().len(), 3);
+}
+
+#[test]
+fn test_redact_values() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Test {
+        port: EnvField<u16>,
+        mode: Option<EnvField<Mode, UseDeserialize>>,
+        hosts: Option<EnvField<Vec<u8>, UseSplit>>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    enum Mode {
+        Fast,
+    }
+
+    env::set_var("SECRET_test_redact", "s3cr3t");
+
+    let errors = [
+        r#"port = "$SECRET_test_redact""#,
+        r#"
+            port = 1
+            mode = "$SECRET_test_redact"
+        "#,
+        r#"
+            port = 1
+            hosts = "1,$SECRET_test_redact"
+        "#,
+    ]
+    .map(|source| {
+        let err = toml::from_str::<Test>(source).unwrap_err();
+        assert!(err.message().contains("s3cr3t"));
+
+        EnvField::redact_values(|| toml::from_str::<Test>(source))
+            .unwrap_err()
+            .message()
+            .to_owned()
+    });
+
+    assert!(errors[0].contains("invalid value '***'"));
+    assert!(errors[1].contains("unknown variant `***`"));
+    assert!(errors[2].contains("invalid value '***'"));
+    assert!(errors.iter().all(|err| !err.contains("s3cr3t")));
+}
+
+#[test]
+fn test_redact_env_attr_values() {
+    #[env_field_wrap]
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Test {
+        #[env_field_wrap(env = "PORT_test_redact_attr")]
+        port: u16,
+    }
+
+    env::set_var("PORT_test_redact_attr", "s3cr3t");
+
+    let err = EnvField::redact_values(|| toml::from_str::<Test>("port = 1"))
+        .unwrap_err()
+        .message()
+        .to_owned();
+
+    assert!(err.contains("invalid value of the 'PORT_test_redact_attr' variable"));
+    assert!(!err.contains("s3cr3t"));
+}
+
+#[test]
+fn test_report_all_missing() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Test {
+        path: EnvField<String>,
+    }
+
+    env::remove_var("A_test_report_all");
+    env::set_var("B_test_report_all", "b");
+    env::remove_var("C_test_report_all");
+    let source = r#"path = "$A_test_report_all/$B_test_report_all/${C_test_report_all}""#;
+
+    let err = EnvField::report_all_missing(|| toml::from_str::<Test>(source))
+        .unwrap_err()
+        .message()
+        .to_owned();
+
+    assert!(err.contains("'A_test_report_all'"));
+    assert!(err.contains("'C_test_report_all'"));
+    assert!(!err.contains("B_test_report_all"));
+
+    // Only the first missing variable is reported by default
+    let err = toml::from_str::<Test>(source)
+        .unwrap_err()
+        .message()
+        .to_owned();
+    assert!(err.contains("'A_test_report_all'"));
+    assert!(!err.contains("C_test_report_all"));
+
+    // A single missing variable is reported as usual
+    env::set_var("A_test_report_all", "a");
+    let err = EnvField::report_all_missing(|| {
+        serde_json::from_str::<EnvField<String>>(r#""$A_test_report_all/$C_test_report_all""#)
+    })
+    .unwrap_err()
+    .to_string();
+
+    assert!(err.starts_with("error looking key 'C_test_report_all' up"));
+}
+
+#[test]
+fn test_env_snapshot() {
+    #[env_field_wrap]
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        mode: String,
+        #[env_field_wrap(env = "PORT_test_env_snapshot")]
+        port: u16,
+    }
+
+    env::set_var("MODE_test_env_snapshot", "fast");
+    env::set_var("PORT_test_env_snapshot", "8080");
+
+    let de = EnvField::with_env_snapshot(|| {
+        env::set_var("MODE_test_env_snapshot", "slow");
+        env::remove_var("PORT_test_env_snapshot");
+
+        toml::from_str::<Test>(
+            r#"
+                mode = "$MODE_test_env_snapshot"
+                port = 1
+            "#,
+        )
+        .unwrap()
+    });
+
+    assert_eq!(*de.mode, "fast");
+    assert_eq!(de.port, 8080);
+
+    // The snapshot is dropped outside of the scope
+    let mode: EnvField<String> = toml::from_str::<Test>(
+        r#"
+            mode = "$MODE_test_env_snapshot"
+            port = 1
+        "#,
+    )
+    .map(|de| de.mode)
+    .unwrap();
+
+    assert_eq!(*mode, "slow");
+}
+
+#[test]
+fn test_with_positional() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        command: EnvField<String>,
+    }
+
+    let args = ["echo".to_string(), "hello".to_string()];
+    env::set_var("SHELL_test_with_positional", "sh");
+
+    let de = EnvField::with_positional(&args, || {
+        toml::from_str::<Test>(
+            r#"command = "$SHELL_test_with_positional -c '$0 $1' $1st ${2:-world}""#,
+        )
+        .unwrap()
+    });
+    assert_eq!(*de.command, "sh -c 'echo hello' hellost world");
+
+    let err = EnvField::with_positional(&args, || {
+        toml::from_str::<Test>(r#"command = "$0 $2""#).unwrap_err()
+    });
+    assert!(err
+        .message()
+        .contains("positional argument '$2' is out of range: 2 arguments are provided"));
+
+    // The digits are variable names outside of the scope
+    let err = toml::from_str::<Test>(r#"command = "$0 $1""#).unwrap_err();
+    assert!(err.message().contains("error looking key '0' up"));
+}
+
+#[test]
+fn test_scopes_are_per_thread() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        _value: EnvField<String>,
+    }
+
+    env::remove_var("MISSING_test_per_thread");
+    let source = r#"_value = "$MISSING_test_per_thread""#;
+
+    let (_, errors) = EnvField::collect_errors(|| {
+        // The spawned thread doesn't inherit the scope
+        std::thread::spawn(move || toml::from_str::<Test>(source).unwrap_err())
+            .join()
+            .unwrap();
+
+        toml::from_str::<Test>(source).unwrap();
+    });
+
+    assert_eq!(missing_vars(&errors), ["MISSING_test_per_thread"]);
+
+    // The scope of another thread doesn't leak into the current one
+    let (tx, rx) = std::sync::mpsc::channel();
+    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+    let handle = std::thread::spawn(move || {
+        EnvField::collect_errors(|| {
+            tx.send(()).unwrap();
+            done_rx.recv().unwrap();
+        })
+    });
+
+    rx.recv().unwrap();
+    toml::from_str::<Test>(source).unwrap_err();
+    done_tx.send(()).unwrap();
+
+    let (_, errors) = handle.join().unwrap();
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_scopes_restored_on_panic() {
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        _value: EnvField<String>,
+    }
+
+    env::remove_var("MISSING_test_restored_on_panic");
+    let source = r#"_value = "$MISSING_test_restored_on_panic""#;
+
+    let (_, errors) = EnvField::collect_errors(|| {
+        let panicked = std::panic::catch_unwind(|| {
+            EnvField::with_env_snapshot(|| {
+                EnvField::collect_errors(|| panic!("inner scope"));
+            })
+        });
+        assert!(panicked.is_err());
+
+        // The outer collection is restored after the inner one panicked
+        toml::from_str::<Test>(source).unwrap();
+    });
+
+    assert_eq!(missing_vars(&errors), ["MISSING_test_restored_on_panic"]);
+    toml::from_str::<Test>(source).unwrap_err();
+}
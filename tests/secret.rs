@@ -0,0 +1,75 @@
+#![cfg(feature = "secrecy")]
+
+use std::env;
+
+use secrecy::{ExposeSecret, SecretBox, SecretString};
+use serde::Deserialize;
+use serde_env_field::{EnvField, UseSecret};
+
+#[derive(Deserialize, Debug)]
+struct Test {
+    password: EnvField<SecretString, UseSecret>,
+    pin: EnvField<SecretBox<u32>, UseSecret>,
+}
+
+#[test]
+fn test_secret() {
+    env::set_var("PASSWORD_test_secret", "pa$$word");
+    env::set_var("PIN_test_secret", "0042");
+
+    let de: Test = toml::from_str(
+        r#"
+            password = "${PASSWORD_test_secret}!"
+            pin = "$PIN_test_secret"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(de.password.expose_secret(), "pa$$word!");
+    assert_eq!(*de.pin.expose_secret(), 42);
+
+    let debug = format!("{de:?}");
+    assert!(!debug.contains("pa$$word"));
+    assert!(!debug.contains("42"));
+}
+
+#[test]
+fn test_secret_not_in_errors() {
+    env::set_var("PIN_test_secret_err", "s3cr3t-pin");
+
+    let err = toml::from_str::<Test>(
+        r#"
+            password = ""
+            pin = "$PIN_test_secret_err"
+        "#,
+    )
+    .unwrap_err()
+    .to_string();
+
+    assert!(err.contains("invalid secret value: the string can't be parsed as `u32`"));
+    assert!(!err.contains("s3cr3t"));
+}
+
+#[test]
+fn test_secret_only_strings() {
+    toml::from_str::<Test>(
+        r#"
+            password = ""
+            pin = 1234
+        "#,
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_secret_not_human_readable() {
+    #[derive(Deserialize)]
+    struct Test {
+        password: EnvField<SecretString, UseSecret>,
+    }
+
+    let serialized = bincode::serialize("$NOT_EXPANDED").unwrap();
+    let de: Test = bincode::deserialize(&serialized).unwrap();
+
+    assert_eq!(de.password.expose_secret(), "$NOT_EXPANDED");
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "semver")]
+
+use std::env;
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use serde_env_field::EnvField;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Test {
+    min_version: EnvField<Version>,
+    requirement: EnvField<VersionReq>,
+}
+
+#[test]
+fn test_semver() {
+    env::remove_var("MIN_VER_test_semver");
+    env::set_var("REQ_test_semver", "^1.2");
+    let de: Test = toml::from_str(
+        r#"
+            min_version = "${MIN_VER_test_semver:-1.2.3}"
+            requirement = "$REQ_test_semver, <1.5"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(*de.min_version, Version::new(1, 2, 3));
+    assert!(de.requirement.matches(&de.min_version));
+    assert!(!de.requirement.matches(&Version::new(1, 5, 0)));
+
+    assert_eq!(
+        toml::to_string(&de).unwrap(),
+        "min_version = \"1.2.3\"\nrequirement = \"^1.2, <1.5\"\n"
+    );
+}
+
+#[test]
+fn test_semver_invalid() {
+    env::set_var("MIN_VER_test_semver_invalid", "1.2");
+    let err = toml::from_str::<Test>(
+        r#"
+            min_version = "${MIN_VER_test_semver_invalid:-1.2.3}"
+            requirement = "*"
+        "#,
+    )
+    .unwrap_err();
+    assert!(err.message().contains(
+        "invalid value '1.2': unexpected end of input while parsing minor version number"
+    ));
+
+    let err = toml::from_str::<Test>(
+        r#"
+            min_version = "1.2.3"
+            requirement = ">= $MIN_VER_test_semver_invalid !"
+        "#,
+    )
+    .unwrap_err();
+    assert!(err.message().contains("invalid value '>= 1.2 !'"));
+}
@@ -0,0 +1,341 @@
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use serde_env_field::{env_field_wrap, serde_env_field, EnvField, StaticTemplate, UseByteSize};
+
+const SOURCE: &str = r#"
+    name = "${NAME_test_serde_env_field:-Default Name}"
+    port = "$PORT_test_serde_env_field"
+    numbers = [1, "$NUMBER_test_serde_env_field"]
+    pair = ["${NAME_test_serde_env_field:-Default Name}", "${NUMBER_test_serde_env_field}0"]
+    pattern = "$HOME/*.log"
+"#;
+
+#[test]
+fn test_serde_env_field_as_env_field_wrap() {
+    #[serde_env_field]
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Plain {
+        name: String,
+        port: u16,
+        timeout: Option<u32>,
+        numbers: Vec<i32>,
+        pair: (String, u8),
+
+        #[serde_env_field(skip)]
+        pattern: String,
+    }
+
+    #[env_field_wrap]
+    #[derive(Serialize, Deserialize)]
+    struct Wrapped {
+        name: String,
+        port: u16,
+        timeout: Option<u32>,
+        numbers: Vec<i32>,
+        pair: (String, u8),
+
+        #[env_field_wrap(skip)]
+        pattern: String,
+    }
+
+    env::remove_var("NAME_test_serde_env_field");
+    env::set_var("PORT_test_serde_env_field", "8080");
+    env::set_var("NUMBER_test_serde_env_field", "2");
+
+    let plain: Plain = toml::from_str(SOURCE).unwrap();
+    let wrapped: Wrapped = toml::from_str(SOURCE).unwrap();
+
+    // The field types are kept intact
+    let port: u16 = plain.port;
+    let timeout: Option<u32> = plain.timeout;
+
+    assert_eq!(port, 8080);
+    assert_eq!(timeout, None);
+    assert_eq!(
+        plain,
+        Plain {
+            name: wrapped.name.into_inner(),
+            port: wrapped.port.into_inner(),
+            timeout: wrapped.timeout.map(EnvField::into_inner),
+            numbers: wrapped
+                .numbers
+                .into_iter()
+                .map(EnvField::into_inner)
+                .collect(),
+            pair: (wrapped.pair.0.into_inner(), wrapped.pair.1.into_inner()),
+            pattern: wrapped.pattern,
+        }
+    );
+
+    assert_eq!(
+        plain,
+        Plain {
+            name: "Default Name".into(),
+            port: 8080,
+            timeout: None,
+            numbers: vec![1, 2],
+            pair: ("Default Name".into(), 20),
+            pattern: "$HOME/*.log".into(),
+        }
+    );
+
+    // The serialization isn't affected
+    assert_eq!(
+        toml::to_string(&plain).unwrap(),
+        toml::to_string(&Plain {
+            name: "Default Name".into(),
+            port: 8080,
+            timeout: None,
+            numbers: vec![1, 2],
+            pair: ("Default Name".into(), 20),
+            pattern: "$HOME/*.log".into(),
+        })
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_serde_env_field_enum() {
+    #[serde_env_field]
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(tag = "type", rename_all = "lowercase")]
+    enum Plain {
+        Tcp { host: String, port: u16 },
+        Unix { path: String },
+    }
+
+    #[env_field_wrap]
+    #[derive(Deserialize)]
+    #[serde(tag = "type", rename_all = "lowercase")]
+    #[allow(dead_code)]
+    enum Wrapped {
+        Tcp { host: String, port: u16 },
+        Unix { path: String },
+    }
+
+    env::set_var("HOST_test_serde_env_field_enum", "localhost");
+    env::set_var("PORT_test_serde_env_field_enum", "443");
+    let source = r#"
+        type = "tcp"
+        host = "$HOST_test_serde_env_field_enum"
+        port = "$PORT_test_serde_env_field_enum"
+    "#;
+
+    let plain: Plain = toml::from_str(source).unwrap();
+    let Wrapped::Tcp { host, port } = toml::from_str(source).unwrap() else {
+        panic!("expected the tcp variant");
+    };
+
+    assert_eq!(
+        plain,
+        Plain::Tcp {
+            host: host.into_inner(),
+            port: port.into_inner(),
+        }
+    );
+    assert_eq!(
+        plain,
+        Plain::Tcp {
+            host: "localhost".into(),
+            port: 443,
+        }
+    );
+}
+
+#[test]
+fn test_serde_env_field_custom_deserialize_with() {
+    fn constant<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u16, D::Error> {
+        serde::de::IgnoredAny::deserialize(deserializer)?;
+        Ok(42)
+    }
+
+    #[serde_env_field]
+    #[derive(Deserialize)]
+    struct Test {
+        #[serde(deserialize_with = "constant")]
+        value: u16,
+
+        #[serde(default)]
+        missing: Option<String>,
+
+        present: Option<String>,
+    }
+
+    env::set_var("PRESENT_test_serde_env_field_custom", "present");
+    let de: Test = toml::from_str(
+        r#"
+            value = "$UNSET_test_serde_env_field_custom"
+            present = "$PRESENT_test_serde_env_field_custom"
+        "#,
+    )
+    .unwrap();
+
+    // The custom `deserialize_with` is kept
+    assert_eq!(de.value, 42);
+    assert!(de.missing.is_none());
+    assert_eq!(de.present.as_deref(), Some("present"));
+}
+
+#[test]
+fn test_serde_env_field_default_expand() {
+    fn default_url() -> &'static str {
+        "${URL_test_serde_env_field_default:-http://localhost}"
+    }
+
+    fn default_ports() -> &'static str {
+        "$PORT_test_serde_env_field_default"
+    }
+
+    #[serde_env_field]
+    #[derive(Deserialize)]
+    struct Test {
+        #[serde_env_field(default_expand = "default_url")]
+        url: String,
+
+        #[serde_env_field(default_expand = "default_ports")]
+        port: Option<u16>,
+
+        #[serde_env_field(default_expand = "default_url")]
+        wrapped: EnvField<String>,
+    }
+
+    env::remove_var("URL_test_serde_env_field_default");
+    env::set_var("PORT_test_serde_env_field_default", "8080");
+
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(de.url, "http://localhost");
+    assert_eq!(de.port, Some(8080));
+    assert_eq!(&de.wrapped, "http://localhost");
+
+    env::set_var("URL_test_serde_env_field_default", "https://example.com");
+    let de: Test = toml::from_str(r#"port = 443"#).unwrap();
+    assert_eq!(de.url, "https://example.com");
+    assert_eq!(de.port, Some(443));
+    assert_eq!(&de.wrapped, "https://example.com");
+}
+
+#[test]
+fn test_serde_env_field_default_template() {
+    const DEFAULT_URL: StaticTemplate<String> =
+        StaticTemplate::new("${URL_test_serde_env_field_template:-http://localhost}");
+    const DEFAULT_SIZE: StaticTemplate<u64, UseByteSize> =
+        StaticTemplate::new("${SIZE_test_serde_env_field_template:-1KiB}");
+
+    #[serde_env_field]
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        #[serde_env_field(default = DEFAULT_URL)]
+        url: String,
+
+        #[serde_env_field(default = DEFAULT_URL)]
+        mirror: Option<String>,
+
+        #[serde_env_field(default = DEFAULT_SIZE)]
+        size: u64,
+    }
+
+    env::remove_var("URL_test_serde_env_field_template");
+    env::remove_var("SIZE_test_serde_env_field_template");
+
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(de.url, "http://localhost");
+    assert_eq!(de.mirror.as_deref(), Some("http://localhost"));
+    assert_eq!(de.size, 1024);
+
+    env::set_var("URL_test_serde_env_field_template", "https://example.com");
+    env::set_var("SIZE_test_serde_env_field_template", "2MB");
+    let de: Test = toml::from_str(r#"mirror = "https://mirror.com""#).unwrap();
+    assert_eq!(de.url, "https://example.com");
+    assert_eq!(de.mirror.as_deref(), Some("https://mirror.com"));
+    assert_eq!(de.size, 2_000_000);
+
+    env::set_var("SIZE_test_serde_env_field_template", "2XB");
+    let err = toml::from_str::<Test>("").unwrap_err();
+    assert!(err
+        .message()
+        .contains("invalid default template '${SIZE_test_serde_env_field_template:-1KiB}'"));
+}
+
+#[test]
+fn test_serde_env_field_skip_if_empty() {
+    #[serde_env_field]
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Test {
+        #[serde_env_field(skip_if_empty)]
+        name: Option<String>,
+
+        #[serde_env_field(skip_if_empty)]
+        port: Option<u16>,
+
+        #[serde_env_field(skip_if_empty)]
+        wrapped: Option<EnvField<u16>>,
+
+        #[serde_env_field(skip_if_empty)]
+        numbers: Option<Vec<u16>>,
+    }
+
+    env::set_var("EMPTY_test_serde_env_field_skip_if_empty", "");
+    env::set_var("NAME_test_serde_env_field_skip_if_empty", "name");
+    env::set_var("PORT_test_serde_env_field_skip_if_empty", "8080");
+
+    // The empty expansions are absent values
+    let de: Test = toml::from_str(
+        r#"
+            name = "$EMPTY_test_serde_env_field_skip_if_empty"
+            port = "${EMPTY_test_serde_env_field_skip_if_empty}"
+            wrapped = "${UNSET_test_serde_env_field_skip_if_empty:-}"
+            numbers = "$EMPTY_test_serde_env_field_skip_if_empty"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        de,
+        Test {
+            name: None,
+            port: None,
+            wrapped: None,
+            numbers: None,
+        }
+    );
+
+    // The set variables are present values
+    let de: Test = toml::from_str(
+        r#"
+            name = "$NAME_test_serde_env_field_skip_if_empty"
+            port = "$PORT_test_serde_env_field_skip_if_empty"
+            wrapped = 443
+            numbers = [1, "$PORT_test_serde_env_field_skip_if_empty"]
+        "#,
+    )
+    .unwrap();
+    assert_eq!(de.name.as_deref(), Some("name"));
+    assert_eq!(de.port, Some(8080));
+    assert_eq!(de.wrapped.as_deref(), Some(&443));
+    assert_eq!(de.numbers, Some(vec![1, 8080]));
+
+    // The absent keys are absent values
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(
+        de,
+        Test {
+            name: None,
+            port: None,
+            wrapped: None,
+            numbers: None,
+        }
+    );
+
+    // The JSON nulls too
+    let de: Test = serde_json::from_str(
+        r#"{ "name": null, "port": "$EMPTY_test_serde_env_field_skip_if_empty", "numbers": [2] }"#,
+    )
+    .unwrap();
+    assert_eq!(de.name, None);
+    assert_eq!(de.port, None);
+    assert_eq!(de.numbers, Some(vec![2]));
+
+    // The non-empty values are still validated
+    let err = toml::from_str::<Test>(r#"port = " ""#).unwrap_err();
+    assert!(err.message().contains("invalid value ' '"), "{err}");
+}
@@ -0,0 +1,103 @@
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use serde_env_field::{EnvField, UseSplit};
+
+#[derive(Serialize, Deserialize)]
+struct Test {
+    hosts: EnvField<Vec<String>, UseSplit>,
+    ports: EnvField<Vec<u16>, UseSplit<':'>>,
+}
+
+fn split(source: &str) -> Result<(Vec<String>, Vec<u16>), toml::de::Error> {
+    toml::from_str::<Test>(source).map(|de| (de.hosts.into_inner(), de.ports.into_inner()))
+}
+
+#[test]
+fn test_split() {
+    env::set_var("HOSTS_test_split", "a.com,b.com");
+    env::set_var("PORTS_test_split", "80:443");
+
+    let (hosts, ports) = split(
+        r#"
+            hosts = "$HOSTS_test_split,c.com"
+            ports = "$PORTS_test_split"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(hosts, ["a.com", "b.com", "c.com"]);
+    assert_eq!(ports, [80, 443]);
+}
+
+#[test]
+fn test_split_empty_and_trailing() {
+    env::set_var("EMPTY_test_split", "");
+
+    let (hosts, ports) = split(
+        r#"
+            hosts = "$EMPTY_test_split"
+            ports = "8080:"
+        "#,
+    )
+    .unwrap();
+
+    assert!(hosts.is_empty());
+    assert_eq!(ports, [8080]);
+
+    let (hosts, ports) = split(
+        r#"
+            hosts = ","
+            ports = ""
+        "#,
+    )
+    .unwrap();
+
+    assert!(hosts.is_empty());
+    assert!(ports.is_empty());
+
+    let (hosts, _) = split(
+        r#"
+            hosts = "a,,b"
+            ports = ""
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(hosts, ["a", "", "b"]);
+}
+
+#[test]
+fn test_split_not_string() {
+    let de: Test = toml::from_str(
+        r#"
+            hosts = ["a.com", "b.com"]
+            ports = [80, 443]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(*de.hosts, ["a.com", "b.com"]);
+    assert_eq!(*de.ports, [80, 443]);
+
+    // Serialized as a sequence
+    assert_eq!(
+        toml::to_string(&de).unwrap(),
+        "hosts = [\"a.com\", \"b.com\"]\nports = [80, 443]\n",
+    );
+}
+
+#[test]
+fn test_split_invalid_item() {
+    let err = split(
+        r#"
+            hosts = ""
+            ports = "80:http"
+        "#,
+    )
+    .unwrap_err();
+
+    assert!(err
+        .message()
+        .contains("invalid value 'http': invalid digit found in string"));
+}
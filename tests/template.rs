@@ -0,0 +1,254 @@
+use std::env;
+
+use indoc::indoc;
+use serde::{Deserialize, Serialize};
+use serde_env_field::{
+    EnvField, EnvFieldError, EnvTemplate, NoExpand, StaticTemplate, UseBoolish, UseByteSize,
+    UseCaseTransforms, UseDeserialize, UseGrouped,
+};
+
+#[test]
+fn test_const_field() {
+    const ANSWER: EnvField<i32> = EnvField::new(42);
+
+    assert_eq!(ANSWER, 42);
+}
+
+#[test]
+fn test_const_template() {
+    const DEFAULT_NAME: StaticTemplate<String> = StaticTemplate::new("${NAME_test_const:-Default}");
+
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        #[serde(default = "default_name")]
+        name: EnvTemplate<String>,
+    }
+
+    fn default_name() -> EnvTemplate<String> {
+        DEFAULT_NAME.into()
+    }
+
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(de.name.try_get().unwrap(), "Default");
+
+    env::set_var("NAME_test_const", "From Env");
+    let de: Test = toml::from_str("").unwrap();
+    assert_eq!(de.name.try_get().unwrap(), "From Env");
+}
+
+#[test]
+fn test_template_round_trip() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        size: EnvTemplate<usize>,
+        num: EnvTemplate<i32>,
+    }
+
+    env::set_var("SIZE_test_template", "64");
+    let de: Test = toml::from_str(
+        r#"
+            size = "$SIZE_test_template"
+            num = -12
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(*de.size.try_get().unwrap(), 64);
+    assert_eq!(*de.num.try_get().unwrap(), -12);
+
+    assert_eq!(
+        toml::to_string_pretty(&de).unwrap(),
+        indoc! {r#"
+            size = "$SIZE_test_template"
+            num = -12
+        "#}
+    );
+}
+
+#[test]
+fn test_template_is_cached() {
+    let template: EnvTemplate<String> = EnvTemplate::new("$VALUE_test_cached");
+
+    env::set_var("VALUE_test_cached", "first");
+    assert_eq!(template.try_get().unwrap(), "first");
+
+    env::set_var("VALUE_test_cached", "second");
+    assert_eq!(template.try_get().unwrap(), "first");
+}
+
+#[test]
+fn test_template_use_deserialize() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "kebab-case")]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    let template: EnvTemplate<Mode, UseDeserialize> =
+        EnvTemplate::new("${MODE_test_template:-slow}");
+    assert_eq!(template.try_get().unwrap(), &Mode::Slow);
+
+    env::set_var("MODE_test_template_de", "fast");
+    let template: EnvTemplate<Mode, UseDeserialize> = EnvTemplate::new("$MODE_test_template_de");
+    assert_eq!(template.try_get().unwrap(), &Mode::Fast);
+}
+
+#[test]
+fn test_template_missing_var() {
+    env::remove_var("MISSING_test_template");
+    let template: EnvTemplate<i32> = EnvTemplate::new("$MISSING_test_template");
+
+    assert!(matches!(
+        template.try_get(),
+        Err(EnvFieldError::Lookup { var_name, .. }) if var_name == "MISSING_test_template"
+    ));
+}
+
+#[test]
+fn test_template_reexpand() {
+    let mut template: EnvTemplate<u32> = EnvTemplate::new("$VALUE_test_reexpand");
+
+    env::set_var("VALUE_test_reexpand", "1");
+    assert_eq!(*template.try_get().unwrap(), 1);
+
+    env::set_var("VALUE_test_reexpand", "2");
+    template.reexpand().unwrap();
+    assert_eq!(*template.try_get().unwrap(), 2);
+
+    env::set_var("VALUE_test_reexpand", "not a number");
+    template.reexpand().unwrap_err();
+    assert_eq!(*template.try_get().unwrap(), 2);
+
+    env::remove_var("VALUE_test_reexpand");
+    assert!(matches!(
+        template.reexpand(),
+        Err(EnvFieldError::Lookup { var_name, .. }) if var_name == "VALUE_test_reexpand"
+    ));
+    assert_eq!(*template.try_get().unwrap(), 2);
+
+    let mut value: EnvTemplate<u32> = 5.into();
+    value.reexpand().unwrap();
+    assert_eq!(*value.try_get().unwrap(), 5);
+}
+
+#[test]
+fn test_template_resolve_all() {
+    let mut templates: Vec<EnvTemplate<u32>> = vec![
+        EnvTemplate::new("$FIRST_test_resolve_all"),
+        EnvTemplate::new("$SECOND_test_resolve_all"),
+        EnvTemplate::new("${THIRD_test_resolve_all:-3}"),
+        7.into(),
+    ];
+
+    env::set_var("FIRST_test_resolve_all", "1");
+    env::set_var("SECOND_test_resolve_all", "2");
+    EnvTemplate::resolve_all(&mut templates).unwrap();
+    assert!(templates
+        .iter()
+        .map(|t| *t.try_get().unwrap())
+        .eq([1, 2, 3, 7]));
+
+    env::set_var("FIRST_test_resolve_all", "10");
+    env::remove_var("SECOND_test_resolve_all");
+    env::set_var("THIRD_test_resolve_all", "not a number");
+    let errors = EnvTemplate::resolve_all(&mut templates).unwrap_err();
+
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        &errors[0],
+        EnvFieldError::Lookup { var_name, .. } if var_name == "SECOND_test_resolve_all"
+    ));
+    assert!(matches!(&errors[1], EnvFieldError::Parse(_)));
+
+    // The failed templates keep their previous values
+    assert!(templates
+        .iter()
+        .map(|t| *t.try_get().unwrap())
+        .eq([10, 2, 3, 7]));
+}
+
+#[test]
+fn test_template_lazy_expansion() {
+    #[derive(Deserialize)]
+    struct Test {
+        used: EnvTemplate<String>,
+        unused: EnvTemplate<u16>,
+    }
+
+    env::set_var("USED_test_lazy", "value");
+    env::remove_var("UNUSED_test_lazy");
+    let de: Test = toml::from_str(
+        r#"
+            used = "$USED_test_lazy"
+            unused = "$UNUSED_test_lazy"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(de.used.try_get().unwrap(), "value");
+    assert!(de.unused.try_get().is_err());
+
+    env::set_var("UNUSED_test_lazy", "8080");
+    assert_eq!(*de.unused.try_get().unwrap(), 8080);
+}
+
+#[test]
+fn test_template_getter() {
+    #[derive(Deserialize)]
+    struct Test {
+        templated: EnvTemplate<u16>,
+        plain: EnvTemplate<u16>,
+    }
+
+    env::set_var("PORT_test_template_getter", "8080");
+    let mut de: Test = toml::from_str(
+        r#"
+            templated = "$PORT_test_template_getter"
+            plain = 80
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(de.templated.template(), Some("$PORT_test_template_getter"));
+    assert_eq!(de.plain.template(), None);
+
+    // The template is kept after the value is resolved
+    assert_eq!(*de.templated.try_get().unwrap(), 8080);
+    de.templated.reexpand().unwrap();
+    assert_eq!(de.templated.template(), Some("$PORT_test_template_getter"));
+}
+
+#[test]
+fn test_template_other_variants() {
+    #[derive(Deserialize)]
+    struct Test {
+        cache_size: EnvTemplate<u64, UseByteSize>,
+        verbose: EnvTemplate<bool, UseBoolish>,
+        max_size: EnvTemplate<u64, UseGrouped>,
+        level: EnvTemplate<String, UseCaseTransforms>,
+        pattern: EnvTemplate<String, NoExpand>,
+    }
+
+    env::set_var("LEVEL_test_template_variants", "debug");
+    let mut de: Test = toml::from_str(
+        r#"
+            cache_size = "${CACHE_SIZE_test_template_variants:-1KiB}"
+            verbose = "${VERBOSE_test_template_variants:-on}"
+            max_size = "${MAX_SIZE_test_template_variants:-1_000}"
+            level = "${LEVEL_test_template_variants^^}"
+            pattern = "$HOME/*.log"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(*de.cache_size.try_get().unwrap(), 1024);
+    assert!(*de.verbose.try_get().unwrap());
+    assert_eq!(*de.max_size.try_get().unwrap(), 1000);
+    assert_eq!(de.level.try_get().unwrap(), "DEBUG");
+    assert_eq!(de.pattern.try_get().unwrap(), "$HOME/*.log");
+
+    env::set_var("CACHE_SIZE_test_template_variants", "2MB");
+    de.cache_size.reexpand().unwrap();
+    assert_eq!(*de.cache_size.try_get().unwrap(), 2_000_000);
+}
@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use serde_env_field::env_field_wrap;
+
+fn default_host() -> &'static str {
+    "${HOST:-localhost}"
+}
+
+#[env_field_wrap]
+#[derive(Deserialize)]
+struct Test {
+    #[env_field_wrap(env = "HOST", default_expand = "default_host")]
+    host: String,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use serde::Deserialize;
+use serde_env_field::{serde_env_field, StaticTemplate};
+
+const DEFAULT_HOST: StaticTemplate<String> = StaticTemplate::new("${HOST:-localhost}");
+
+fn default_host() -> &'static str {
+    "${HOST:-localhost}"
+}
+
+#[serde_env_field]
+#[derive(Deserialize)]
+struct Test {
+    #[serde_env_field(default = DEFAULT_HOST, default_expand = "default_host")]
+    host: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use serde_env_field::env_field_wrap;
+
+#[env_field_wrap]
+#[derive(Deserialize)]
+struct Test {
+    #[env_field_wrap(expand_keys)]
+    names: Vec<String>,
+}
+
+fn main() {}
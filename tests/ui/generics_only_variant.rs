@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use serde_env_field::env_field_wrap;
+
+#[env_field_wrap]
+#[derive(Deserialize)]
+enum Test {
+    #[env_field_wrap(generics_only)]
+    Variant(Option<String>),
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use serde_env_field::env_field_wrap;
+
+#[derive(Deserialize)]
+struct Inner {
+    value: u32,
+}
+
+#[env_field_wrap]
+#[derive(Deserialize)]
+struct Test {
+    name: String,
+    inner: Inner,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use serde::Deserialize;
+use serde_env_field::serde_env_field;
+
+#[serde_env_field]
+#[derive(Deserialize)]
+enum Test {
+    Database {
+        #[serde_env_field(rename_env = "LEGACY_DB_URL")]
+        url: String,
+    },
+}
+
+fn main() {}
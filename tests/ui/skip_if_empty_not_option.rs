@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use serde_env_field::serde_env_field;
+
+#[serde_env_field]
+#[derive(Deserialize)]
+struct Test {
+    #[serde_env_field(skip_if_empty)]
+    name: String,
+}
+
+fn main() {}
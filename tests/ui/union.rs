@@ -0,0 +1,9 @@
+use serde_env_field::env_field_wrap;
+
+#[env_field_wrap]
+union Test {
+    num: u32,
+    float: f32,
+}
+
+fn main() {}
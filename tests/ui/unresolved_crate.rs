@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use serde_env_field::env_field_wrap;
+
+#[env_field_wrap(crate = "missing_reexport")]
+#[derive(Deserialize)]
+struct Test {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,64 @@
+#![cfg(feature = "uuid")]
+
+use std::env;
+
+use serde::Deserialize;
+use serde_env_field::EnvField;
+use uuid::Uuid;
+
+const ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+#[derive(Deserialize, Debug)]
+struct Test {
+    id: EnvField<Uuid>,
+}
+
+fn id(source: &str) -> Result<Uuid, toml::de::Error> {
+    toml::from_str::<Test>(source).map(|de| de.id.into_inner())
+}
+
+#[test]
+fn test_uuid() {
+    let expected = Uuid::parse_str(ID).unwrap();
+
+    assert_eq!(id(&format!(r#"id = "{ID}""#)).unwrap(), expected);
+    assert_eq!(
+        id(r#"id = "{67e55044-10b1-426f-9247-bb680e5fe0c8}""#).unwrap(),
+        expected
+    );
+
+    env::set_var("ID_test_uuid", ID);
+    assert_eq!(id(r#"id = "$ID_test_uuid""#).unwrap(), expected);
+    assert_eq!(
+        id(r#"id = "${UNSET_test_uuid:-67e55044-10b1-426f-9247-bb680e5fe0c8}""#).unwrap(),
+        expected
+    );
+
+    env::set_var("PREFIX_test_uuid", "67e55044");
+    assert_eq!(
+        id(r#"id = "${PREFIX_test_uuid}-10b1-426f-9247-bb680e5fe0c8""#).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_uuid_errors() {
+    env::set_var("ID_test_uuid_errors", "67e55044-10b1-426f-9247");
+    let err = id(r#"id = "$ID_test_uuid_errors""#).unwrap_err();
+    assert!(
+        err.message()
+            .contains("invalid value '67e55044-10b1-426f-9247'"),
+        "{err}"
+    );
+
+    let err = id(r#"id = "not-a-uuid""#).unwrap_err();
+    assert!(
+        err.message().contains("invalid value 'not-a-uuid'"),
+        "{err}"
+    );
+
+    let err = id(r#"id = "$UNSET_test_uuid_errors""#).unwrap_err();
+    assert!(err.message().contains("UNSET_test_uuid_errors"), "{err}");
+
+    assert!(id("id = 42").is_err());
+}
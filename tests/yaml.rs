@@ -0,0 +1,255 @@
+use std::{collections::BTreeMap, env};
+
+use indoc::indoc;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_env_field::{EnvField, UseDeserialize};
+
+fn de_se_de_test<T: Serialize + DeserializeOwned>(
+    source_text: &'static str,
+    check_value: impl Fn(&T),
+    expected_serialized: &'static str,
+) {
+    let deserialized: T = serde_yaml::from_str(source_text).unwrap();
+    check_value(&deserialized);
+
+    let serialized = serde_yaml::to_string(&deserialized).unwrap();
+    assert_eq!(serialized, expected_serialized);
+
+    let deserialized_again: T = serde_yaml::from_str(&serialized).unwrap();
+    check_value(&deserialized_again);
+}
+
+#[test]
+fn test_yaml_required_fields() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        name: EnvField<String>,
+        size: EnvField<usize>,
+    }
+
+    de_se_de_test::<Test>(
+        indoc! {r#"
+            name: ${NAME_test_yaml_required:-Default Entry}
+            size: "${SIZE_test_yaml_required:-0}"
+        "#},
+        |de| {
+            assert_eq!(&de.name, "Default Entry");
+            assert_eq!(de.size, 0);
+        },
+        indoc! {r#"
+            name: Default Entry
+            size: 0
+        "#},
+    );
+
+    env::set_var("NAME_test_yaml_required", "Example Name");
+    env::set_var("SIZE_test_yaml_required", "1023");
+    de_se_de_test::<Test>(
+        indoc! {r#"
+            name: $NAME_test_yaml_required
+            size: $SIZE_test_yaml_required
+        "#},
+        |de| {
+            assert_eq!(&de.name, "Example Name");
+            assert_eq!(de.size, 1023);
+        },
+        indoc! {r#"
+            name: Example Name
+            size: 1023
+        "#},
+    );
+}
+
+#[test]
+fn test_yaml_unquoted_scalars() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        name: EnvField<String>,
+        version: EnvField<String>,
+        flag: EnvField<String>,
+        size: EnvField<u32>,
+        enabled: EnvField<bool>,
+    }
+
+    // The plain scalars resolved to numbers and booleans are accepted by the strings
+    de_se_de_test::<Test>(
+        indoc! {r#"
+            name: 42
+            version: 1.5
+            flag: true
+            size: "17"
+            enabled: "false"
+        "#},
+        |de| {
+            assert_eq!(&de.name, "42");
+            assert_eq!(&de.version, "1.5");
+            assert_eq!(&de.flag, "true");
+            assert_eq!(de.size, 17);
+            assert!(!*de.enabled);
+        },
+        indoc! {r#"
+            name: '42'
+            version: '1.5'
+            flag: 'true'
+            size: 17
+            enabled: false
+        "#},
+    );
+}
+
+#[test]
+fn test_yaml_optional_fields() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<EnvField<String>>,
+        size: Option<EnvField<usize>>,
+    }
+
+    de_se_de_test::<Test>(
+        indoc! {r#"
+            name: null
+            size: ~
+        "#},
+        |de| {
+            assert!(de.name.is_none());
+            assert!(de.size.is_none());
+        },
+        indoc! {r#"
+            size: null
+        "#},
+    );
+
+    env::set_var("NAME_test_yaml_optional", "Name from Env");
+    de_se_de_test::<Test>(
+        indoc! {r#"
+            name: $NAME_test_yaml_optional
+            size: "${SIZE_test_yaml_optional:-12}"
+        "#},
+        |de| {
+            assert_eq!(de.name.as_ref().unwrap(), "Name from Env");
+            assert_eq!(de.size.unwrap(), 12);
+        },
+        indoc! {r#"
+            name: Name from Env
+            size: 12
+        "#},
+    );
+}
+
+#[test]
+fn test_yaml_seq_fields() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        numbers: Vec<EnvField<i32>>,
+        strings: Vec<EnvField<String>>,
+    }
+
+    env::set_var("NUMBER_test_yaml_seq", "-1024");
+    env::set_var("STRING_test_yaml_seq", "Str from Env");
+    de_se_de_test::<Test>(
+        indoc! {r#"
+            numbers:
+              - 42
+              - $NUMBER_test_yaml_seq
+              - ${NUMBER2_test_yaml_seq:-48}
+            strings: [ONE, $STRING_test_yaml_seq]
+        "#},
+        |de| {
+            assert!(de.numbers.iter().eq([42, -1024, 48].iter()));
+            assert!(de
+                .strings
+                .iter()
+                .map(|e| e.as_str())
+                .eq(["ONE", "Str from Env"]));
+        },
+        indoc! {r#"
+            numbers:
+            - 42
+            - -1024
+            - 48
+            strings:
+            - ONE
+            - Str from Env
+        "#},
+    );
+}
+
+#[test]
+fn test_yaml_map_fields() {
+    #[derive(Serialize, Deserialize)]
+    struct Test {
+        values: BTreeMap<String, EnvField<u16>>,
+        inner: EnvField<Inner, UseDeserialize>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Inner {
+        host: String,
+        port: u16,
+    }
+
+    env::set_var("PORT_test_yaml_map", "8080");
+    env::set_var("HOST_test_yaml_map", "localhost");
+    de_se_de_test::<Test>(
+        indoc! {r#"
+            values:
+              http: $PORT_test_yaml_map
+              https: 443
+            inner:
+              host: $HOST_test_yaml_map
+              port: 80
+        "#},
+        |de| {
+            assert_eq!(de.values["http"], 8080);
+            assert_eq!(de.values["https"], 443);
+            assert_eq!(de.inner.host, "localhost");
+            assert_eq!(de.inner.port, 80);
+        },
+        indoc! {r#"
+            values:
+              http: 8080
+              https: 443
+            inner:
+              host: localhost
+              port: 80
+        "#},
+    );
+}
+
+#[test]
+fn test_yaml_tagged_values() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Mode {
+        Plain,
+        Tls { cert: String },
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Test {
+        mode: EnvField<Mode, UseDeserialize>,
+        fallback: EnvField<Mode, UseDeserialize>,
+    }
+
+    env::set_var("CERT_test_yaml_tagged", "/etc/cert.pem");
+    let de: Test = serde_yaml::from_str(indoc! {r#"
+        mode: !Tls
+          cert: $CERT_test_yaml_tagged
+        fallback: !Plain
+    "#})
+    .unwrap();
+
+    assert_eq!(
+        *de.mode,
+        Mode::Tls {
+            cert: "/etc/cert.pem".into()
+        }
+    );
+    assert_eq!(*de.fallback, Mode::Plain);
+
+    // The tagged values are handed over to the `T`, which reports what it expects
+    let err = serde_yaml::from_str::<EnvField<String>>("!Tag value").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("invalid type: enum, expected a string"));
+}